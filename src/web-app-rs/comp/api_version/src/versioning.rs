@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use url::Url;
 
 struct VersionInt {
@@ -8,7 +10,6 @@ struct VersionInt {
 const API_VERSION_PARAMETER: &str = "api-version";
 
 pub struct QueryStringApiVersion {
-    #[allow(dead_code)]
     version: VersionInt,
     str_value: String,
 }
@@ -18,6 +19,10 @@ impl QueryStringApiVersion {
         url.query_pairs_mut().append_pair(API_VERSION_PARAMETER, &self.str_value);
     }
 
+    pub fn major_version(&self) -> usize {
+        self.version.major_version
+    }
+
     fn new(v: VersionInt) -> Self {
         Self {
             str_value: QueryStringApiVersion::string_val(&v),
@@ -52,6 +57,19 @@ impl From<(usize, usize)> for QueryStringApiVersion {
     }
 }
 
+impl FromStr for QueryStringApiVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |v: &str| v.trim().parse::<usize>().map_err(|e| anyhow::anyhow!("invalid api version '{s}': {e}"));
+
+        match s.split_once('.') {
+            Some((major_version, minor_version)) => Ok(QueryStringApiVersion::from((parse(major_version)?, parse(minor_version)?))),
+            None => Ok(QueryStringApiVersion::from(parse(s)?)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -68,4 +86,17 @@ mod test {
         v12.append_to_url(&mut url);
         assert_eq!(url.as_str(), "https://api.spotify.com/v1/search?api-version=1.2");
     }
+
+    #[test]
+    fn test_version_from_str() {
+        let mut url = Url::parse("https://api.spotify.com/v1/search").unwrap();
+        let v20 = "2.0".parse::<QueryStringApiVersion>().unwrap();
+        v20.append_to_url(&mut url);
+        assert_eq!(url.as_str(), "https://api.spotify.com/v1/search?api-version=2.0");
+        assert_eq!(v20.major_version(), 2);
+
+        assert_eq!("1".parse::<QueryStringApiVersion>().unwrap().major_version(), 1);
+        assert!("v2".parse::<QueryStringApiVersion>().is_err());
+        assert!("2.x".parse::<QueryStringApiVersion>().is_err());
+    }
 }
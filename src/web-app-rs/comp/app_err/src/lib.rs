@@ -11,6 +11,12 @@ pub enum AppError {
     ServerFnError(ServerFnErrorErr),
     #[error("Unauthorized")]
     Unauthorized,
+    #[error("Forbidden")]
+    Forbidden,
+    #[error("Timeout {0}")]
+    Timeout(String),
+    #[error("ServiceUnavailable {0}")]
+    ServiceUnavailable(String),
     #[error("RabbitMqBusError {0}")]
     RabbitMqBusError(String),
     #[error("Other {0}")]
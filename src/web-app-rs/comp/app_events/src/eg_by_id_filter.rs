@@ -20,6 +20,8 @@ pub trait BuyerIdentity {
 pub struct EvRedirect {
     pub order_id: i32,
     pub order_status: String,
+    /// When the ordering-api raised the event.
+    pub at: chrono::DateTime<chrono::Utc>,
 }
 
 impl From<&OrderStatusChangedToStockConfirmed> for EvRedirect {
@@ -27,6 +29,7 @@ impl From<&OrderStatusChangedToStockConfirmed> for EvRedirect {
         EvRedirect {
             order_id: value.order_id,
             order_status: value.order_status.clone(),
+            at: value.base.creation_date,
         }
     }
 }
@@ -42,6 +45,7 @@ impl From<&OrderStatusChangedToAwaitingValidation> for EvRedirect {
         EvRedirect {
             order_id: value.order_id,
             order_status: value.order_status.clone(),
+            at: value.base.creation_date,
         }
     }
 }
@@ -57,6 +61,7 @@ impl From<&OrderStatusChangedToCancelled> for EvRedirect {
         EvRedirect {
             order_id: value.order_id,
             order_status: value.order_status.clone(),
+            at: value.base.creation_date,
         }
     }
 }
@@ -72,6 +77,7 @@ impl From<&OrderStatusChangedToPaid> for EvRedirect {
         EvRedirect {
             order_id: value.order_id,
             order_status: value.order_status.clone(),
+            at: value.base.creation_date,
         }
     }
 }
@@ -87,6 +93,7 @@ impl From<&OrderStatusChangedToShipped> for EvRedirect {
         EvRedirect {
             order_id: value.order_id,
             order_status: value.order_status.clone(),
+            at: value.base.creation_date,
         }
     }
 }
@@ -102,6 +109,7 @@ impl From<&OrderStatusChangedToSubmitted> for EvRedirect {
         EvRedirect {
             order_id: value.order_id,
             order_status: value.order_status.clone(),
+            at: value.base.creation_date,
         }
     }
 }
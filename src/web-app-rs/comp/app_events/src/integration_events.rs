@@ -19,5 +19,8 @@ pub use order_status_changed_to_shipped::*;
 mod order_status_changed_to_submitted;
 pub use order_status_changed_to_submitted::*;
 
+mod product_price_changed;
+pub use product_price_changed::*;
+
 mod register;
 pub use register::*;
@@ -0,0 +1,83 @@
+use rabbit_mq_bus::{Content, Dispatcherable, FromContent, Keyed};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use rabbit_mq_bus::ebus;
+
+use crate::integration_events::IntegrationEvent;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ProductPriceChanged {
+    #[serde(flatten)]
+    pub base: IntegrationEvent,
+    pub product_id: i32,
+    pub new_price: Decimal,
+    pub old_price: Decimal,
+}
+
+impl Default for ProductPriceChanged {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProductPriceChanged {
+    pub fn new() -> ProductPriceChanged {
+        ProductPriceChanged {
+            base: IntegrationEvent::new(),
+            product_id: 0,
+            new_price: Decimal::ZERO,
+            old_price: Decimal::ZERO,
+        }
+    }
+}
+impl Keyed for ProductPriceChanged {
+    fn key() -> &'static str {
+        "ProductPriceChangedIntegrationEvent"
+    }
+}
+
+impl FromContent for ProductPriceChanged {
+    fn from_content(data: Vec<u8>) -> Result<Self, ebus::lib_err::AppError> {
+        let event = serde_json::from_slice(&data)?;
+        Ok(event)
+    }
+}
+
+impl Content for ProductPriceChanged {
+    fn content(&self) -> Result<(&str, Vec<u8>), ebus::lib_err::AppError> {
+        let json = serde_json::to_vec(&self)?;
+        Ok((ProductPriceChanged::key(), json))
+    }
+}
+
+impl Dispatcherable<ProductPriceChanged> for ProductPriceChanged {
+    fn dispatcher() -> Arc<RwLock<Dispatcher<ProductPriceChanged>>> {
+        get_dispatcher().clone()
+    }
+}
+
+use rabbit_mq_bus::Dispatcher;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::RwLock;
+static DISPATCHER: OnceLock<Arc<RwLock<Dispatcher<ProductPriceChanged>>>> = OnceLock::new();
+
+fn get_dispatcher() -> &'static Arc<RwLock<Dispatcher<ProductPriceChanged>>> {
+    DISPATCHER.get_or_init(|| Arc::new(RwLock::new(Dispatcher::new())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_content() {
+        let content = r#"{"ProductId":42,"NewPrice":12.50,"OldPrice":10,"Id":"c8168f83-42d2-483c-b217-01f7eb87ccfb","CreationDate":"2025-08-09T20:51:51.3865279Z"}"#;
+
+        let event = ProductPriceChanged::from_content(content.as_bytes().to_vec()).unwrap();
+        assert_eq!(event.product_id, 42);
+        assert_eq!(event.new_price, Decimal::new(1250, 2));
+        assert_eq!(event.old_price, Decimal::new(10, 0));
+    }
+}
@@ -8,6 +8,7 @@ use crate::integration_events::OrderStatusChangedToPaid;
 use crate::integration_events::OrderStatusChangedToShipped;
 use crate::integration_events::OrderStatusChangedToStockConfirmed;
 use crate::integration_events::OrderStatusChangedToSubmitted;
+use crate::integration_events::ProductPriceChanged;
 
 pub fn register(processor: &mut Arc<ContentProcessor>) {
     let processor = Arc::get_mut(processor).unwrap();
@@ -17,4 +18,5 @@ pub fn register(processor: &mut Arc<ContentProcessor>) {
     processor.register::<OrderStatusChangedToPaid>();
     processor.register::<OrderStatusChangedToShipped>();
     processor.register::<OrderStatusChangedToSubmitted>();
+    processor.register::<ProductPriceChanged>();
 }
@@ -10,6 +10,10 @@ impl UserInfoCntxt {
     pub fn is_logged_in(&self) -> bool {
         self.0.get_untracked().is_some()
     }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.0.get_untracked().is_some_and(|user_info| user_info.has_role(role))
+    }
 }
 
 pub fn init() -> Effect<LocalStorage> {
@@ -54,3 +58,8 @@ pub fn is_logged_in() -> bool {
     let user_info_context = expect_context::<UserInfoCntxt>();
     user_info_context.is_logged_in()
 }
+
+pub fn has_role(role: &str) -> bool {
+    let user_info_context = expect_context::<UserInfoCntxt>();
+    user_info_context.has_role(role)
+}
@@ -25,6 +25,11 @@ mod authorised;
 #[cfg(feature = "ssr")]
 pub use authorised::RequireAuth;
 
+#[cfg(feature = "ssr")]
+mod role_authorised;
+#[cfg(feature = "ssr")]
+pub use role_authorised::RequireRole;
+
 #[cfg(feature = "ssr")]
 pub mod server;
 
@@ -47,6 +52,8 @@ pub const NEXT_URL_KEY: &str = "auth.next-url";
 pub const CSRF_STATE_KEY: &str = "oauth.csrf-state";
 pub const NONCE_STATE_KEY: &str = "oauth.nonce";
 
+pub const ADMIN_ROLE: &str = "admin";
+
 #[cfg(feature = "ssr")]
 fn internal_server_error() -> crate::AppError {
     crate::AppError::ServerFnError(ServerFnErrorErr::ServerError("internal server error".to_string()))
@@ -56,6 +56,19 @@ pub async fn handle_auth_redirect(provided_csrf: String, code: String) -> Result
         internal_server_error()
     })?;
 
+    // the rest of this request sees the signed in user, e.g. the services called by the login hooks
+    if let Some(mut parts) = use_context::<axum::http::request::Parts>() {
+        parts.extensions.insert(auth_session.clone());
+        provide_context(parts);
+    }
+    if let Some(login_hooks) = use_context::<crate::service::LoginHooksContext>() {
+        for hook in login_hooks.hooks {
+            if let Err(e) = hook.after_login().await {
+                leptos::logging::error!("login hook failed {:?} ", e);
+            }
+        }
+    }
+
     let next = if let Ok(Some(next)) = session.remove::<String>(NEXT_URL_KEY).await {
         match next.as_str() {
             "" => "/".to_string(),
@@ -68,5 +81,12 @@ pub async fn handle_auth_redirect(provided_csrf: String, code: String) -> Result
     //println!("sfn next {:?} ", next);
     // log!("end   handle_auth_redirect sfn next {:?} ", next);
 
-    Ok((UserInfo { name: user.username, email: user.email }, next))
+    Ok((
+        UserInfo {
+            roles: user.roles(),
+            name: user.username,
+            email: user.email,
+        },
+        next,
+    ))
 }
@@ -0,0 +1,87 @@
+use axum::{body::Body, http, response::Response};
+use http::Request;
+use leptos::server_fn::response::Res;
+use pin_project_lite::pin_project;
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+/// Like [`crate::RequireAuth`], but the logged in user must also have the given role.
+pub struct RequireRole(pub &'static str);
+
+impl<S> Layer<S> for RequireRole {
+    type Service = RequireRoleService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequireRoleService { inner, role: self.0 }
+    }
+}
+
+pub struct RequireRoleService<T> {
+    inner: T,
+    role: &'static str,
+}
+
+impl<T> Service<Request<Body>> for RequireRoleService<T>
+where
+    T: Service<Request<Body>, Response = Response<Body>> + Send + 'static,
+    T::Future: Send + 'static,
+{
+    type Response = T::Response;
+    type Error = T::Error;
+    type Future = RoleAuthorisedServiceFuture<T>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let is_authenticated = crate::server::is_authenticated_from_extensions(req.extensions());
+        let has_role = crate::server::has_role_from_extensions(req.extensions(), self.role);
+
+        let err = match (is_authenticated, has_role) {
+            (Ok(true), Ok(true)) => None,
+            (Ok(false), _) => Some(app_err::AppError::Unauthorized),
+            (Ok(true), Ok(false)) => Some(app_err::AppError::Forbidden),
+            (Err(e), _) | (_, Err(e)) => Some(app_err::AppError::ServerFnError(e)),
+        };
+
+        let err_response = err.map(|err| {
+            let path = req.uri().path().to_string();
+            let data = serde_json::to_string(&err).unwrap();
+            http::Response::<axum::body::Body>::error_response(&path, data.into())
+        });
+
+        RoleAuthorisedServiceFuture { inner: self.inner.call(req), err_response }
+    }
+}
+
+pin_project! {
+    pub struct RoleAuthorisedServiceFuture<S>
+    where
+        S: Service<Request<Body>, Response = Response<Body>>,
+    {
+        #[pin]
+        inner: S::Future,
+        err_response: Option<Response<Body>>,
+    }
+}
+
+impl<S> Future for RoleAuthorisedServiceFuture<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if let Some(err_response) = this.err_response.take() {
+            return Poll::Ready(Ok(err_response));
+        }
+
+        this.inner.poll(cx)
+    }
+}
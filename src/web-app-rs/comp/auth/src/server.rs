@@ -62,6 +62,15 @@ pub fn is_authenticated_from_extensions(extensions: &Extensions) -> Result<bool,
     }
 }
 
+pub fn has_role_from_extensions(extensions: &Extensions, role: &str) -> Result<bool, ServerFnErrorErr> {
+    let auth_session = if let Some(auth_session) = extensions.get::<users::AuthSession>() {
+        auth_session
+    } else {
+        return Ok(false);
+    };
+    Ok(auth_session.user.as_ref().is_some_and(|user| user.has_role(role)))
+}
+
 struct AuthServiceApi {}
 
 impl AuthServiceApi {
@@ -83,6 +92,7 @@ impl AuthService for AuthServiceApi {
             return Ok(Some(UserInfo {
                 name: _user.username.clone(),
                 email: _user.email.clone(),
+                roles: _user.roles(),
             }));
         } else {
             return Ok(None);
@@ -24,3 +24,15 @@ pub trait AuthService: Send + Sync {
 pub struct AuthServiceContext {
     pub service: Arc<dyn AuthService>,
 }
+
+/// Runs in the request of the OIDC callback right after the sign-in, the request already carries the signed in user.
+#[async_trait]
+pub trait LoginHook: Send + Sync {
+    async fn after_login(&self) -> Result<(), crate::AppError>;
+}
+
+/// Optional context, the hooks run in order and their errors do not fail the sign-in.
+#[derive(Clone, Default)]
+pub struct LoginHooksContext {
+    pub hooks: Vec<Arc<dyn LoginHook>>,
+}
@@ -4,4 +4,12 @@ use super::*;
 pub struct UserInfo {
     pub name: String,
     pub email: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+impl UserInfo {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
 }
@@ -17,9 +17,27 @@ struct AppClaims {
     address_state: Option<String>,
     address_zip_code: Option<String>,
     address_country: Option<String>,
+    role: Option<RoleClaim>,
 }
 impl AdditionalClaims for AppClaims {}
 
+// The identity server sends a single role as a string and several roles as an array.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum RoleClaim {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl RoleClaim {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            RoleClaim::One(role) => vec![role],
+            RoleClaim::Many(roles) => roles,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
     id: i64,
@@ -34,6 +52,17 @@ pub struct User {
     pub state: Option<String>,
     pub country: Option<String>,
     pub zip: Option<String>,
+    pub roles: Option<String>,
+}
+
+impl User {
+    pub fn roles(&self) -> Vec<String> {
+        self.roles.as_deref().map(|roles| roles.split(',').filter(|r| !r.is_empty()).map(|r| r.to_string()).collect()).unwrap_or_default()
+    }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles().iter().any(|r| r == role)
+    }
 }
 
 // Here we've implemented `Debug` manually to avoid accidentally logging the
@@ -51,6 +80,7 @@ impl std::fmt::Debug for User {
             .field("state", &self.state)
             .field("country", &self.country)
             .field("zip", &self.zip)
+            .field("roles", &self.roles)
             .finish()
     }
 }
@@ -115,11 +145,18 @@ pub struct Backend {
     db: SqlitePool,
     client: OCClient,
     http_client: reqwest::Client,
+    admin_users: Vec<String>,
 }
 
 impl Backend {
     pub fn new(db: SqlitePool, client: OCClient, http_client: reqwest::Client) -> Self {
-        Self { db, client, http_client }
+        Self { db, client, http_client, admin_users: vec![] }
+    }
+
+    /// Users (by name) that get the admin role regardless of the role claims sent by the identity server.
+    pub fn with_admin_users(mut self, admin_users: Vec<String>) -> Self {
+        self.admin_users = admin_users;
+        self
     }
 
     pub fn authorize_url_with_scopes(&self, vec: Vec<&str>) -> (Url, CsrfToken, Nonce) {
@@ -185,15 +222,22 @@ impl AuthnBackend for Backend {
             address_state,
             address_zip_code,
             address_country,
+            role,
         } = userinfo_claims.additional_claims();
 
+        let mut roles = role.clone().map(RoleClaim::into_vec).unwrap_or_default();
+        if self.admin_users.contains(&name) && !roles.iter().any(|r| r == crate::ADMIN_ROLE) {
+            roles.push(crate::ADMIN_ROLE.to_string());
+        }
+        let roles = roles.join(",");
+
         //println!(" returned user info: {:?}, {:?}, {:?}, {:?}, {:?}", address_street, address_city, address_state, address_zip_code, address_country);
 
         // Persist user in our database so we can use `get_user`.
         let user = sqlx::query_as(
             r#"
-            insert into users (sub, username, email, access_token, refresh_token, street, city, state, country, zip)
-            values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            insert into users (sub, username, email, access_token, refresh_token, street, city, state, country, zip, roles)
+            values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             on conflict(username) do update
             set
             sub = excluded.sub,
@@ -204,7 +248,8 @@ impl AuthnBackend for Backend {
             city = excluded.city ,
             state =excluded.state,
             country = excluded.country ,
-            zip = excluded.zip
+            zip = excluded.zip,
+            roles = excluded.roles
             returning *
             "#,
         )
@@ -218,6 +263,7 @@ impl AuthnBackend for Backend {
         .bind(address_state)
         .bind(address_zip_code)
         .bind(address_country)
+        .bind(roles)
         .fetch_one(&self.db)
         .await
         .map_err(Self::Error::Sqlx)?;
@@ -1,10 +1,20 @@
+/// The proto of the basket-api service itself, the web app never keeps a copy of its own.
+#[cfg(feature = "ssr")]
+const BASKET_PROTO: &str = "../../../../Basket.API/Proto/basket.proto";
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(feature = "ssr")]
-    unsafe {
-        std::env::set_var("PROTOC", "C:/soft/protoc-win64/bin/protoc.exe")
-    };
+    {
+        // a protoc from the environment wins, otherwise the one shipped with protoc-bin-vendored
+        if std::env::var_os("PROTOC").is_none() {
+            unsafe { std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?) };
+        }
 
-    #[cfg(feature = "ssr")]
-    tonic_prost_build::compile_protos("./api/protos/basket.proto")?;
+        let proto_dir = std::path::Path::new(BASKET_PROTO).parent().ok_or("no proto dir")?;
+        tonic_prost_build::configure()
+            .build_client(true)
+            .build_server(cfg!(feature = "grpc-server"))
+            .compile_protos(&[std::path::Path::new(BASKET_PROTO)], &[proto_dir])?;
+    }
     Ok(())
 }
@@ -0,0 +1,6 @@
+pub mod server_api;
+pub mod service;
+pub mod types;
+
+#[cfg(feature = "ssr")]
+pub mod server;
@@ -0,0 +1,263 @@
+//! Address book kept in the `addresses` table of the web app database, next to `users`.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use auth::service::AuthServiceContext;
+use axum::http::Extensions;
+use leptos_axum::extract;
+use sqlx::SqlitePool;
+
+use crate::addresses::service::{AddressService, AddressServiceContext};
+use crate::addresses::types::{SavedAddress, ShippingAddress};
+
+struct AddressServiceApi {
+    db: SqlitePool,
+    auth_service: AuthServiceContext,
+}
+
+type AddressRow = (i64, String, String, String, String, String);
+
+fn sqlx_err(e: sqlx::Error) -> crate::AppError {
+    crate::AppError::Other(format!("address storage error {e}"))
+}
+
+async fn user_sub() -> Result<String, crate::AppError> {
+    let extensions: Extensions = extract().await?;
+    Ok(auth::server::get_user_ref_from_extensions(&extensions)?.sub.clone())
+}
+
+fn from_row((id, street, city, state, zip_code, country): AddressRow) -> SavedAddress {
+    SavedAddress {
+        id,
+        address: ShippingAddress { street, city, state, zip_code, country },
+    }
+}
+
+impl AddressServiceApi {
+    /// The first read of a book copies the address claims of the sign-in into it, once per user even if the user deletes
+    /// the address later.
+    async fn seed_from_claims(&self, user_sub: &str) -> Result<(), crate::AppError> {
+        let seeded: Option<(String,)> = sqlx::query_as("select user_sub from address_books_seeded where user_sub = ?").bind(user_sub).fetch_optional(&self.db).await.map_err(sqlx_err)?;
+        if seeded.is_some() {
+            return Ok(());
+        }
+
+        let claims = self.auth_service.service.get_user_address_info().await?;
+        let mut tx = self.db.begin().await.map_err(sqlx_err)?;
+        let marked = sqlx::query("insert into address_books_seeded (user_sub) values (?) on conflict(user_sub) do nothing")
+            .bind(user_sub)
+            .execute(&mut *tx)
+            .await
+            .map_err(sqlx_err)?;
+        if marked.rows_affected() > 0 && claims.street.as_deref().is_some_and(|x| !x.trim().is_empty()) {
+            let field = |x: Option<String>| x.unwrap_or_default().trim().to_string();
+            sqlx::query("insert into addresses (user_sub, street, city, state, zip_code, country, created_at) values (?, ?, ?, ?, ?, ?, ?)")
+                .bind(user_sub)
+                .bind(field(claims.street))
+                .bind(field(claims.city))
+                .bind(field(claims.state))
+                .bind(field(claims.zip))
+                .bind(field(claims.country))
+                .bind(chrono::Utc::now().timestamp())
+                .execute(&mut *tx)
+                .await
+                .map_err(sqlx_err)?;
+        }
+        tx.commit().await.map_err(sqlx_err)
+    }
+
+    async fn addresses_of(&self, user_sub: &str) -> Result<Vec<SavedAddress>, crate::AppError> {
+        self.seed_from_claims(user_sub).await?;
+
+        let rows: Vec<AddressRow> = sqlx::query_as("select id, street, city, state, zip_code, country from addresses where user_sub = ? order by id")
+            .bind(user_sub)
+            .fetch_all(&self.db)
+            .await
+            .map_err(sqlx_err)?;
+        Ok(rows.into_iter().map(from_row).collect())
+    }
+
+    async fn address_of(&self, user_sub: &str, address_id: i64) -> Result<Option<SavedAddress>, crate::AppError> {
+        let row: Option<AddressRow> = sqlx::query_as("select id, street, city, state, zip_code, country from addresses where id = ? and user_sub = ?")
+            .bind(address_id)
+            .bind(user_sub)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(sqlx_err)?;
+        Ok(row.map(from_row))
+    }
+
+    /// The id of an equal address of the book instead of a second copy.
+    async fn add(&self, user_sub: &str, address: ShippingAddress) -> Result<i64, crate::AppError> {
+        let existing: Option<(i64,)> = sqlx::query_as("select id from addresses where user_sub = ? and street = ? and city = ? and state = ? and zip_code = ? and country = ? order by id limit 1")
+            .bind(user_sub)
+            .bind(&address.street)
+            .bind(&address.city)
+            .bind(&address.state)
+            .bind(&address.zip_code)
+            .bind(&address.country)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(sqlx_err)?;
+        if let Some((id,)) = existing {
+            return Ok(id);
+        }
+
+        let rslt = sqlx::query("insert into addresses (user_sub, street, city, state, zip_code, country, created_at) values (?, ?, ?, ?, ?, ?, ?)")
+            .bind(user_sub)
+            .bind(address.street)
+            .bind(address.city)
+            .bind(address.state)
+            .bind(address.zip_code)
+            .bind(address.country)
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.db)
+            .await
+            .map_err(sqlx_err)?;
+        Ok(rslt.last_insert_rowid())
+    }
+
+    async fn update(&self, user_sub: &str, address: SavedAddress) -> Result<bool, crate::AppError> {
+        let SavedAddress { id, address } = address;
+        let rslt = sqlx::query("update addresses set street = ?, city = ?, state = ?, zip_code = ?, country = ? where id = ? and user_sub = ?")
+            .bind(address.street)
+            .bind(address.city)
+            .bind(address.state)
+            .bind(address.zip_code)
+            .bind(address.country)
+            .bind(id)
+            .bind(user_sub)
+            .execute(&self.db)
+            .await
+            .map_err(sqlx_err)?;
+        Ok(rslt.rows_affected() > 0)
+    }
+
+    async fn delete(&self, user_sub: &str, address_id: i64) -> Result<bool, crate::AppError> {
+        let rslt = sqlx::query("delete from addresses where id = ? and user_sub = ?").bind(address_id).bind(user_sub).execute(&self.db).await.map_err(sqlx_err)?;
+        Ok(rslt.rows_affected() > 0)
+    }
+}
+
+#[async_trait]
+impl AddressService for AddressServiceApi {
+    async fn get_addresses(&self) -> Result<Vec<SavedAddress>, crate::AppError> {
+        self.addresses_of(&user_sub().await?).await
+    }
+
+    async fn get_address(&self, address_id: i64) -> Result<Option<SavedAddress>, crate::AppError> {
+        self.address_of(&user_sub().await?, address_id).await
+    }
+
+    async fn add_address(&self, address: ShippingAddress) -> Result<i64, crate::AppError> {
+        self.add(&user_sub().await?, address).await
+    }
+
+    async fn update_address(&self, address: SavedAddress) -> Result<bool, crate::AppError> {
+        self.update(&user_sub().await?, address).await
+    }
+
+    async fn delete_address(&self, address_id: i64) -> Result<bool, crate::AppError> {
+        self.delete(&user_sub().await?, address_id).await
+    }
+}
+
+/// `db` must have the `addresses` and `address_books_seeded` tables of the web app migrations.
+pub fn make_service(db: SqlitePool, auth_service: AuthServiceContext) -> AddressServiceContext {
+    AddressServiceContext {
+        service: Arc::new(AddressServiceApi { db, auth_service }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auth::service::{AuthService, UserAddressInfo};
+    use auth::user::UserInfo;
+
+    /// Every user signs in with the same address claims.
+    struct ClaimsAuthService;
+
+    #[async_trait]
+    impl AuthService for ClaimsAuthService {
+        async fn is_logged_in(&self) -> Result<Option<UserInfo>, crate::AppError> {
+            Ok(None)
+        }
+        async fn get_user_address_info(&self) -> Result<UserAddressInfo, crate::AppError> {
+            Ok(UserAddressInfo {
+                street: Some(" 1 Main St ".to_string()),
+                city: Some("Redmond".to_string()),
+                state: Some("WA".to_string()),
+                country: Some("U.S.".to_string()),
+                zip: Some("98052".to_string()),
+            })
+        }
+        async fn get_buyer_id(&self) -> Result<String, crate::AppError> {
+            Err(crate::AppError::Unauthorized)
+        }
+        async fn get_user_name(&self) -> Result<String, crate::AppError> {
+            Err(crate::AppError::Unauthorized)
+        }
+    }
+
+    async fn make_api() -> AddressServiceApi {
+        AddressServiceApi {
+            db: crate::test_db::migrated_db().await,
+            auth_service: AuthServiceContext { service: Arc::new(ClaimsAuthService) },
+        }
+    }
+
+    fn address(street: &str) -> ShippingAddress {
+        ShippingAddress {
+            street: street.to_string(),
+            city: "Seattle".to_string(),
+            state: "WA".to_string(),
+            zip_code: "98101".to_string(),
+            country: "U.S.".to_string(),
+        }
+    }
+
+    fn streets(addresses: &[SavedAddress]) -> Vec<&str> {
+        addresses.iter().map(|x| x.address.street.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_claims_seed_the_book_once() {
+        let api = make_api().await;
+
+        let addresses = api.addresses_of("alice").await.unwrap();
+        assert_eq!(streets(&addresses), vec!["1 Main St"]);
+        assert_eq!(api.addresses_of("alice").await.unwrap().len(), 1);
+
+        // a deleted seed stays deleted
+        assert!(api.delete("alice", addresses[0].id).await.unwrap());
+        assert!(api.addresses_of("alice").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_add_address_keeps_no_duplicates() {
+        let api = make_api().await;
+
+        let id = api.add("alice", address("2 Pike St")).await.unwrap();
+        assert_eq!(api.add("alice", address("2 Pike St")).await.unwrap(), id);
+        assert_ne!(api.add("alice", address("3 Pine St")).await.unwrap(), id);
+        // the claims come in with the first read
+        assert_eq!(streets(&api.addresses_of("alice").await.unwrap()), vec!["2 Pike St", "3 Pine St", "1 Main St"]);
+
+        // the same address in another book is another address
+        assert_ne!(api.add("bob", address("2 Pike St")).await.unwrap(), id);
+    }
+
+    #[tokio::test]
+    async fn test_addresses_of_another_user() {
+        let api = make_api().await;
+
+        let id = api.add("alice", address("2 Pike St")).await.unwrap();
+
+        assert_eq!(api.address_of("bob", id).await.unwrap(), None);
+        assert!(!api.update("bob", SavedAddress { id, address: address("4 Union St") }).await.unwrap());
+        assert!(!api.delete("bob", id).await.unwrap());
+        assert_eq!(api.address_of("alice", id).await.unwrap().map(|x| x.address), Some(address("2 Pike St")));
+    }
+}
@@ -0,0 +1,49 @@
+use leptos::server;
+
+use super::types::{SavedAddress, ShippingAddress};
+
+#[cfg(feature = "ssr")]
+use super::service::AddressServiceContext;
+
+#[cfg(feature = "ssr")]
+use leptos::prelude::expect_context;
+
+#[server(prefix = "/api_addresses")]
+#[middleware(auth::RequireAuth)]
+pub async fn get_addresses() -> Result<Vec<SavedAddress>, crate::AppError> {
+    let context: AddressServiceContext = expect_context();
+
+    context.service.get_addresses().await
+}
+
+#[server(prefix = "/api_addresses")]
+#[middleware(auth::RequireAuth)]
+pub async fn get_address(address_id: i64) -> Result<Option<SavedAddress>, crate::AppError> {
+    let context: AddressServiceContext = expect_context();
+
+    context.service.get_address(address_id).await
+}
+
+#[server(prefix = "/api_addresses")]
+#[middleware(auth::RequireAuth)]
+pub async fn add_address(address: ShippingAddress) -> Result<i64, crate::AppError> {
+    let context: AddressServiceContext = expect_context();
+
+    context.service.add_address(address).await
+}
+
+#[server(prefix = "/api_addresses")]
+#[middleware(auth::RequireAuth)]
+pub async fn update_address(address: SavedAddress) -> Result<bool, crate::AppError> {
+    let context: AddressServiceContext = expect_context();
+
+    context.service.update_address(address).await
+}
+
+#[server(prefix = "/api_addresses")]
+#[middleware(auth::RequireAuth)]
+pub async fn delete_address(address_id: i64) -> Result<bool, crate::AppError> {
+    let context: AddressServiceContext = expect_context();
+
+    context.service.delete_address(address_id).await
+}
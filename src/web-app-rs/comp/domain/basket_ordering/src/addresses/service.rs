@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::types::{SavedAddress, ShippingAddress};
+
+/// The address book of the signed in user, oldest address first.
+///
+/// The address claims of the identity server seed the first address once, later sign-ins don't touch the book.
+#[async_trait]
+pub trait AddressService: Send + Sync {
+    async fn get_addresses(&self) -> Result<Vec<SavedAddress>, crate::AppError>;
+    async fn get_address(&self, address_id: i64) -> Result<Option<SavedAddress>, crate::AppError>;
+
+    /// An address equal to a saved one is not saved twice, the id of the saved one is returned then.
+    async fn add_address(&self, address: ShippingAddress) -> Result<i64, crate::AppError>;
+    async fn update_address(&self, address: SavedAddress) -> Result<bool, crate::AppError>;
+    async fn delete_address(&self, address_id: i64) -> Result<bool, crate::AppError>;
+}
+
+#[derive(Clone)]
+pub struct AddressServiceContext {
+    pub service: Arc<dyn AddressService>,
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShippingAddress {
+    pub street: String,
+    pub city: String,
+    pub state: String,
+    pub zip_code: String,
+    pub country: String,
+}
+
+impl ShippingAddress {
+    /// One line for pickers and lists.
+    pub fn summary(&self) -> String {
+        [self.street.as_str(), &format!("{} {}", self.zip_code, self.city), self.state.as_str(), self.country.as_str()]
+            .into_iter()
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// An address of the address book of the signed in user.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedAddress {
+    pub id: i64,
+    pub address: ShippingAddress,
+}
@@ -0,0 +1,152 @@
+//! Basket of anonymous users kept in the session, merged into the basket of the user on sign-in.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tower_sessions::Session;
+
+use crate::basket::{
+    service::*,
+    types::{BasketQuantity, MAX_LINES, MAX_QUANTITY},
+};
+use leptos_axum::extract;
+
+const GUEST_BASKET_KEY: &str = "basket.guest";
+
+/// Signed in users get the basket of `inner`, anonymous users the one in their session.
+struct GuestAwareBasketService {
+    inner: Arc<dyn BasketService>,
+}
+
+async fn session() -> Result<Session, crate::AppError> {
+    Ok(extract::<Session>().await?)
+}
+
+fn session_err(e: tower_sessions::session::Error) -> crate::AppError {
+    crate::AppError::Other(format!("session error {e}"))
+}
+
+async fn get_guest_basket(session: &Session) -> Result<Vec<BasketQuantity>, crate::AppError> {
+    Ok(session.get::<Vec<BasketQuantity>>(GUEST_BASKET_KEY).await.map_err(session_err)?.unwrap_or_default())
+}
+
+/// Quantities of the same product are summed up to [`MAX_QUANTITY`], the order of `user` is kept and new products go
+/// last.
+pub fn merge_quantities(user: Vec<BasketQuantity>, guest: Vec<BasketQuantity>) -> Vec<BasketQuantity> {
+    let mut merged = user;
+    for item in guest.into_iter().filter(|x| x.quantity > 0) {
+        match merged.iter_mut().find(|x| x.product_id == item.product_id) {
+            Some(existing) => existing.quantity = existing.quantity.saturating_add(item.quantity).min(MAX_QUANTITY),
+            None => merged.push(BasketQuantity {
+                quantity: item.quantity.min(MAX_QUANTITY),
+                ..item
+            }),
+        }
+    }
+    merged
+}
+
+/// The quantities a guest basket stores: one line per product up to [`MAX_QUANTITY`], at most [`MAX_LINES`] lines.
+fn guest_quantities(items: Vec<BasketQuantity>) -> Result<Vec<BasketQuantity>, crate::AppError> {
+    let items = merge_quantities(Vec::new(), items);
+    if items.len() > MAX_LINES {
+        return Err(crate::AppError::Other(format!("A basket holds at most {MAX_LINES} products")));
+    }
+    Ok(items)
+}
+
+#[async_trait]
+impl BasketService for GuestAwareBasketService {
+    async fn get_basket(&self) -> Result<Vec<BasketQuantity>, crate::AppError> {
+        if auth::server::is_authenticated().await? {
+            return self.inner.get_basket().await;
+        }
+        get_guest_basket(&session().await?).await
+    }
+
+    async fn update_basket(&self, items: Vec<BasketQuantity>) -> Result<(), crate::AppError> {
+        if auth::server::is_authenticated().await? {
+            return self.inner.update_basket(items).await;
+        }
+        let session = session().await?;
+        let items = guest_quantities(items)?;
+        if items.is_empty() {
+            session.remove::<Vec<BasketQuantity>>(GUEST_BASKET_KEY).await.map_err(session_err)?;
+        } else {
+            session.insert(GUEST_BASKET_KEY, items).await.map_err(session_err)?;
+        }
+        Ok(())
+    }
+
+    async fn delete_basket(&self) -> Result<(), crate::AppError> {
+        if auth::server::is_authenticated().await? {
+            return self.inner.delete_basket().await;
+        }
+        session().await?.remove::<Vec<BasketQuantity>>(GUEST_BASKET_KEY).await.map_err(session_err)?;
+        Ok(())
+    }
+}
+
+/// Moves the guest basket of the session into the basket of the user who just signed in.
+struct GuestBasketMerge {
+    inner: Arc<dyn BasketService>,
+}
+
+#[async_trait]
+impl auth::service::LoginHook for GuestBasketMerge {
+    async fn after_login(&self) -> Result<(), crate::AppError> {
+        let session = session().await?;
+        let guest = get_guest_basket(&session).await?;
+        if guest.is_empty() {
+            return Ok(());
+        }
+
+        let user = self.inner.get_basket().await?;
+        self.inner.update_basket(merge_quantities(user, guest)).await?;
+
+        // only dropped once merged, a failed merge leaves the guest basket for the next sign-in
+        session.remove::<Vec<BasketQuantity>>(GUEST_BASKET_KEY).await.map_err(session_err)?;
+        Ok(())
+    }
+}
+
+/// Wraps the basket of signed in users, `inner` is the gRPC basket-api or the mock.
+pub fn make_service(inner: BasketServiceContext) -> BasketServiceContext {
+    BasketServiceContext {
+        service: Arc::new(GuestAwareBasketService { inner: inner.service }),
+    }
+}
+
+/// The login hook merging the guest basket into `inner`, the unwrapped basket of signed in users.
+pub fn make_login_hook(inner: &BasketServiceContext) -> Arc<dyn auth::service::LoginHook> {
+    Arc::new(GuestBasketMerge { inner: inner.service.clone() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q(product_id: i32, quantity: i32) -> BasketQuantity {
+        BasketQuantity { product_id, quantity }
+    }
+
+    #[test]
+    fn test_merge_quantities() {
+        assert_eq!(merge_quantities(vec![q(1, 2), q(2, 1)], vec![q(2, 3), q(3, 1), q(4, 0)]), vec![q(1, 2), q(2, 4), q(3, 1)]);
+        assert_eq!(merge_quantities(vec![], vec![q(5, 1)]), vec![q(5, 1)]);
+    }
+
+    #[test]
+    fn test_merge_quantities_overflow() {
+        assert_eq!(merge_quantities(vec![q(1, i32::MAX)], vec![q(1, i32::MAX), q(2, i32::MAX)]), vec![q(1, MAX_QUANTITY), q(2, MAX_QUANTITY)]);
+        assert_eq!(merge_quantities(vec![q(1, MAX_QUANTITY - 1)], vec![q(1, 2)]), vec![q(1, MAX_QUANTITY)]);
+    }
+
+    #[test]
+    fn test_guest_quantities() {
+        assert_eq!(guest_quantities(vec![q(1, 1000), q(2, 0), q(1, 1), q(3, -4)]), Ok(vec![q(1, MAX_QUANTITY)]));
+
+        let full = (0..MAX_LINES as i32).map(|id| q(id, 1)).collect::<Vec<_>>();
+        assert_eq!(guest_quantities(full.clone()).map(|x| x.len()), Ok(MAX_LINES));
+        assert!(guest_quantities([full, vec![q(-1, 1)]].concat()).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+//! In-memory `BasketService` for the `mock-backends` feature, baskets are keyed by the user `sub`.
+
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tonic::Extensions;
+
+use crate::basket::{service::*, types::BasketQuantity};
+use leptos_axum::extract;
+
+#[derive(Default)]
+pub struct MockBasketService {
+    baskets: Mutex<HashMap<String, Vec<BasketQuantity>>>,
+}
+
+/// The `sub` of the signed in user, the same id the basket-api takes from the access token.
+pub(crate) async fn current_user_sub() -> Result<String, crate::AppError> {
+    let extensions: Extensions = extract().await?;
+    Ok(auth::server::get_user_ref_from_extensions(&extensions)?.sub.clone())
+}
+
+impl MockBasketService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, sub: &str) -> Vec<BasketQuantity> {
+        self.baskets.lock().unwrap_or_else(|e| e.into_inner()).get(sub).cloned().unwrap_or_default()
+    }
+
+    /// Like the basket-api, an update replaces the whole basket.
+    pub fn update(&self, sub: &str, items: Vec<BasketQuantity>) {
+        let items = items.into_iter().filter(|x| x.quantity > 0).collect::<Vec<_>>();
+        let mut baskets = self.baskets.lock().unwrap_or_else(|e| e.into_inner());
+        if items.is_empty() {
+            baskets.remove(sub);
+        } else {
+            baskets.insert(sub.to_string(), items);
+        }
+    }
+
+    pub fn delete(&self, sub: &str) {
+        self.baskets.lock().unwrap_or_else(|e| e.into_inner()).remove(sub);
+    }
+}
+
+#[async_trait]
+impl BasketService for MockBasketService {
+    async fn get_basket(&self) -> Result<Vec<BasketQuantity>, crate::AppError> {
+        Ok(self.get(&current_user_sub().await?))
+    }
+
+    async fn update_basket(&self, items: Vec<BasketQuantity>) -> Result<(), crate::AppError> {
+        self.update(&current_user_sub().await?, items);
+        Ok(())
+    }
+
+    async fn delete_basket(&self) -> Result<(), crate::AppError> {
+        self.delete(&current_user_sub().await?);
+        Ok(())
+    }
+}
+
+pub fn make_service() -> BasketServiceContext {
+    BasketServiceContext { service: Arc::new(MockBasketService::new()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_baskets_are_per_user() {
+        let service = MockBasketService::new();
+        service.update("alice", vec![BasketQuantity { product_id: 1, quantity: 2 }, BasketQuantity { product_id: 2, quantity: 0 }]);
+        service.update("bob", vec![BasketQuantity { product_id: 3, quantity: 1 }]);
+
+        assert_eq!(service.get("alice"), vec![BasketQuantity { product_id: 1, quantity: 2 }]);
+
+        service.delete("alice");
+        assert!(service.get("alice").is_empty());
+        assert_eq!(service.get("bob").len(), 1);
+    }
+}
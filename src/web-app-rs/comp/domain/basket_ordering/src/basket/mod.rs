@@ -6,3 +6,9 @@ pub mod client;
 
 #[cfg(feature = "ssr")]
 pub mod server;
+
+#[cfg(feature = "ssr")]
+pub mod guest;
+
+#[cfg(feature = "mock-backends")]
+pub mod mock;
@@ -1,14 +1,18 @@
 use async_trait::async_trait;
-use std::sync::Arc;
-use tonic::{Extensions, IntoRequest, metadata::MetadataMap, transport::Channel};
+use std::{future::Future, sync::Arc, time::Duration};
+use tonic::{
+    Code, Extensions, Status,
+    metadata::{Ascii, MetadataValue},
+    transport::{Certificate, Channel, ClientTlsConfig, Endpoint},
+};
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 
 use crate::basket::{service::*, types::BasketQuantity};
 use url::Url;
 
-use leptos::prelude::ServerFnErrorErr;
 use leptos_axum::extract;
+use resilient_http::ResilienceConfig;
 
 pub mod basket_grpc {
     tonic::include_proto!("basket_api");
@@ -23,37 +27,72 @@ struct BasketServiceApi {
     client: BasketClientGrpc,
     #[allow(dead_code)]
     base_url: Url,
+    /// `timeout` is the deadline of each call, `retries` and `retry_base` apply to `Unavailable` only.
+    config: ResilienceConfig,
     //api_version: QueryStringApiVersion,
 }
 
 const BASKET_SERVICE_PATH: &str = "api/basket";
 const KEY: &str = "http://basket-api";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 impl BasketServiceApi {
-    pub fn new(client: BasketClientGrpc, base_url: Url) -> Self {
-        BasketServiceApi { client, base_url }
+    pub fn new(client: BasketClientGrpc, base_url: Url, config: ResilienceConfig) -> Self {
+        BasketServiceApi { client, base_url, config }
+    }
+
+    /// Sends `message` with the token of the signed in user and the call deadline. `Unavailable` is retried,
+    /// the basket-api only reads or replaces whole baskets so repeating a call is safe.
+    async fn call<T, R, F, Fut>(&self, message: T, send: F) -> Result<R, crate::AppError>
+    where
+        T: Clone,
+        F: Fn(BasketClientGrpc, tonic::Request<T>) -> Fut,
+        Fut: Future<Output = Result<tonic::Response<R>, Status>>,
+    {
+        let authorization = authorization_delegating().await?;
+
+        let mut attempt = 0;
+        loop {
+            let mut request = tonic::Request::new(message.clone());
+            request.metadata_mut().insert("authorization", authorization.clone());
+            request.set_timeout(self.config.timeout);
+
+            match send(self.client.clone(), request).await {
+                Ok(response) => return Ok(response.into_inner()),
+                Err(status) if status.code() == Code::Unavailable && attempt < self.config.retries => {
+                    let delay = self.config.retry_base.saturating_mul(2u32.saturating_pow(attempt));
+                    leptos::logging::warn!("retry {} of basket-api call in {:?}: {}", attempt + 1, delay, status.message());
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(status) => return Err(status_to_app_error(&status)),
+            }
+        }
     }
 }
 
-async fn authorization_delegating(metadata: &mut MetadataMap) -> Result<(), crate::AppError> {
+async fn authorization_delegating() -> Result<MetadataValue<Ascii>, crate::AppError> {
     let extensions: Extensions = extract().await?;
     let auth_session = extensions.get::<auth::users::AuthSession>().ok_or(crate::AppError::Unauthorized)?;
     let t = auth_session.user.as_ref().ok_or(crate::AppError::Unauthorized)?.access_token.clone();
-    if metadata.append("authorization", format!("Bearer {}", t).parse().unwrap()) {
-        return Err(crate::AppError::Other("Authorization is already  present".to_string()));
+    format!("Bearer {}", t).parse().map_err(|_| crate::AppError::Other("access token is not a valid header value".to_string()))
+}
+
+fn status_to_app_error(status: &Status) -> crate::AppError {
+    match status.code() {
+        Code::Unauthenticated => crate::AppError::Unauthorized,
+        Code::PermissionDenied => crate::AppError::Forbidden,
+        Code::DeadlineExceeded => crate::AppError::Timeout(KEY.to_string()),
+        Code::Unavailable => crate::AppError::ServiceUnavailable(KEY.to_string()),
+        code => crate::AppError::Other(format!("{KEY}: {code}: {}", status.message())),
     }
-    Ok(())
 }
 
 #[async_trait]
 impl BasketService for BasketServiceApi {
     async fn get_basket(&self) -> Result<Vec<BasketQuantity>, crate::AppError> {
-        let mut request = GetBasketRequest {}.into_request();
-        authorization_delegating(&mut request.metadata_mut()).await?;
-        let mut client = self.client.clone();
-        let response = client.get_basket(request).await.map_err(|e| crate::AppError::ServerFnError(ServerFnErrorErr::ServerError(e.to_string())))?;
+        let response = self.call(GetBasketRequest {}, |mut client, request| async move { client.get_basket(request).await }).await?;
         Ok(response
-            .into_inner()
             .items
             .into_iter()
             .map(|item| BasketQuantity {
@@ -71,22 +110,37 @@ impl BasketService for BasketServiceApi {
                 quantity: item.quantity,
             })
             .collect();
-        let mut request = UpdateBasketRequest { items }.into_request();
-        authorization_delegating(&mut request.metadata_mut()).await?;
-        let mut client = self.client.clone();
-        let _response = client.update_basket(request).await.map_err(|e| crate::AppError::ServerFnError(ServerFnErrorErr::ServerError(e.to_string())))?;
+        self.call(UpdateBasketRequest { items }, |mut client, request| async move { client.update_basket(request).await }).await?;
         Ok(())
     }
 
     async fn delete_basket(&self) -> Result<(), crate::AppError> {
-        let mut request = DeleteBasketRequest {}.into_request();
-        authorization_delegating(&mut request.metadata_mut()).await?;
-        let mut client = self.client.clone();
-        let _response = client.delete_basket(request).await.map_err(|e| crate::AppError::ServerFnError(ServerFnErrorErr::ServerError(e.to_string())))?;
+        self.call(DeleteBasketRequest {}, |mut client, request| async move { client.delete_basket(request).await }).await?;
         Ok(())
     }
 }
 
+/// TLS for `https` urls or when `BASKET_API_TLS_CA` names a PEM file of the CA to trust in addition to the
+/// system roots. `BASKET_API_TLS_DOMAIN` overrides the name checked against the certificate.
+fn tls_config_from_env(base_url: &Url) -> Result<Option<ClientTlsConfig>> {
+    let ca = std::env::var("BASKET_API_TLS_CA").ok().filter(|v| !v.trim().is_empty());
+    if base_url.scheme() != "https" && ca.is_none() {
+        return Ok(None);
+    }
+
+    let mut tls = ClientTlsConfig::new().with_native_roots();
+    if let Some(ca) = ca {
+        let pem = std::fs::read(ca.trim()).map_err(|e| anyhow!("BASKET_API_TLS_CA {ca}: {e}"))?;
+        tls = tls.ca_certificate(Certificate::from_pem(pem));
+    }
+    if let Ok(domain) = std::env::var("BASKET_API_TLS_DOMAIN") {
+        tls = tls.domain_name(domain.trim());
+    }
+    Ok(Some(tls))
+}
+
+/// Connects lazily, the web app starts while basket-api is down and the channel reconnects when it is back.
+/// Deadline and retries come from `BASKET_API_TIMEOUT_MS`, `BASKET_API_RETRIES` and `BASKET_API_RETRY_BASE_MS`.
 pub async fn make_service(url_map_service: UrlMapService) -> Result<BasketServiceContext> {
     let base_url = url_map_service.get_mapped_url(KEY).unwrap_or(KEY);
 
@@ -94,9 +148,28 @@ pub async fn make_service(url_map_service: UrlMapService) -> Result<BasketServic
     let base_url = Url::parse(base_url)?;
     let base_url = base_url.join(BASKET_SERVICE_PATH)?;
 
-    let client = BasketClient::connect(base_url.to_string()).await?;
+    let config = ResilienceConfig::from_env(KEY)?;
+
+    let mut endpoint = Endpoint::from_shared(base_url.to_string())?.connect_timeout(CONNECT_TIMEOUT).timeout(config.timeout);
+    if let Some(tls) = tls_config_from_env(&base_url)? {
+        endpoint = endpoint.tls_config(tls)?;
+    }
+    let client = BasketClient::new(endpoint.connect_lazy());
 
     Ok(BasketServiceContext {
-        service: Arc::new(BasketServiceApi::new(client, base_url)),
+        service: Arc::new(BasketServiceApi::new(client, base_url, config)),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_to_app_error() {
+        assert_eq!(status_to_app_error(&Status::unauthenticated("token expired")), crate::AppError::Unauthorized);
+        assert_eq!(status_to_app_error(&Status::permission_denied("")), crate::AppError::Forbidden);
+        assert_eq!(status_to_app_error(&Status::unavailable("")), crate::AppError::ServiceUnavailable(KEY.to_string()));
+        assert!(matches!(status_to_app_error(&Status::invalid_argument("quantity")), crate::AppError::Other(m) if m.contains("quantity")));
+    }
+}
@@ -11,24 +11,22 @@ use leptos::prelude::expect_context;
 #[cfg(feature = "ssr")]
 use crate::basket::service::BasketServiceContext;
 
-#[server]
-#[middleware(auth::RequireAuth)]
+/// Signed in users get their basket, anonymous users the guest basket of their session.
+#[server(prefix = "/api_basket")]
 pub async fn get_basket() -> Result<Vec<BasketQuantity>, crate::AppError> {
     let basket_service_context: BasketServiceContext = expect_context();
 
     basket_service_context.service.get_basket().await
 }
 
-#[server]
-#[middleware(auth::RequireAuth)]
+#[server(prefix = "/api_basket")]
 pub async fn update_basket(items: Vec<BasketQuantity>) -> Result<(), crate::AppError> {
     let basket_service_context: BasketServiceContext = expect_context();
 
     basket_service_context.service.update_basket(items).await
 }
 
-#[server]
-#[middleware(auth::RequireAuth)]
+#[server(prefix = "/api_basket")]
 pub async fn delete_basket() -> Result<(), crate::AppError> {
     let basket_service_context: BasketServiceContext = expect_context();
 
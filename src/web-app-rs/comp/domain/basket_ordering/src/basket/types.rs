@@ -1,5 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+/// Most units of one product a basket holds.
+pub const MAX_QUANTITY: i32 = 99;
+
+/// Most different products a basket holds.
+pub const MAX_LINES: usize = 100;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BasketQuantity {
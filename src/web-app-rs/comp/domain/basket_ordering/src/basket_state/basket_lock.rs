@@ -0,0 +1,343 @@
+//! Serializes the read-modify-write basket updates of one owner, the basket-api only replaces whole baskets.
+//! Guest baskets live in the session record, their requests are serialized by [`GuestBasketLockLayer`].
+//!
+//! The locks live in this process, instances of the web app behind a load balancer still race.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex, Weak},
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::Body,
+    http::{Method, Request, header},
+};
+use futures::future::BoxFuture;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+use tower::{Layer, Service};
+
+use crate::basket::{
+    service::BasketService,
+    types::{BasketQuantity, MAX_LINES, MAX_QUANTITY},
+};
+use leptos_axum::extract;
+
+/// Server function prefixes whose calls may change the guest basket.
+const GUEST_BASKET_PATHS: [&str; 2] = ["/api_basket/", "/api_basket_state/"];
+
+#[derive(Default)]
+pub(crate) struct BasketLocks {
+    locks: Mutex<HashMap<String, Weak<AsyncMutex<()>>>>,
+}
+
+impl BasketLocks {
+    pub(crate) async fn lock(&self, owner: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+            // owners without a pending update have no strong reference left
+            locks.retain(|_, lock| lock.strong_count() > 0);
+            match locks.get(owner).and_then(Weak::upgrade) {
+                Some(lock) => lock,
+                None => {
+                    let lock = Arc::new(AsyncMutex::new(()));
+                    locks.insert(owner.to_string(), Arc::downgrade(&lock));
+                    lock
+                }
+            }
+        };
+        lock.lock_owned().await
+    }
+}
+
+/// One set of locks for the process, the basket state service is also created per request.
+pub(crate) fn shared_locks() -> Arc<BasketLocks> {
+    static LOCKS: LazyLock<Arc<BasketLocks>> = LazyLock::new(Default::default);
+    LOCKS.clone()
+}
+
+/// The signed in user; `None` for a guest, whose whole request already holds the lock of the session.
+pub(crate) async fn basket_owner() -> Option<String> {
+    let extensions: axum::http::Extensions = extract().await.ok()?;
+    let user = auth::server::get_user_ref_from_extensions(&extensions).ok()?;
+    Some(format!("user:{}", user.sub))
+}
+
+/// Holds the lock of the session named by the `cookie_name` cookie for a whole basket server function call. The session
+/// record is loaded before and saved after the handler, a lock taken inside would let concurrent calls overwrite the
+/// guest basket of each other. Goes outside the session layer.
+///
+/// A call without the cookie starts a new session no other request knows about, it needs no lock.
+#[derive(Clone)]
+pub struct GuestBasketLockLayer {
+    cookie_name: Arc<str>,
+    locks: Arc<BasketLocks>,
+}
+
+impl GuestBasketLockLayer {
+    pub fn new(cookie_name: &str) -> Self {
+        Self {
+            cookie_name: Arc::from(cookie_name),
+            locks: shared_locks(),
+        }
+    }
+
+    fn session_of(&self, req: &Request<Body>) -> Option<String> {
+        if req.method() != Method::POST || !GUEST_BASKET_PATHS.iter().any(|path| req.uri().path().starts_with(path)) {
+            return None;
+        }
+        let cookies = req.headers().get_all(header::COOKIE).into_iter().filter_map(|v| v.to_str().ok()).flat_map(|v| v.split(';'));
+        cookies.filter_map(|cookie| cookie.trim().split_once('=')).find(|(name, _)| *name == &*self.cookie_name).map(|(_, id)| format!("session:{id}"))
+    }
+}
+
+impl<S> Layer<S> for GuestBasketLockLayer {
+    type Service = GuestBasketLockService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GuestBasketLockService { inner, layer: self.clone() }
+    }
+}
+
+#[derive(Clone)]
+pub struct GuestBasketLockService<S> {
+    inner: S,
+    layer: GuestBasketLockLayer,
+}
+
+impl<S> Service<Request<Body>> for GuestBasketLockService<S>
+where
+    S: Service<Request<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let Some(session) = self.layer.session_of(&req) else {
+            return Box::pin(self.inner.call(req));
+        };
+
+        // the clone waits for the lock, the service that was polled ready takes the call
+        let mut inner = self.inner.clone();
+        std::mem::swap(&mut self.inner, &mut inner);
+        let locks = self.layer.locks.clone();
+        Box::pin(async move {
+            let _guard = locks.lock(&session).await;
+            inner.call(req).await
+        })
+    }
+}
+
+/// Reads the quantities, lets `f` change them and writes them back if it reports a change,
+/// all under the lock of `owner`.
+pub(crate) async fn mutate_basket<F>(basket: &dyn BasketService, locks: &BasketLocks, owner: Option<&str>, f: F) -> Result<bool, crate::AppError>
+where
+    F: FnOnce(&mut Vec<BasketQuantity>) -> bool,
+{
+    let _guard = match owner {
+        Some(owner) => Some(locks.lock(owner).await),
+        None => None,
+    };
+
+    let mut items = basket.get_basket().await?;
+    let changed = f(&mut items);
+    if changed {
+        basket.update_basket(items).await?;
+    }
+    Ok(changed)
+}
+
+/// `false` if the product is at [`MAX_QUANTITY`] already or new in a basket of [`MAX_LINES`] products.
+pub(crate) fn add_quantity(items: &mut Vec<BasketQuantity>, product_id: i32) -> bool {
+    let full = items.len() >= MAX_LINES;
+    match items.iter_mut().find(|x| x.product_id == product_id) {
+        Some(item) if item.quantity >= MAX_QUANTITY => {
+            item.quantity = MAX_QUANTITY;
+            return false;
+        }
+        Some(item) => item.quantity = item.quantity.saturating_add(1),
+        None if full => return false,
+        None => items.push(BasketQuantity { product_id, quantity: 1 }),
+    }
+    true
+}
+
+/// Zero or less removes the product, more than [`MAX_QUANTITY`] is cut to it. `false` if nothing changed or the product
+/// is new in a basket of [`MAX_LINES`] products.
+pub(crate) fn set_quantity(items: &mut Vec<BasketQuantity>, product_id: i32, quantity: i32) -> bool {
+    let quantity = quantity.min(MAX_QUANTITY);
+    let full = items.len() >= MAX_LINES;
+    match items.iter_mut().find(|x| x.product_id == product_id) {
+        Some(item) if quantity > 0 => item.quantity = quantity,
+        Some(_) => items.retain(|x| x.product_id != product_id),
+        None if quantity > 0 && !full => items.push(BasketQuantity { product_id, quantity }),
+        None => return false,
+    }
+    true
+}
+
+/// Takes the ordered quantities out of the basket, items added after the order was placed stay.
+pub(crate) fn remove_quantities(items: &mut Vec<BasketQuantity>, ordered: &[BasketQuantity]) -> bool {
+    let mut changed = false;
+    for ordered in ordered {
+        if let Some(item) = items.iter_mut().find(|x| x.product_id == ordered.product_id) {
+            item.quantity -= ordered.quantity;
+            changed = true;
+        }
+    }
+    items.retain(|x| x.quantity > 0);
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::time::Duration;
+
+    /// Stores one basket and yields between read and write like a remote basket-api would.
+    #[derive(Default)]
+    struct SlowBasket {
+        items: Mutex<Vec<BasketQuantity>>,
+    }
+
+    #[async_trait]
+    impl BasketService for SlowBasket {
+        async fn get_basket(&self) -> Result<Vec<BasketQuantity>, crate::AppError> {
+            let items = self.items.lock().unwrap().clone();
+            tokio::time::sleep(Duration::from_millis(2)).await;
+            Ok(items)
+        }
+
+        async fn update_basket(&self, basket: Vec<BasketQuantity>) -> Result<(), crate::AppError> {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            *self.items.lock().unwrap() = basket;
+            Ok(())
+        }
+
+        async fn delete_basket(&self) -> Result<(), crate::AppError> {
+            self.items.lock().unwrap().clear();
+            Ok(())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_adds_are_not_lost() {
+        const ADDS: i32 = 40;
+
+        let basket = Arc::new(SlowBasket::default());
+        let locks = Arc::new(BasketLocks::default());
+
+        let tasks = (0..ADDS)
+            .map(|i| {
+                let (basket, locks) = (basket.clone(), locks.clone());
+                tokio::spawn(async move { mutate_basket(basket.as_ref(), &locks, Some("user:1"), |items| add_quantity(items, 1 + i % 2)).await })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            assert!(task.await.unwrap().unwrap());
+        }
+
+        let items = basket.get_basket().await.unwrap();
+        assert_eq!(items.iter().map(|x| x.quantity).sum::<i32>(), ADDS);
+        assert_eq!(items.len(), 2);
+
+        // the lock of an owner without pending updates is dropped
+        let _ = locks.lock("user:2").await;
+        assert_eq!(locks.locks.lock().unwrap().values().filter(|x| x.strong_count() > 0).count(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_guest_adds_are_not_lost() {
+        use tower::ServiceExt;
+        use tower_sessions::{MemoryStore, Session, SessionManagerLayer};
+
+        const ADDS: i32 = 20;
+
+        // the guest basket in the session record, read and written with a pause in between
+        async fn add(session: Session) {
+            let mut items = session.get::<Vec<BasketQuantity>>("basket").await.unwrap().unwrap_or_default();
+            tokio::time::sleep(Duration::from_millis(2)).await;
+            add_quantity(&mut items, 1);
+            session.insert("basket", items).await.unwrap();
+        }
+        async fn quantity(session: Session) -> String {
+            let items = session.get::<Vec<BasketQuantity>>("basket").await.unwrap().unwrap_or_default();
+            items.iter().map(|x| x.quantity).sum::<i32>().to_string()
+        }
+        let app = axum::Router::new()
+            .route("/api_basket_state/add", axum::routing::post(add))
+            .route("/api_basket_state/quantity", axum::routing::post(quantity))
+            .layer(SessionManagerLayer::new(MemoryStore::default()).with_secure(false))
+            .layer(GuestBasketLockLayer::new("id"));
+        let request = |path: &str, cookie: Option<&str>| {
+            let builder = Request::builder().method(Method::POST).uri(path);
+            match cookie {
+                Some(cookie) => builder.header(header::COOKIE, cookie),
+                None => builder,
+            }
+            .body(Body::empty())
+            .unwrap()
+        };
+
+        let response = app.clone().oneshot(request("/api_basket_state/add", None)).await.unwrap();
+        let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap().split(';').next().unwrap().to_string();
+
+        let tasks = (1..ADDS)
+            .map(|_| {
+                let (app, request) = (app.clone(), request("/api_basket_state/add", Some(&cookie)));
+                tokio::spawn(async move { app.oneshot(request).await.unwrap() })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            assert!(task.await.unwrap().status().is_success());
+        }
+
+        let response = app.oneshot(request("/api_basket_state/quantity", Some(&cookie))).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, ADDS.to_string());
+    }
+
+    #[test]
+    fn test_set_quantity() {
+        let mut items = vec![BasketQuantity { product_id: 1, quantity: 2 }];
+        assert!(set_quantity(&mut items, 1, 5));
+        assert!(set_quantity(&mut items, 2, 1));
+        assert!(!set_quantity(&mut items, 3, 0));
+        assert!(set_quantity(&mut items, 1, 0));
+        assert_eq!(items, vec![BasketQuantity { product_id: 2, quantity: 1 }]);
+
+        let mut items = vec![BasketQuantity { product_id: 1, quantity: 3 }, BasketQuantity { product_id: 2, quantity: 1 }];
+        assert!(remove_quantities(&mut items, &[BasketQuantity { product_id: 1, quantity: 2 }, BasketQuantity { product_id: 2, quantity: 1 }]));
+        assert_eq!(items, vec![BasketQuantity { product_id: 1, quantity: 1 }]);
+    }
+
+    #[test]
+    fn test_quantity_bounds() {
+        let q = |product_id, quantity| BasketQuantity { product_id, quantity };
+
+        let mut items = vec![q(1, MAX_QUANTITY - 1), q(2, i32::MAX)];
+        assert!(add_quantity(&mut items, 1));
+        assert!(!add_quantity(&mut items, 1));
+        assert!(!add_quantity(&mut items, 2));
+        assert_eq!(items, vec![q(1, MAX_QUANTITY), q(2, MAX_QUANTITY)]);
+
+        assert!(set_quantity(&mut items, 1, i32::MAX));
+        assert!(set_quantity(&mut items, 3, 1000));
+        assert_eq!(items, vec![q(1, MAX_QUANTITY), q(2, MAX_QUANTITY), q(3, MAX_QUANTITY)]);
+
+        // a full basket takes no new products, the ones in it still change
+        let mut items = (0..MAX_LINES as i32).map(|id| q(id, 1)).collect::<Vec<_>>();
+        assert!(!add_quantity(&mut items, -1));
+        assert!(!set_quantity(&mut items, -1, 1));
+        assert_eq!(items.len(), MAX_LINES);
+        assert!(add_quantity(&mut items, 0));
+        assert!(set_quantity(&mut items, 1, 5));
+    }
+}
@@ -0,0 +1,100 @@
+//! Remembers the checkouts that placed their order by request id, a double submit or a retried request with the same
+//! id gets the first outcome instead of a second order.
+//!
+//! Like the basket locks this lives in the process, the ordering-api still dedups the order itself by `x-requestid`.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::basket_state::basket_lock::BasketLocks;
+
+/// A retry comes within seconds, but the checkout page may stay open for a while before the second click.
+const OUTCOME_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Default)]
+pub(crate) struct CheckoutRequests {
+    locks: BasketLocks,
+    placed: Mutex<HashMap<String, Instant>>,
+}
+
+impl CheckoutRequests {
+    /// Runs `checkout` unless it already succeeded for `key`; a duplicate waits for a pending run first.
+    ///
+    /// Only the success is kept, after an error the order may or may not exist and running again with the same request
+    /// id is safe.
+    pub(crate) async fn run_once<F, Fut>(&self, key: &str, checkout: F) -> Result<(), crate::AppError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(), crate::AppError>>,
+    {
+        let _guard = self.locks.lock(key).await;
+        if self.is_placed(key) {
+            return Ok(());
+        }
+
+        checkout().await?;
+
+        let mut placed = self.placed.lock().unwrap_or_else(|e| e.into_inner());
+        placed.retain(|_, at| at.elapsed() < OUTCOME_TTL);
+        placed.insert(key.to_string(), Instant::now());
+        Ok(())
+    }
+
+    fn is_placed(&self, key: &str) -> bool {
+        let placed = self.placed.lock().unwrap_or_else(|e| e.into_inner());
+        placed.get(key).is_some_and(|at| at.elapsed() < OUTCOME_TTL)
+    }
+}
+
+/// One record for the process, the basket state service is created per request.
+pub(crate) fn shared_checkout_requests() -> Arc<CheckoutRequests> {
+    static REQUESTS: LazyLock<Arc<CheckoutRequests>> = LazyLock::new(Default::default);
+    REQUESTS.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_duplicates_place_one_order() {
+        let requests = Arc::new(CheckoutRequests::default());
+        let orders = Arc::new(AtomicUsize::new(0));
+
+        let tasks = (0..8)
+            .map(|_| {
+                let (requests, orders) = (requests.clone(), orders.clone());
+                tokio::spawn(async move {
+                    requests
+                        .run_once("user:1", || async {
+                            tokio::time::sleep(Duration::from_millis(5)).await;
+                            orders.fetch_add(1, Ordering::SeqCst);
+                            Ok(())
+                        })
+                        .await
+                })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), Ok(()));
+        }
+        assert_eq!(orders.load(Ordering::SeqCst), 1);
+
+        // a failed checkout is not remembered, the retry runs again
+        let failed = requests.run_once("user:2", || async { Err(crate::AppError::Timeout("ordering-api".to_string())) }).await;
+        assert!(failed.is_err());
+        let retried = requests
+            .run_once("user:2", || async {
+                orders.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .await;
+        assert_eq!(retried, Ok(()));
+        assert_eq!(orders.load(Ordering::SeqCst), 2);
+    }
+}
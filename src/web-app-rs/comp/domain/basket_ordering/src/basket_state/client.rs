@@ -43,24 +43,18 @@ pub fn provide_basket_state_info_context() {
     provide_context(BasketStateInfoContext::new());
 }
 
-pub async fn refresh_basket_state_info(context: BasketStateInfoContext, auth_context: auth::client::UserInfoCntxt) {
-    if !auth_context.is_logged_in() {
-        context.0.update(|basket_state_info| {
-            basket_state_info.basket_items = Ok(None);
-        });
-    } else {
-        let basket_items = basket_state::server_api::get_basket_items().await;
-        let basket_items = basket_items.map(|basket_items| Some(basket_items));
-        context.0.update(|basket_state_info| {
-            basket_state_info.basket_items = basket_items;
-        });
-    }
+/// Signed in or not, anonymous users have the guest basket of their session.
+pub async fn refresh_basket_state_info(context: BasketStateInfoContext) {
+    let basket_items = basket_state::server_api::get_basket_items().await;
+    let basket_items = basket_items.map(|basket_items| Some(basket_items));
+    context.0.update(|basket_state_info| {
+        basket_state_info.basket_items = basket_items;
+    });
 }
 
 pub fn refresh_basket_state_info_action() -> Action<(), ()> {
     Action::new(move |_: &()| {
         let context = expect_context::<BasketStateInfoContext>();
-        let auth_context = expect_context::<auth::client::UserInfoCntxt>();
-        refresh_basket_state_info(context, auth_context)
+        refresh_basket_state_info(context)
     })
 }
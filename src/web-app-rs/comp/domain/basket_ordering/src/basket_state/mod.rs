@@ -8,4 +8,12 @@ pub mod context_layer;
 #[cfg(feature = "ssr")]
 pub mod server;
 
+#[cfg(feature = "ssr")]
+mod basket_lock;
+#[cfg(feature = "ssr")]
+pub use basket_lock::GuestBasketLockLayer;
+
+#[cfg(feature = "ssr")]
+mod checkout_requests;
+
 pub mod client;
@@ -1,15 +1,18 @@
 use async_trait::async_trait;
 use tonic::Extensions;
 
-use crate::basket::types::BasketQuantity;
+use crate::basket_state::basket_lock::{self, BasketLocks};
+use crate::basket_state::checkout_requests::{self, CheckoutRequests};
 use crate::catalog::service::CatalogServiceContext;
 use crate::catalog::types::CatalogItem;
 
 use crate::basket_state::service::{BasketCheckoutInfo, BasketStateService, BasketStateServiceContext};
 use leptos_axum::extract;
 
-use crate::basket_state::types::BasketItem;
+use crate::basket::types::BasketQuantity;
+use crate::basket_state::types::{BasketItem, CreateOrderRequest};
 use crate::ordering::service::OrderingServiceContext;
+use crate::ordering::types::{CreateOrderDraftRequest, OrderDraft};
 
 use anyhow::Result;
 use uuid::Uuid;
@@ -22,14 +25,18 @@ struct BasketStateServiceApi {
     basket_service: BasketServiceContext,
     catalog_service: CatalogServiceContext,
     ordering_service: OrderingServiceContext,
+    locks: Arc<BasketLocks>,
+    checkout_requests: Arc<CheckoutRequests>,
 }
 
 impl BasketStateServiceApi {
-    pub fn new(basket_service: BasketServiceContext, catalog_service: CatalogServiceContext, ordering_service: OrderingServiceContext) -> Self {
+    pub fn new(basket_service: BasketServiceContext, catalog_service: CatalogServiceContext, ordering_service: OrderingServiceContext, locks: Arc<BasketLocks>, checkout_requests: Arc<CheckoutRequests>) -> Self {
         BasketStateServiceApi {
             basket_service,
             catalog_service,
             ordering_service,
+            locks,
+            checkout_requests,
         }
     }
 
@@ -67,66 +74,69 @@ impl BasketStateServiceApi {
         self.fetch_core_async().await
     }
 
-    async fn delete_basket(&self) -> Result<(), crate::AppError> {
-        self.basket_service.service.delete_basket().await
+    /// Places the order with the items in the basket now, and only once it is confirmed takes them out of the basket.
+    async fn place_order(&self, mut order: CreateOrderRequest, request_id: Uuid) -> Result<(), crate::AppError> {
+        order.items = self.fetch_basket_items().await?;
+        let ordered = order
+            .items
+            .iter()
+            .map(|x| BasketQuantity {
+                product_id: x.product_id,
+                quantity: x.quantity,
+            })
+            .collect::<Vec<_>>();
+
+        self.ordering_service.service.create_order(order, request_id).await?;
+
+        // the order stands, a basket that could not be updated is no reason to report the checkout as failed
+        if let Err(e) = self.mutate_quantities(|items| basket_lock::remove_quantities(items, &ordered)).await {
+            leptos::logging::warn!("order {request_id} placed, but the basket still has its items: {e}");
+        }
+        Ok(())
+    }
+
+    /// Products that are not in the catalog do not go into the basket.
+    async fn ensure_in_catalog(&self, product_id: i32) -> Result<(), crate::AppError> {
+        let items = self.catalog_service.service.get_catalog_items_by_ids(vec![product_id]).await.map_err(|e| crate::AppError::Other(e.to_string()))?;
+        if items.is_empty() {
+            return Err(crate::AppError::Other(format!("Catalog item not found {product_id}")));
+        }
+        Ok(())
+    }
+
+    /// Quantities only, the catalog is not needed to change them.
+    async fn mutate_quantities(&self, f: impl FnOnce(&mut Vec<crate::basket::types::BasketQuantity>) -> bool) -> Result<bool, crate::AppError> {
+        let owner = basket_lock::basket_owner().await;
+        basket_lock::mutate_basket(self.basket_service.service.as_ref(), &self.locks, owner.as_deref(), f).await
     }
 }
 
 #[async_trait]
 impl BasketStateService for BasketStateServiceApi {
     async fn get_basket_items(&self) -> Result<Vec<BasketItem>, crate::AppError> {
-        if auth::server::is_authenticated().await? { self.fetch_basket_items().await } else { Ok(vec![]) }
+        // anonymous users have the guest basket of their session
+        self.fetch_basket_items().await
     }
 
     async fn add_basket_item(&self, item: CatalogItem) -> Result<(), crate::AppError> {
-        let mut basket_items = self
-            .fetch_basket_items()
-            .await?
-            .iter()
-            .map(|x| BasketQuantity {
-                product_id: x.product_id,
-                quantity: x.quantity,
-            })
-            .collect::<Vec<_>>();
-        if let Some(basket_item) = basket_items.iter_mut().find(|x| x.product_id == item.id) {
-            basket_item.quantity += 1;
-        } else {
-            basket_items.push(BasketQuantity { product_id: item.id, quantity: 1 });
-        }
-        self.basket_service.service.update_basket(basket_items).await?;
+        self.ensure_in_catalog(item.id).await?;
+        self.mutate_quantities(|items| basket_lock::add_quantity(items, item.id)).await?;
         Ok(())
     }
 
     async fn set_quantity(&self, product_id: i32, quantity: i32) -> Result<bool, crate::AppError> {
-        let mut basket_items = self
-            .fetch_basket_items()
-            .await?
-            .iter()
-            .map(|x| BasketQuantity {
-                product_id: x.product_id,
-                quantity: x.quantity,
-            })
-            .collect::<Vec<_>>();
-
-        let changed = if let Some(basket_item) = basket_items.iter_mut().find(|x| x.product_id == product_id) {
-            if quantity > 0 {
-                basket_item.quantity = quantity;
-            } else {
-                basket_items.retain(|x| x.product_id != product_id);
-            }
-            true
-        } else if quantity > 0 {
-            basket_items.push(BasketQuantity { product_id: product_id, quantity: quantity });
-            true
-        } else {
-            false
-        };
-
-        if changed {
-            self.basket_service.service.update_basket(basket_items).await?;
+        if quantity > 0 {
+            self.ensure_in_catalog(product_id).await?;
         }
+        self.mutate_quantities(|items| basket_lock::set_quantity(items, product_id, quantity)).await
+    }
+
+    async fn get_order_draft(&self) -> Result<OrderDraft, crate::AppError> {
+        let extensions: Extensions = extract().await?;
+        let buyer_id = auth::server::get_user_ref_from_extensions(&extensions)?.sub.clone();
 
-        Ok(changed)
+        let items = self.fetch_basket_items().await?;
+        self.ordering_service.service.create_order_draft(CreateOrderDraftRequest { buyer_id, items }).await
     }
 
     async fn checkout(&self, checkout_info: BasketCheckoutInfo) -> Result<(), crate::AppError> {
@@ -143,9 +153,7 @@ impl BasketStateService for BasketStateServiceApi {
         let user_name = user.username.clone();
         let buyer_id = user.sub.clone();
 
-        let order_items = self.fetch_basket_items().await?;
-
-        let order = super::types::CreateOrderRequest {
+        let order = CreateOrderRequest {
             user_id: buyer_id.clone(),
             user_name,
             city: checkout_info.city,
@@ -153,18 +161,17 @@ impl BasketStateService for BasketStateServiceApi {
             state: checkout_info.state,
             country: checkout_info.country,
             zip_code: checkout_info.zip_code,
-            card_number: checkout_info.card_number.unwrap_or("1111222233334444".to_string()),
-            card_holder_name: checkout_info.card_holder_name.unwrap_or("TESTUSER".to_string()),
-            card_security_number: checkout_info.card_security_number.unwrap_or("111".to_string()),
+            card_number: checkout_info.card_number,
+            card_holder_name: checkout_info.card_holder_name,
+            card_security_number: checkout_info.card_security_number,
             card_expiration: checkout_info.card_expiration,
             card_type_id: checkout_info.card_type_id,
-            buyer: buyer_id,
-            items: order_items,
+            buyer: buyer_id.clone(),
+            items: Vec::new(),
         };
 
-        self.ordering_service.service.create_order(order, checkout_info.request_id).await?;
-        self.delete_basket().await?;
-        Ok(())
+        let key = format!("{buyer_id}:{}", checkout_info.request_id);
+        self.checkout_requests.run_once(&key, || self.place_order(order, checkout_info.request_id)).await
     }
 }
 
@@ -175,12 +182,20 @@ pub fn make_service_from_context() -> Result<BasketStateServiceContext> {
             expect_context::<BasketServiceContext>(),
             expect_context::<CatalogServiceContext>(),
             expect_context::<OrderingServiceContext>(),
+            basket_lock::shared_locks(),
+            checkout_requests::shared_checkout_requests(),
         )),
     })
 }
 
 pub fn make_service(basket_service: BasketServiceContext, catalog_service: CatalogServiceContext, ordering_service: OrderingServiceContext) -> Result<BasketStateServiceContext> {
     Ok(BasketStateServiceContext {
-        service: Arc::new(BasketStateServiceApi::new(basket_service, catalog_service, ordering_service)),
+        service: Arc::new(BasketStateServiceApi::new(
+            basket_service,
+            catalog_service,
+            ordering_service,
+            basket_lock::shared_locks(),
+            checkout_requests::shared_checkout_requests(),
+        )),
     })
 }
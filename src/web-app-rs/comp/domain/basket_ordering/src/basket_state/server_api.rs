@@ -6,12 +6,12 @@ use leptos::server;
 use crate::basket_state::service::BasketStateServiceContext;
 
 use crate::basket_state::types::BasketItem;
+use crate::ordering::types::OrderDraft;
 
 #[cfg(feature = "ssr")]
 use leptos::prelude::expect_context;
 
 #[server(prefix = "/api_basket_state")]
-#[middleware(auth::RequireAuth)]
 pub async fn get_basket_items() -> Result<Vec<BasketItem>, crate::AppError> {
     let context: BasketStateServiceContext = expect_context();
 
@@ -19,7 +19,6 @@ pub async fn get_basket_items() -> Result<Vec<BasketItem>, crate::AppError> {
 }
 
 #[server(prefix = "/api_basket_state")]
-#[middleware(auth::RequireAuth)]
 pub async fn add_basket_item(item: CatalogItem) -> Result<(), crate::AppError> {
     let context: BasketStateServiceContext = expect_context();
 
@@ -27,9 +26,16 @@ pub async fn add_basket_item(item: CatalogItem) -> Result<(), crate::AppError> {
 }
 
 #[server(prefix = "/api_basket_state")]
-#[middleware(auth::RequireAuth)]
 pub async fn set_quantity(product_id: i32, quantity: i32) -> Result<bool, crate::AppError> {
     let context: BasketStateServiceContext = expect_context();
 
     context.service.set_quantity(product_id, quantity).await
 }
+
+#[server(prefix = "/api_basket_state")]
+#[middleware(auth::RequireAuth)]
+pub async fn get_order_draft() -> Result<OrderDraft, crate::AppError> {
+    let context: BasketStateServiceContext = expect_context();
+
+    context.service.get_order_draft().await
+}
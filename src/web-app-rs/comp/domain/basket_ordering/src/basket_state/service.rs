@@ -3,8 +3,9 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use super::types::BasketItem;
+use super::types::{BasketItem, MASKED, mask_card_number};
 use crate::catalog::types::CatalogItem;
+use crate::ordering::types::OrderDraft;
 
 pub struct BasketCheckoutInfo {
     pub street: String,
@@ -12,21 +13,42 @@ pub struct BasketCheckoutInfo {
     pub state: String,
     pub country: String,
     pub zip_code: String,
-    pub card_number: Option<String>,
-    pub card_holder_name: Option<String>,
-    pub card_security_number: Option<String>,
+    pub card_number: String,
+    pub card_holder_name: String,
+    pub card_security_number: String,
     pub card_expiration: chrono::DateTime<chrono::Utc>,
     pub card_type_id: i32,
     pub buyer: Option<String>,
     pub request_id: Uuid,
 }
 
+impl std::fmt::Debug for BasketCheckoutInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BasketCheckoutInfo")
+            .field("street", &self.street)
+            .field("city", &self.city)
+            .field("state", &self.state)
+            .field("country", &self.country)
+            .field("zip_code", &self.zip_code)
+            .field("card_number", &mask_card_number(&self.card_number))
+            .field("card_holder_name", &MASKED)
+            .field("card_security_number", &MASKED)
+            .field("card_expiration", &self.card_expiration)
+            .field("card_type_id", &self.card_type_id)
+            .field("buyer", &self.buyer)
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
+
 #[async_trait]
 pub trait BasketStateService: Send + Sync {
     async fn get_basket_items(&self) -> Result<Vec<BasketItem>, crate::AppError>;
 
     async fn add_basket_item(&self, item: CatalogItem) -> Result<(), crate::AppError>;
     async fn set_quantity(&self, product_id: i32, quantity: i32) -> Result<bool, crate::AppError>;
+    /// The basket as the ordering-api would price the order, for a review before `checkout`.
+    async fn get_order_draft(&self) -> Result<OrderDraft, crate::AppError>;
     async fn checkout(&self, checkout_info: BasketCheckoutInfo) -> Result<(), crate::AppError>;
 }
 #[derive(Clone)]
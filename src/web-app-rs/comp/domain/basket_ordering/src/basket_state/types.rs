@@ -1,7 +1,7 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateOrderRequest {
     pub user_id: String,
@@ -41,6 +41,40 @@ impl CreateOrderRequest {
     }
 }
 
+/// The card data stays out of logs, see `mask_card_number`.
+impl std::fmt::Debug for CreateOrderRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CreateOrderRequest")
+            .field("user_id", &self.user_id)
+            .field("user_name", &self.user_name)
+            .field("city", &self.city)
+            .field("street", &self.street)
+            .field("state", &self.state)
+            .field("country", &self.country)
+            .field("zip_code", &self.zip_code)
+            .field("card_number", &mask_card_number(&self.card_number))
+            .field("card_holder_name", &MASKED)
+            .field("card_expiration", &self.card_expiration)
+            .field("card_security_number", &MASKED)
+            .field("card_type_id", &self.card_type_id)
+            .field("buyer", &self.buyer)
+            .field("items", &self.items)
+            .finish()
+    }
+}
+
+/// Stands in for card data in `Debug` output.
+pub const MASKED: &str = "***";
+
+/// Only the last four digits, the way a receipt shows a card.
+pub fn mask_card_number(card_number: &str) -> String {
+    let digits = card_number.chars().filter(char::is_ascii_digit).collect::<Vec<_>>();
+    match digits.len() {
+        0..=4 => MASKED.to_string(),
+        len => format!("{MASKED}{}", digits[len - 4..].iter().collect::<String>()),
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BasketItem {
@@ -64,3 +98,23 @@ impl BasketItem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_masks_card_data() {
+        let mut request = CreateOrderRequest::new();
+        request.card_number = "4111 1111 1111 1234".to_string();
+        request.card_holder_name = "Jane Doe".to_string();
+        request.card_security_number = "987".to_string();
+
+        let debug = format!("{request:?}");
+        assert!(debug.contains("***1234"));
+        assert!(!debug.contains("4111"));
+        assert!(!debug.contains("Jane Doe"));
+        assert!(!debug.contains("987"));
+        assert_eq!(mask_card_number("123"), MASKED);
+    }
+}
@@ -1,6 +1,11 @@
+pub mod addresses;
 pub mod basket;
 pub mod basket_state;
 pub mod ordering;
+pub mod wishlist;
+
+#[cfg(all(test, feature = "ssr"))]
+mod test_db;
 
 pub(crate) use catalog;
 
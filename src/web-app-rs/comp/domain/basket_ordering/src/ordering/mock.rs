@@ -0,0 +1,155 @@
+//! In-memory `OrderingService` for the `mock-backends` feature.
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use uuid::Uuid;
+
+use crate::basket::mock::current_user_sub;
+use crate::basket_state::types::CreateOrderRequest;
+use crate::ordering::service::{OrderingService, OrderingServiceContext};
+use crate::ordering::types::{CardType, CreateOrderDraftRequest, ORDER_STATUS_CANCELLED, Order, OrderDetails, OrderDraft, OrderDraftItem, OrderItem, is_cancellable};
+
+#[derive(Default)]
+struct OrderStore {
+    next_order_number: usize,
+    /// orders per buyer `sub`, with the request id they were created with
+    orders: HashMap<String, Vec<(Uuid, OrderDetails)>>,
+}
+
+#[derive(Default)]
+pub struct MockOrderingService {
+    store: Mutex<OrderStore>,
+}
+
+impl MockOrderingService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn orders(&self, sub: &str) -> Vec<Order> {
+        let store = self.store.lock().unwrap_or_else(|e| e.into_inner());
+        store
+            .orders
+            .get(sub)
+            .map(|x| {
+                x.iter()
+                    .map(|(_, order)| Order {
+                        order_number: order.order_number,
+                        date: order.date,
+                        status: order.status.clone(),
+                        total: order.total,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn order(&self, sub: &str, order_number: usize) -> Option<OrderDetails> {
+        let store = self.store.lock().unwrap_or_else(|e| e.into_inner());
+        store.orders.get(sub)?.iter().map(|(_, order)| order).find(|order| order.order_number == order_number).cloned()
+    }
+
+    /// A repeated request id does not create a second order, as with the x-requestid of the ordering-api.
+    pub fn create(&self, request: CreateOrderRequest, request_id: Uuid) {
+        let mut store = self.store.lock().unwrap_or_else(|e| e.into_inner());
+        if store.orders.get(&request.user_id).is_some_and(|x| x.iter().any(|(id, _)| *id == request_id)) {
+            return;
+        }
+        store.next_order_number += 1;
+        let order = OrderDetails {
+            order_number: store.next_order_number,
+            date: chrono::Utc::now(),
+            status: "Submitted".to_string(),
+            description: None,
+            street: Some(request.street),
+            city: Some(request.city),
+            state: Some(request.state),
+            zipcode: Some(request.zip_code),
+            country: Some(request.country),
+            order_items: request
+                .items
+                .iter()
+                .map(|x| OrderItem {
+                    product_name: x.product_name.clone(),
+                    units: x.quantity,
+                    unit_price: x.unit_price,
+                    picture_url: None,
+                })
+                .collect(),
+            total: request.items.iter().map(|x| x.unit_price * Decimal::from(x.quantity)).sum(),
+        };
+        store.orders.entry(request.user_id).or_default().push((request_id, order));
+    }
+
+    /// Cancelling a cancelled order again succeeds, so a retried request does not fail.
+    pub fn cancel(&self, sub: &str, order_number: usize) -> Result<(), crate::AppError> {
+        let mut store = self.store.lock().unwrap_or_else(|e| e.into_inner());
+        let order = store
+            .orders
+            .get_mut(sub)
+            .and_then(|x| x.iter_mut().map(|(_, order)| order).find(|order| order.order_number == order_number))
+            .ok_or_else(|| crate::AppError::Other(format!("order {order_number} not found")))?;
+        if order.status == ORDER_STATUS_CANCELLED {
+            return Ok(());
+        }
+        if !is_cancellable(&order.status) {
+            return Err(crate::AppError::Other(format!("order {order_number} is {} and can not be cancelled", order.status)));
+        }
+        order.status = ORDER_STATUS_CANCELLED.to_string();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OrderingService for MockOrderingService {
+    async fn get_orders(&self) -> Result<Vec<Order>, crate::AppError> {
+        Ok(self.orders(&current_user_sub().await?))
+    }
+
+    async fn get_order(&self, order_id: i32) -> Result<Option<OrderDetails>, crate::AppError> {
+        let Ok(order_number) = usize::try_from(order_id) else {
+            return Ok(None);
+        };
+        Ok(self.order(&current_user_sub().await?, order_number))
+    }
+
+    async fn create_order(&self, request: CreateOrderRequest, request_id: Uuid) -> Result<(), crate::AppError> {
+        self.create(request, request_id);
+        Ok(())
+    }
+
+    async fn get_card_types(&self) -> Result<Vec<CardType>, crate::AppError> {
+        // the seed of the ordering-api
+        Ok(["Amex", "Visa", "MasterCard"].into_iter().zip(1..).map(|(name, id)| CardType { id, name: name.to_string() }).collect())
+    }
+
+    async fn create_order_draft(&self, request: CreateOrderDraftRequest) -> Result<OrderDraft, crate::AppError> {
+        let order_items = request
+            .items
+            .into_iter()
+            .map(|x| OrderDraftItem {
+                product_id: x.product_id,
+                product_name: x.product_name,
+                unit_price: x.unit_price,
+                discount: Decimal::ZERO,
+                units: x.quantity,
+                picture_url: None,
+            })
+            .collect::<Vec<_>>();
+        let total = order_items.iter().map(|x| x.unit_price * Decimal::from(x.units)).sum();
+        Ok(OrderDraft { order_items, total })
+    }
+
+    async fn cancel_order(&self, order_number: i32, _request_id: Uuid) -> Result<(), crate::AppError> {
+        let order_number = usize::try_from(order_number).map_err(|e| crate::AppError::Other(e.to_string()))?;
+        self.cancel(&current_user_sub().await?, order_number)
+    }
+}
+
+pub fn make_service() -> OrderingServiceContext {
+    OrderingServiceContext { service: Arc::new(MockOrderingService::new()) }
+}
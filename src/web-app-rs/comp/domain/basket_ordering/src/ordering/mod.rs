@@ -4,3 +4,6 @@ pub mod types;
 
 #[cfg(feature = "ssr")]
 pub mod server;
+
+#[cfg(feature = "mock-backends")]
+pub mod mock;
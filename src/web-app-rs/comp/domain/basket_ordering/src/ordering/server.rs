@@ -3,16 +3,17 @@ use std::sync::Arc;
 
 use crate::basket_state::types::CreateOrderRequest;
 use crate::ordering::service::OrderingService;
-use crate::ordering::types::Order;
+use crate::ordering::types::{CancelOrderRequest, CardType, CreateOrderDraftRequest, Order, OrderDetails, OrderDraft};
 use api_version::versioning::QueryStringApiVersion;
 use async_trait::async_trait;
 pub use reqwest::Client as HttpClient;
+use resilient_http::ResilientClient;
 use url::Url;
 use url_mapper::UrlMapService;
 use uuid::Uuid;
 
 struct OrderingServiceApi {
-    http_client: HttpClient,
+    http_client: ResilientClient,
     base_url: Url,
     api_version: QueryStringApiVersion,
 }
@@ -21,22 +22,68 @@ const ORDERING_SERVICE_BASE_URL: &str = "api/orders/";
 const BASE_MAP_TO_PATH: &str = "http://ordering-api";
 
 impl OrderingServiceApi {
-    pub fn new(http_client: HttpClient, base_url: Url, api_version: QueryStringApiVersion) -> Self {
+    pub fn new(http_client: ResilientClient, base_url: Url, api_version: QueryStringApiVersion) -> Self {
         OrderingServiceApi { http_client, base_url, api_version }
     }
-}
 
-#[async_trait]
-impl OrderingService for OrderingServiceApi {
-    async fn get_orders(&self) -> Result<Vec<Order>, crate::AppError> {
+    async fn orders(&self, token: &str) -> Result<Vec<Order>, crate::AppError> {
         let mut uri = self.base_url.clone();
         self.api_version.append_to_url(&mut uri);
 
-        let r = self.http_client.get(uri).bearer_auth(auth::server::token_from_auth_session().await?).send().await?.error_for_status()?.json::<Vec<Order>>().await?;
+        let r = self.http_client.get(uri).bearer_auth(token).send().await?.error_for_status()?.json::<Vec<Order>>().await?;
 
         Ok(r)
     }
 
+    /// Only orders of the list of the user are fetched, the ordering-api returns any order by its id.
+    async fn order(&self, token: &str, order_id: i32) -> Result<Option<OrderDetails>, crate::AppError> {
+        if !self.orders(token).await?.iter().any(|x| usize::try_from(order_id).is_ok_and(|id| x.order_number == id)) {
+            return Ok(None);
+        }
+
+        let mut uri = self.base_url.join(&order_id.to_string()).map_err(|e| crate::AppError::Other(e.to_string()))?;
+        self.api_version.append_to_url(&mut uri);
+
+        let response = self.http_client.get(uri).bearer_auth(token).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.json::<OrderDetails>().await?))
+    }
+
+    /// `Forbidden` for an order that is not in the list of the user, the ordering-api cancels any order by its number.
+    async fn cancel(&self, token: &str, order_number: i32, request_id: Uuid) -> Result<(), crate::AppError> {
+        if !self.orders(token).await?.iter().any(|x| usize::try_from(order_number).is_ok_and(|number| x.order_number == number)) {
+            return Err(crate::AppError::Forbidden);
+        }
+
+        let mut uri = self.base_url.join("cancel").map_err(|e| crate::AppError::Other(e.to_string()))?;
+        self.api_version.append_to_url(&mut uri);
+
+        let _r = self
+            .http_client
+            .put(uri)
+            .bearer_auth(token)
+            .header("x-requestid", request_id.to_string())
+            .json(&CancelOrderRequest { order_number })
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OrderingService for OrderingServiceApi {
+    async fn get_orders(&self) -> Result<Vec<Order>, crate::AppError> {
+        self.orders(&auth::server::token_from_auth_session().await?).await
+    }
+
+    async fn get_order(&self, order_id: i32) -> Result<Option<OrderDetails>, crate::AppError> {
+        self.order(&auth::server::token_from_auth_session().await?, order_id).await
+    }
+
     async fn create_order(&self, request: CreateOrderRequest, request_id: Uuid) -> Result<(), crate::AppError> {
         let mut uri = self.base_url.clone();
         self.api_version.append_to_url(&mut uri);
@@ -53,6 +100,45 @@ impl OrderingService for OrderingServiceApi {
 
         Ok(())
     }
+
+    async fn get_card_types(&self) -> Result<Vec<CardType>, crate::AppError> {
+        let mut uri = self.base_url.join("cardtypes").map_err(|e| crate::AppError::Other(e.to_string()))?;
+        self.api_version.append_to_url(&mut uri);
+
+        let r = self
+            .http_client
+            .get(uri)
+            .bearer_auth(auth::server::token_from_auth_session().await?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<CardType>>()
+            .await?;
+
+        Ok(r)
+    }
+
+    async fn create_order_draft(&self, request: CreateOrderDraftRequest) -> Result<OrderDraft, crate::AppError> {
+        let mut uri = self.base_url.join("draft").map_err(|e| crate::AppError::Other(e.to_string()))?;
+        self.api_version.append_to_url(&mut uri);
+
+        let r = self
+            .http_client
+            .post(uri)
+            .bearer_auth(auth::server::token_from_auth_session().await?)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<OrderDraft>()
+            .await?;
+
+        Ok(r)
+    }
+
+    async fn cancel_order(&self, order_number: i32, request_id: Uuid) -> Result<(), crate::AppError> {
+        self.cancel(&auth::server::token_from_auth_session().await?, order_number, request_id).await
+    }
 }
 
 pub async fn make_service(http_client: HttpClient, url_map_service: UrlMapService, api_version: QueryStringApiVersion) -> Result<super::service::OrderingServiceContext> {
@@ -62,7 +148,77 @@ pub async fn make_service(http_client: HttpClient, url_map_service: UrlMapServic
     let base_url = Url::parse(base_url)?;
     let base_url = base_url.join(ORDERING_SERVICE_BASE_URL)?;
 
+    // resilience settings are keyed like the url mapping, see `ResilienceConfig::from_env`
+    let http_client = ResilientClient::from_env(http_client, BASE_MAP_TO_PATH)?;
+
     Ok(super::service::OrderingServiceContext {
         service: Arc::new(OrderingServiceApi::new(http_client, base_url, api_version)),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Json, Router,
+        extract::Path,
+        http::{HeaderMap, header},
+        routing::{get, put},
+    };
+
+    /// An ordering-api with order 1 of `alice` and order 2 of `bob`, that like the real one returns and cancels any
+    /// order by its number.
+    async fn ordering_api() -> Url {
+        fn order(order_number: usize) -> Order {
+            Order { order_number, ..Order::new() }
+        }
+        let orders = |headers: HeaderMap| async move {
+            let numbers = match headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()) {
+                Some("Bearer alice") => vec![1],
+                Some("Bearer bob") => vec![2],
+                _ => vec![],
+            };
+            Json(numbers.into_iter().map(order).collect::<Vec<_>>())
+        };
+        let order_details = |Path(order_number): Path<usize>| async move {
+            Json(OrderDetails {
+                order_number,
+                date: chrono::DateTime::default(),
+                status: "Submitted".to_string(),
+                description: None,
+                street: None,
+                city: None,
+                state: None,
+                zipcode: None,
+                country: None,
+                order_items: vec![],
+                total: Default::default(),
+            })
+        };
+        let app = Router::new().route("/api/orders/", get(orders)).route("/api/orders/cancel", put(|| async {})).route("/api/orders/{order_number}", get(order_details));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Url::parse(&format!("http://{addr}/{ORDERING_SERVICE_BASE_URL}")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_order_of_another_buyer() {
+        let http_client = ResilientClient::new(HttpClient::new(), BASE_MAP_TO_PATH, Default::default());
+        let service = OrderingServiceApi::new(http_client, ordering_api().await, QueryStringApiVersion::from(1));
+
+        assert_eq!(service.order("alice", 1).await.unwrap().map(|x| x.order_number), Some(1));
+        assert_eq!(service.order("alice", 2).await.unwrap(), None);
+        assert_eq!(service.order("alice", -1).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_order_of_another_buyer() {
+        let http_client = ResilientClient::new(HttpClient::new(), BASE_MAP_TO_PATH, Default::default());
+        let service = OrderingServiceApi::new(http_client, ordering_api().await, QueryStringApiVersion::from(1));
+
+        assert_eq!(service.cancel("alice", 1, Uuid::new_v4()).await, Ok(()));
+        assert_eq!(service.cancel("alice", 2, Uuid::new_v4()).await, Err(crate::AppError::Forbidden));
+    }
+}
@@ -1,8 +1,11 @@
 use anyhow::Result;
-use leptos::server;
+use leptos::{
+    server,
+    server_fn::{BoxedStream, ServerFnError, Websocket, codec::JsonEncoding},
+};
 use uuid::Uuid;
 
-use super::types::Order;
+use super::types::{CardType, OrderDetails, OrderFilter, OrderResult, OrderStatusChange};
 
 #[cfg(feature = "ssr")]
 use super::service::OrderingServiceContext;
@@ -13,12 +16,72 @@ fn use_ordering_service_context() -> Result<OrderingServiceContext, crate::AppEr
     use_context::<OrderingServiceContext>().ok_or(crate::AppError::ServerFnError(ServerFnErrorErr::ServerError("OrderingServiceContext not in context".to_string())))
 }
 
+/// The page `page_index` of the orders of the signed in user selected by `filter`, the ordering-api only returns all.
 #[server]
 #[middleware(auth::RequireAuth)]
+pub async fn get_orders(page_index: usize, page_size: usize, filter: OrderFilter) -> Result<OrderResult, crate::AppError> {
+    let ctx = use_ordering_service_context()?;
+    Ok(OrderResult::page(filter.apply(ctx.service.get_orders().await?), page_index, page_size))
+}
+
+/// All orders selected by `filter` as CSV, see `orders_to_csv`.
+#[server]
+#[middleware(auth::RequireAuth)]
+pub async fn export_orders_csv(filter: OrderFilter) -> Result<String, crate::AppError> {
+    let ctx = use_ordering_service_context()?;
+    Ok(super::types::orders_to_csv(&filter.apply(ctx.service.get_orders().await?)))
+}
+
+#[server]
+#[middleware(auth::RequireAuth)]
+pub async fn get_order(order_id: i32) -> Result<Option<OrderDetails>, crate::AppError> {
+    let ctx = use_ordering_service_context()?;
+    ctx.service.get_order(order_id).await
+}
+
+/// Status changes of all orders of the signed in user while the socket is open, the input only keeps it open.
+#[server(protocol = Websocket<JsonEncoding, JsonEncoding>)]
+#[middleware(auth::RequireAuth)]
+pub async fn order_status_notify(input: BoxedStream<bool, ServerFnError>) -> Result<BoxedStream<OrderStatusChange, ServerFnError>, ServerFnError> {
+    use app_events::eg_by_id_filter;
+    use futures::{SinkExt, StreamExt, channel::mpsc};
+    use leptos_axum::extract;
+
+    let extensions: axum::http::Extensions = extract().await?;
+    let user_id = auth::server::get_user_ref_from_extensions(&extensions)?.sub.clone();
+    let mut input = input;
+
+    let (mut tx, rx) = mpsc::channel::<Result<OrderStatusChange, ServerFnError>>(8);
+
+    tokio::spawn(async move {
+        let (mut rx_evt, mut unsubscribe) = eg_by_id_filter::register_group(Some(user_id)).await;
+        loop {
+            tokio::select! {
+                event = rx_evt.recv() => {
+                    let Some(event) = event else { break };
+                    let change = OrderStatusChange { order_id: event.order_id, status: event.order_status, at: event.at };
+                    if tx.send(Ok(change)).await.is_err() {
+                        break;
+                    }
+                }
+                message = input.next() => {
+                    if message.is_none() {
+                        break;
+                    }
+                }
+            }
+        }
+        unsubscribe.unsubscribe().await;
+    });
 
-pub async fn get_orders() -> Result<Vec<Order>, crate::AppError> {
+    Ok(rx.into())
+}
+
+#[server]
+#[middleware(auth::RequireAuth)]
+pub async fn get_card_types() -> Result<Vec<CardType>, crate::AppError> {
     let ctx = use_ordering_service_context()?;
-    ctx.service.get_orders().await
+    ctx.service.get_card_types().await
 }
 
 use crate::basket_state::types::CreateOrderRequest;
@@ -28,3 +91,10 @@ pub async fn create_order(request: CreateOrderRequest, request_id: Uuid) -> Resu
     let ctx = use_ordering_service_context()?;
     ctx.service.create_order(request, request_id).await
 }
+
+#[server]
+#[middleware(auth::RequireAuth)]
+pub async fn cancel_order(order_number: i32, request_id: Uuid) -> Result<(), crate::AppError> {
+    let ctx = use_ordering_service_context()?;
+    ctx.service.cancel_order(order_number, request_id).await
+}
@@ -5,7 +5,7 @@ use async_trait::async_trait;
 use anyhow::Result;
 use uuid::Uuid;
 
-use crate::ordering::types::Order;
+use crate::ordering::types::{CardType, CreateOrderDraftRequest, Order, OrderDetails, OrderDraft};
 
 use crate::basket_state::types::CreateOrderRequest;
 
@@ -13,7 +13,18 @@ use crate::basket_state::types::CreateOrderRequest;
 pub trait OrderingService: Send + Sync {
     async fn get_orders(&self) -> Result<Vec<Order>, crate::AppError>;
 
+    /// `None` if the order does not exist or belongs to another buyer.
+    async fn get_order(&self, order_id: i32) -> Result<Option<OrderDetails>, crate::AppError>;
+
     async fn create_order(&self, request: CreateOrderRequest, request_id: Uuid) -> Result<(), crate::AppError>;
+
+    async fn get_card_types(&self) -> Result<Vec<CardType>, crate::AppError>;
+
+    async fn create_order_draft(&self, request: CreateOrderDraftRequest) -> Result<OrderDraft, crate::AppError>;
+
+    /// A repeated `request_id` is a no-op, the ordering-api keeps the ids of the commands it handled. `Forbidden` for
+    /// an order of another buyer.
+    async fn cancel_order(&self, order_number: i32, request_id: Uuid) -> Result<(), crate::AppError>;
 }
 
 #[derive(Clone)]
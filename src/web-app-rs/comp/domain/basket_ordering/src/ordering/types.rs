@@ -1,6 +1,8 @@
 use rust_decimal::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::basket_state::types::BasketItem;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Order {
@@ -20,3 +22,320 @@ impl Order {
         }
     }
 }
+
+/// Order of the order history, the ordering-api returns the orders of the user unsorted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderSort {
+    Newest,
+    Oldest,
+    TotalDesc,
+    TotalAsc,
+}
+
+impl OrderSort {
+    pub const ALL: [OrderSort; 4] = [OrderSort::Newest, OrderSort::Oldest, OrderSort::TotalDesc, OrderSort::TotalAsc];
+
+    /// The value of the `sort` query parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderSort::Newest => "newest",
+            OrderSort::Oldest => "oldest",
+            OrderSort::TotalDesc => "total_desc",
+            OrderSort::TotalAsc => "total_asc",
+        }
+    }
+
+    fn sort(&self, orders: &mut [Order]) {
+        let newest = |a: &Order, b: &Order| b.date.cmp(&a.date).then_with(|| b.order_number.cmp(&a.order_number));
+        match self {
+            OrderSort::Newest => orders.sort_by(newest),
+            OrderSort::Oldest => orders.sort_by(|a, b| newest(b, a)),
+            OrderSort::TotalDesc => orders.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| newest(a, b))),
+            OrderSort::TotalAsc => orders.sort_by(|a, b| a.total.cmp(&b.total).then_with(|| newest(a, b))),
+        }
+    }
+}
+
+impl std::str::FromStr for OrderSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OrderSort::ALL.into_iter().find(|x| x.as_str() == s).ok_or_else(|| format!("unknown sort order {s}"))
+    }
+}
+
+/// A selection of the order history, `from` and `to` are days in UTC and both included.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderFilter {
+    pub status: Option<String>,
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+    #[serde(default)]
+    pub sort: Option<OrderSort>,
+}
+
+impl OrderFilter {
+    fn matches(&self, order: &Order) -> bool {
+        let day = order.date.date_naive();
+        self.status.as_ref().is_none_or(|status| *status == order.status) && self.from.is_none_or(|from| day >= from) && self.to.is_none_or(|to| day <= to)
+    }
+
+    /// The matching orders, newest first unless `sort` says otherwise.
+    pub fn apply(&self, orders: Vec<Order>) -> Vec<Order> {
+        let mut orders = orders.into_iter().filter(|x| self.matches(x)).collect::<Vec<_>>();
+        self.sort.unwrap_or(OrderSort::Newest).sort(&mut orders);
+        orders
+    }
+}
+
+/// One page of the order history.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderResult {
+    pub page_index: usize,
+    pub page_size: usize,
+    pub count: usize,
+    pub data: Vec<Order>,
+}
+
+/// Larger pages of the order history are cut to this size.
+pub const MAX_ORDERS_PAGE_SIZE: usize = 100;
+
+impl OrderResult {
+    /// The page `page_index` of `orders`, `page_size` between 1 and `MAX_ORDERS_PAGE_SIZE`.
+    pub fn page(orders: Vec<Order>, page_index: usize, page_size: usize) -> Self {
+        let page_size = page_size.clamp(1, MAX_ORDERS_PAGE_SIZE);
+        OrderResult {
+            page_index,
+            page_size,
+            count: orders.len(),
+            data: orders.into_iter().skip(page_index.saturating_mul(page_size)).take(page_size).collect(),
+        }
+    }
+}
+
+/// The orders as `Number,Date,Status,Total` rows with a header, the date in RFC 3339.
+pub fn orders_to_csv(orders: &[Order]) -> String {
+    let field = |value: String| {
+        if value.contains([',', '"', '\n', '\r']) { format!("\"{}\"", value.replace('"', "\"\"")) } else { value }
+    };
+    let mut csv = String::from("Number,Date,Status,Total\r\n");
+    for order in orders {
+        let row = [order.order_number.to_string(), order.date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true), order.status.clone(), format!("{:.2}", order.total)];
+        csv.push_str(&row.map(field).join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderItem {
+    pub product_name: String,
+    pub units: i32,
+    pub unit_price: Decimal,
+    pub picture_url: Option<String>,
+}
+
+/// `GET /api/orders/{orderId}` of the ordering-api.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderDetails {
+    pub order_number: usize,
+    pub date: chrono::DateTime<chrono::Utc>,
+    pub status: String,
+    pub description: Option<String>,
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub zipcode: Option<String>,
+    pub country: Option<String>,
+    pub order_items: Vec<OrderItem>,
+    pub total: Decimal,
+}
+
+/// `GET /api/orders/cardtypes` of the ordering-api.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardType {
+    pub id: i32,
+    pub name: String,
+}
+
+/// Body of `POST /api/orders/draft` of the ordering-api.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrderDraftRequest {
+    pub buyer_id: String,
+    pub items: Vec<BasketItem>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderDraftItem {
+    pub product_id: i32,
+    pub product_name: String,
+    pub unit_price: Decimal,
+    pub discount: Decimal,
+    pub units: i32,
+    pub picture_url: Option<String>,
+}
+
+/// The basket priced by the ordering-api as it would be ordered, nothing is stored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderDraft {
+    pub order_items: Vec<OrderDraftItem>,
+    pub total: Decimal,
+}
+
+/// An `OrderStatusChangedTo*` event of one of the orders of the signed in user.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderStatusChange {
+    pub order_id: i32,
+    pub status: String,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+/// The statuses an order goes through in this order, `Cancelled` may end it after any of them but `Shipped`.
+pub const ORDER_STATUS_STEPS: [&str; 5] = ["Submitted", "AwaitingValidation", "StockConfirmed", "Paid", "Shipped"];
+pub const ORDER_STATUS_CANCELLED: &str = "Cancelled";
+
+/// The ordering-api refuses to cancel an order once it is paid.
+pub const ORDER_STATUS_CANCELLABLE: [&str; 3] = ["Submitted", "AwaitingValidation", "StockConfirmed"];
+
+pub fn is_cancellable(status: &str) -> bool {
+    ORDER_STATUS_CANCELLABLE.contains(&status)
+}
+
+/// Body of `PUT /api/orders/cancel` of the ordering-api.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrderRequest {
+    pub order_number: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderTimelineStep {
+    pub status: String,
+    pub reached: bool,
+    /// Only known for the changes seen while the page was open.
+    pub at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// The steps up to `Shipped`, or up to the cancellation for a cancelled order. `changes` are the live events of
+/// this order, the newest status wins over `status` of the order loaded before.
+pub fn order_timeline(status: &str, changes: &[OrderStatusChange]) -> Vec<OrderTimelineStep> {
+    let at = |status: &str| changes.iter().filter(|c| c.status == status).map(|c| c.at).max();
+    let current = changes.iter().max_by_key(|c| c.at).map(|c| c.status.as_str()).unwrap_or(status);
+
+    if current == ORDER_STATUS_CANCELLED {
+        // the steps passed before the cancellation are not known, only the ones seen live
+        let mut steps = ORDER_STATUS_STEPS
+            .iter()
+            .filter_map(|s| {
+                at(s).map(|at| OrderTimelineStep {
+                    status: s.to_string(),
+                    reached: true,
+                    at: Some(at),
+                })
+            })
+            .collect::<Vec<_>>();
+        steps.push(OrderTimelineStep {
+            status: ORDER_STATUS_CANCELLED.to_string(),
+            reached: true,
+            at: at(ORDER_STATUS_CANCELLED),
+        });
+        return steps;
+    }
+
+    let reached = ORDER_STATUS_STEPS.iter().position(|s| *s == current);
+    ORDER_STATUS_STEPS
+        .iter()
+        .enumerate()
+        .map(|(i, s)| OrderTimelineStep {
+            status: s.to_string(),
+            reached: reached.is_some_and(|reached| i <= reached),
+            at: at(s),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(status: &str, secs: i64) -> OrderStatusChange {
+        OrderStatusChange {
+            order_id: 1,
+            status: status.to_string(),
+            at: chrono::DateTime::from_timestamp(secs, 0).unwrap(),
+        }
+    }
+
+    fn order(order_number: usize, day: u32, status: &str, total: i64) -> Order {
+        Order {
+            order_number,
+            date: chrono::NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc(),
+            status: status.to_string(),
+            total: Decimal::from(total),
+        }
+    }
+
+    #[test]
+    fn test_order_filter() {
+        let orders = vec![order(1, 1, "Shipped", 30), order(2, 5, "Paid", 10), order(3, 9, "Shipped", 20)];
+        let numbers = |filter: OrderFilter| filter.apply(orders.clone()).into_iter().map(|x| x.order_number).collect::<Vec<_>>();
+
+        assert_eq!(numbers(OrderFilter::default()), vec![3, 2, 1]);
+        assert_eq!(
+            numbers(OrderFilter {
+                status: Some("Shipped".to_string()),
+                sort: Some(OrderSort::TotalAsc),
+                ..Default::default()
+            }),
+            vec![3, 1]
+        );
+        let (from, to) = (chrono::NaiveDate::from_ymd_opt(2026, 10, 5), chrono::NaiveDate::from_ymd_opt(2026, 10, 9));
+        assert_eq!(
+            numbers(OrderFilter {
+                from,
+                to,
+                sort: Some(OrderSort::Oldest),
+                ..Default::default()
+            }),
+            vec![2, 3]
+        );
+
+        let csv = orders_to_csv(&[Order {
+            status: "Cancelled, \"late\"".to_string(),
+            ..order(4, 2, "", 5)
+        }]);
+        assert_eq!(csv, "Number,Date,Status,Total\r\n4,2026-10-02T12:00:00Z,\"Cancelled, \"\"late\"\"\",5.00\r\n");
+    }
+
+    #[test]
+    fn test_order_timeline() {
+        let steps = order_timeline("AwaitingValidation", &[change("StockConfirmed", 10)]);
+        assert_eq!(steps.iter().map(|s| s.reached).collect::<Vec<_>>(), vec![true, true, true, false, false]);
+        assert_eq!(steps[2].at, Some(change("", 10).at));
+        assert_eq!(steps[1].at, None);
+
+        let steps = order_timeline("Paid", &[change("StockConfirmed", 10), change("Cancelled", 20)]);
+        assert_eq!(steps.iter().map(|s| s.status.as_str()).collect::<Vec<_>>(), vec!["StockConfirmed", "Cancelled"]);
+    }
+
+    #[test]
+    fn test_order_page() {
+        let orders = (1..=150).map(|i| order(i, 1, "Paid", 1)).collect::<Vec<_>>();
+        let numbers = |r: OrderResult| (r.page_size, r.count, r.data.first().map(|x| x.order_number), r.data.len());
+
+        assert_eq!(numbers(OrderResult::page(orders.clone(), 1, 10)), (10, 150, Some(11), 10));
+        assert_eq!(numbers(OrderResult::page(orders.clone(), 0, 0)), (1, 150, Some(1), 1));
+        assert_eq!(numbers(OrderResult::page(orders.clone(), 0, usize::MAX)), (MAX_ORDERS_PAGE_SIZE, 150, Some(1), 100));
+        assert_eq!(numbers(OrderResult::page(orders, usize::MAX, 10)), (10, 150, None, 0));
+    }
+}
@@ -0,0 +1,10 @@
+//! Database of the tests of the services stored in the web app database.
+
+use sqlx::SqlitePool;
+
+/// An empty in-memory database with the migrations of the web app server, which owns the schema.
+pub(crate) async fn migrated_db() -> SqlitePool {
+    let db = SqlitePool::connect(":memory:").await.unwrap();
+    sqlx::migrate!("../../../web-app-ws/server/migrations").run(&db).await.unwrap();
+    db
+}
@@ -0,0 +1,222 @@
+//! Wishlist kept in the `wishlist_items` table of the web app database, next to `users`.
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use axum::http::Extensions;
+use leptos_axum::extract;
+use sqlx::SqlitePool;
+
+use crate::basket_state::service::BasketStateServiceContext;
+use crate::catalog::service::CatalogServiceContext;
+use crate::catalog::types::CatalogItem;
+use crate::wishlist::service::{WishlistService, WishlistServiceContext};
+use crate::wishlist::types::WishlistItem;
+
+struct WishlistServiceApi {
+    db: SqlitePool,
+    catalog_service: CatalogServiceContext,
+    basket_state_service: BasketStateServiceContext,
+}
+
+fn sqlx_err(e: sqlx::Error) -> crate::AppError {
+    crate::AppError::Other(format!("wishlist storage error {e}"))
+}
+
+async fn user_sub() -> Result<String, crate::AppError> {
+    let extensions: Extensions = extract().await?;
+    Ok(auth::server::get_user_ref_from_extensions(&extensions)?.sub.clone())
+}
+
+impl WishlistServiceApi {
+    async fn get_catalog_item(&self, product_id: i32) -> Result<CatalogItem, crate::AppError> {
+        let items = self.catalog_service.service.get_catalog_items_by_ids(vec![product_id]).await.map_err(|e| crate::AppError::Other(e.to_string()))?;
+        items.into_iter().next().ok_or_else(|| crate::AppError::Other(format!("Catalog item not found {product_id}")))
+    }
+
+    async fn insert(&self, user_sub: &str, product_id: i32) -> Result<bool, crate::AppError> {
+        let rslt = sqlx::query("insert into wishlist_items (user_sub, product_id, added_at) values (?, ?, ?) on conflict(user_sub, product_id) do nothing")
+            .bind(user_sub)
+            .bind(product_id)
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.db)
+            .await
+            .map_err(sqlx_err)?;
+        Ok(rslt.rows_affected() > 0)
+    }
+
+    async fn delete(&self, user_sub: &str, product_id: i32) -> Result<bool, crate::AppError> {
+        let rslt = sqlx::query("delete from wishlist_items where user_sub = ? and product_id = ?")
+            .bind(user_sub)
+            .bind(product_id)
+            .execute(&self.db)
+            .await
+            .map_err(sqlx_err)?;
+        Ok(rslt.rows_affected() > 0)
+    }
+
+    async fn items_of(&self, user_sub: &str) -> Result<Vec<WishlistItem>, crate::AppError> {
+        let entries: Vec<(i32, i64)> = sqlx::query_as("select product_id, added_at from wishlist_items where user_sub = ? order by added_at desc, rowid desc")
+            .bind(user_sub)
+            .fetch_all(&self.db)
+            .await
+            .map_err(sqlx_err)?;
+        if entries.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // prices come from the catalog, the wishlist only remembers the products
+        let product_ids = entries.iter().map(|(product_id, _)| *product_id).collect::<Vec<_>>();
+        let catalog_items_v = self.catalog_service.service.get_catalog_items_by_ids(product_ids).await.map_err(|e| crate::AppError::Other(e.to_string()))?;
+        let catalog_items: HashMap<i32, &CatalogItem> = catalog_items_v.iter().map(|x| (x.id, x)).collect();
+
+        // products removed from the catalog are left out
+        Ok(entries
+            .into_iter()
+            .filter_map(|(product_id, added_at)| {
+                let catalog_item = catalog_items.get(&product_id)?;
+                Some(WishlistItem {
+                    product_id,
+                    product_name: catalog_item.name.clone(),
+                    unit_price: catalog_item.price,
+                    added_at: chrono::DateTime::from_timestamp(added_at, 0).unwrap_or_default(),
+                })
+            })
+            .collect())
+    }
+
+    async fn add(&self, user_sub: &str, product_id: i32) -> Result<bool, crate::AppError> {
+        self.get_catalog_item(product_id).await?;
+        self.insert(user_sub, product_id).await
+    }
+
+    async fn move_to_basket_of(&self, user_sub: &str, product_id: i32) -> Result<(), crate::AppError> {
+        let item = self.get_catalog_item(product_id).await?;
+        self.basket_state_service.service.add_basket_item(item).await?;
+        self.delete(user_sub, product_id).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WishlistService for WishlistServiceApi {
+    async fn get_wishlist_items(&self) -> Result<Vec<WishlistItem>, crate::AppError> {
+        self.items_of(&user_sub().await?).await
+    }
+
+    async fn add_wishlist_item(&self, product_id: i32) -> Result<bool, crate::AppError> {
+        self.add(&user_sub().await?, product_id).await
+    }
+
+    async fn remove_wishlist_item(&self, product_id: i32) -> Result<bool, crate::AppError> {
+        self.delete(&user_sub().await?, product_id).await
+    }
+
+    async fn move_to_wishlist(&self, product_id: i32) -> Result<(), crate::AppError> {
+        self.insert(&user_sub().await?, product_id).await?;
+        self.basket_state_service.service.set_quantity(product_id, 0).await?;
+        Ok(())
+    }
+
+    async fn move_to_basket(&self, product_id: i32) -> Result<(), crate::AppError> {
+        self.move_to_basket_of(&user_sub().await?, product_id).await
+    }
+}
+
+/// `db` must have the `wishlist_items` table of the web app migrations.
+pub fn make_service(db: SqlitePool, catalog_service: CatalogServiceContext, basket_state_service: BasketStateServiceContext) -> WishlistServiceContext {
+    WishlistServiceContext {
+        service: Arc::new(WishlistServiceApi { db, catalog_service, basket_state_service }),
+    }
+}
+
+#[cfg(all(test, feature = "mock-backends"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::basket_state::service::{BasketCheckoutInfo, BasketStateService};
+    use crate::basket_state::types::BasketItem;
+    use crate::ordering::types::OrderDraft;
+
+    /// Remembers the products added to the basket.
+    #[derive(Default)]
+    struct RecordingBasketState {
+        added: Mutex<Vec<i32>>,
+    }
+
+    #[async_trait]
+    impl BasketStateService for RecordingBasketState {
+        async fn get_basket_items(&self) -> Result<Vec<BasketItem>, crate::AppError> {
+            Ok(vec![])
+        }
+        async fn add_basket_item(&self, item: CatalogItem) -> Result<(), crate::AppError> {
+            self.added.lock().unwrap().push(item.id);
+            Ok(())
+        }
+        async fn set_quantity(&self, _product_id: i32, _quantity: i32) -> Result<bool, crate::AppError> {
+            Ok(true)
+        }
+        async fn get_order_draft(&self) -> Result<OrderDraft, crate::AppError> {
+            Err(crate::AppError::Other("no orders in the wishlist tests".to_string()))
+        }
+        async fn checkout(&self, _checkout_info: BasketCheckoutInfo) -> Result<(), crate::AppError> {
+            Err(crate::AppError::Other("no orders in the wishlist tests".to_string()))
+        }
+    }
+
+    async fn make_api() -> (WishlistServiceApi, Arc<RecordingBasketState>) {
+        let db = crate::test_db::migrated_db().await;
+
+        let basket_state = Arc::new(RecordingBasketState::default());
+        let api = WishlistServiceApi {
+            db,
+            catalog_service: CatalogServiceContext {
+                service: Arc::new(crate::catalog::mock::MockCatalogService::from_json(crate::catalog::mock::SAMPLE_FIXTURE).unwrap()),
+            },
+            basket_state_service: BasketStateServiceContext { service: basket_state.clone() },
+        };
+        (api, basket_state)
+    }
+
+    fn product_ids(items: &[WishlistItem]) -> Vec<i32> {
+        items.iter().map(|x| x.product_id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_add_remove_and_move_to_basket() {
+        let (api, basket_state) = make_api().await;
+
+        assert!(api.add("alice", 1).await.unwrap());
+        assert!(api.add("alice", 2).await.unwrap());
+        assert!(!api.add("alice", 1).await.unwrap());
+        assert!(api.add("alice", 4).await.is_err());
+
+        let items = api.items_of("alice").await.unwrap();
+        assert_eq!(product_ids(&items), vec![2, 1]);
+        assert_eq!(items[0].product_name, "Summit Pro Harness");
+
+        assert!(api.delete("alice", 2).await.unwrap());
+        assert!(!api.delete("alice", 2).await.unwrap());
+        assert_eq!(product_ids(&api.items_of("alice").await.unwrap()), vec![1]);
+
+        api.move_to_basket_of("alice", 1).await.unwrap();
+        assert_eq!(*basket_state.added.lock().unwrap(), vec![1]);
+        assert!(api.items_of("alice").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wishlists_are_per_user() {
+        let (api, _) = make_api().await;
+
+        api.add("alice", 1).await.unwrap();
+        api.add("bob", 2).await.unwrap();
+
+        assert_eq!(product_ids(&api.items_of("alice").await.unwrap()), vec![1]);
+        assert_eq!(product_ids(&api.items_of("bob").await.unwrap()), vec![2]);
+
+        // another user's item is neither seen nor removed
+        assert!(!api.delete("bob", 1).await.unwrap());
+        assert_eq!(product_ids(&api.items_of("alice").await.unwrap()), vec![1]);
+    }
+}
@@ -0,0 +1,49 @@
+use leptos::server;
+
+use super::types::WishlistItem;
+
+#[cfg(feature = "ssr")]
+use super::service::WishlistServiceContext;
+
+#[cfg(feature = "ssr")]
+use leptos::prelude::expect_context;
+
+#[server(prefix = "/api_wishlist")]
+#[middleware(auth::RequireAuth)]
+pub async fn get_wishlist_items() -> Result<Vec<WishlistItem>, crate::AppError> {
+    let context: WishlistServiceContext = expect_context();
+
+    context.service.get_wishlist_items().await
+}
+
+#[server(prefix = "/api_wishlist")]
+#[middleware(auth::RequireAuth)]
+pub async fn add_wishlist_item(product_id: i32) -> Result<bool, crate::AppError> {
+    let context: WishlistServiceContext = expect_context();
+
+    context.service.add_wishlist_item(product_id).await
+}
+
+#[server(prefix = "/api_wishlist")]
+#[middleware(auth::RequireAuth)]
+pub async fn remove_wishlist_item(product_id: i32) -> Result<bool, crate::AppError> {
+    let context: WishlistServiceContext = expect_context();
+
+    context.service.remove_wishlist_item(product_id).await
+}
+
+#[server(prefix = "/api_wishlist")]
+#[middleware(auth::RequireAuth)]
+pub async fn move_to_wishlist(product_id: i32) -> Result<(), crate::AppError> {
+    let context: WishlistServiceContext = expect_context();
+
+    context.service.move_to_wishlist(product_id).await
+}
+
+#[server(prefix = "/api_wishlist")]
+#[middleware(auth::RequireAuth)]
+pub async fn move_to_basket(product_id: i32) -> Result<(), crate::AppError> {
+    let context: WishlistServiceContext = expect_context();
+
+    context.service.move_to_basket(product_id).await
+}
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::types::WishlistItem;
+
+/// Products the signed in user saved for later, newest first.
+#[async_trait]
+pub trait WishlistService: Send + Sync {
+    async fn get_wishlist_items(&self) -> Result<Vec<WishlistItem>, crate::AppError>;
+
+    /// Saving a product twice keeps the first entry, `false` if it was already saved.
+    async fn add_wishlist_item(&self, product_id: i32) -> Result<bool, crate::AppError>;
+    async fn remove_wishlist_item(&self, product_id: i32) -> Result<bool, crate::AppError>;
+
+    /// Takes the product out of the basket, whatever its quantity, and saves it.
+    async fn move_to_wishlist(&self, product_id: i32) -> Result<(), crate::AppError>;
+    /// Adds one of the product to the basket and removes it from the wishlist.
+    async fn move_to_basket(&self, product_id: i32) -> Result<(), crate::AppError>;
+}
+
+#[derive(Clone)]
+pub struct WishlistServiceContext {
+    pub service: Arc<dyn WishlistService>,
+}
@@ -0,0 +1,12 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// A product saved for later, with the current price of the catalog.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WishlistItem {
+    pub product_id: i32,
+    pub product_name: String,
+    pub unit_price: Decimal,
+    pub added_at: chrono::DateTime<chrono::Utc>,
+}
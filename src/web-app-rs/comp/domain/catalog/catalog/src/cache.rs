@@ -0,0 +1,353 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::OnceCell;
+
+use app_events::integration_events::ProductPriceChanged;
+use rabbit_mq_bus::Dispatcherable;
+
+use crate::service::{CatalogResult, CatalogService, CatalogServiceContext};
+use crate::types::{CatalogBrand, CatalogItem, CatalogItemType};
+
+/// Expired entries are swept once a cache holds more keys than this.
+const SWEEP_THRESHOLD: usize = 1024;
+
+#[derive(Clone, Debug)]
+pub struct CatalogCacheConfig {
+    /// Item pages, single items, items by ids, related items and search results. Invalidated on `ProductPriceChanged`.
+    pub items_ttl: Duration,
+    pub brands_ttl: Duration,
+    pub types_ttl: Duration,
+}
+
+impl Default for CatalogCacheConfig {
+    fn default() -> Self {
+        Self {
+            items_ttl: Duration::from_secs(30),
+            brands_ttl: Duration::from_secs(300),
+            types_ttl: Duration::from_secs(300),
+        }
+    }
+}
+
+impl CatalogCacheConfig {
+    /// Reads the TTLs in seconds from `CATALOG_CACHE_ITEMS_TTL`, `CATALOG_CACHE_BRANDS_TTL` and `CATALOG_CACHE_TYPES_TTL`;
+    /// unset variables keep the default, 0 disables caching for that method group.
+    pub fn from_env() -> Result<Self> {
+        let ttl_from_env = |name: &str, default: Duration| -> Result<Duration> {
+            match std::env::var(name) {
+                Ok(v) => Ok(Duration::from_secs(v.trim().parse::<u64>().map_err(|e| anyhow::anyhow!("{name}: {e}"))?)),
+                Err(_) => Ok(default),
+            }
+        };
+
+        let default = Self::default();
+        Ok(Self {
+            items_ttl: ttl_from_env("CATALOG_CACHE_ITEMS_TTL", default.items_ttl)?,
+            brands_ttl: ttl_from_env("CATALOG_CACHE_BRANDS_TTL", default.brands_ttl)?,
+            types_ttl: ttl_from_env("CATALOG_CACHE_TYPES_TTL", default.types_ttl)?,
+        })
+    }
+}
+
+type Slot<V> = Arc<OnceCell<(V, Instant)>>;
+
+struct TtlCache<K, V> {
+    ttl: Duration,
+    slots: Mutex<HashMap<K, Slot<V>>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    fn new(ttl: Duration) -> Self {
+        Self { ttl, slots: Mutex::new(HashMap::new()) }
+    }
+
+    fn is_expired(&self, slot: &Slot<V>) -> bool {
+        slot.get().is_some_and(|(_, fetched_at)| fetched_at.elapsed() >= self.ttl)
+    }
+
+    /// Concurrent calls for the same key share one `fetch`; a failed fetch is not cached.
+    async fn get_or_fetch<F, Fut>(&self, key: K, fetch: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V>>,
+    {
+        if self.ttl.is_zero() {
+            return fetch().await;
+        }
+
+        let slot = {
+            let mut slots = self.slots.lock().unwrap();
+            if slots.len() > SWEEP_THRESHOLD {
+                slots.retain(|_, slot| !self.is_expired(slot));
+            }
+            if slots.get(&key).is_some_and(|slot| self.is_expired(slot)) {
+                slots.remove(&key);
+            }
+            slots.entry(key).or_default().clone()
+        };
+
+        let (value, _) = slot.get_or_try_init(|| async { fetch().await.map(|value| (value, Instant::now())) }).await?;
+        Ok(value.clone())
+    }
+
+    fn clear(&self) {
+        // fetches in flight complete into the dropped slots, later calls start over
+        self.slots.lock().unwrap().clear();
+    }
+}
+
+/// `CatalogService` decorator caching the read methods of the inner service.
+/// Mutations are passed through and invalidate the item caches.
+pub struct CachedCatalogService {
+    inner: Arc<dyn CatalogService>,
+    items: TtlCache<(usize, usize, Option<usize>, Option<usize>), CatalogResult>,
+    item: TtlCache<usize, Option<CatalogItem>>,
+    items_by_ids: TtlCache<Vec<i32>, Vec<CatalogItem>>,
+    related: TtlCache<(usize, usize), Vec<CatalogItem>>,
+    by_name: TtlCache<(String, usize, usize), CatalogResult>,
+    semantic: TtlCache<(String, usize, usize), CatalogResult>,
+    brands: TtlCache<(), Vec<CatalogBrand>>,
+    types: TtlCache<(), Vec<CatalogItemType>>,
+}
+
+impl CachedCatalogService {
+    pub fn new(inner: Arc<dyn CatalogService>, config: CatalogCacheConfig) -> Self {
+        Self {
+            inner,
+            items: TtlCache::new(config.items_ttl),
+            item: TtlCache::new(config.items_ttl),
+            items_by_ids: TtlCache::new(config.items_ttl),
+            related: TtlCache::new(config.items_ttl),
+            by_name: TtlCache::new(config.items_ttl),
+            semantic: TtlCache::new(config.items_ttl),
+            brands: TtlCache::new(config.brands_ttl),
+            types: TtlCache::new(config.types_ttl),
+        }
+    }
+
+    pub fn invalidate_items(&self) {
+        self.items.clear();
+        self.item.clear();
+        self.items_by_ids.clear();
+        self.related.clear();
+        self.by_name.clear();
+        self.semantic.clear();
+    }
+}
+
+#[async_trait]
+impl CatalogService for CachedCatalogService {
+    async fn get_catalog_items(&self, page_index: usize, page_size: usize, brand: Option<usize>, type_id: Option<usize>) -> Result<CatalogResult> {
+        self.items.get_or_fetch((page_index, page_size, brand, type_id), || self.inner.get_catalog_items(page_index, page_size, brand, type_id)).await
+    }
+
+    async fn get_brands(&self) -> Result<Vec<CatalogBrand>> {
+        self.brands.get_or_fetch((), || self.inner.get_brands()).await
+    }
+
+    async fn get_types(&self) -> Result<Vec<CatalogItemType>> {
+        self.types.get_or_fetch((), || self.inner.get_types()).await
+    }
+
+    async fn get_catalog_item(&self, item_id: usize) -> Result<Option<CatalogItem>> {
+        self.item.get_or_fetch(item_id, || self.inner.get_catalog_item(item_id)).await
+    }
+
+    async fn get_catalog_items_by_ids(&self, item_ids: Vec<i32>) -> Result<Vec<CatalogItem>> {
+        self.items_by_ids.get_or_fetch(item_ids.clone(), || self.inner.get_catalog_items_by_ids(item_ids)).await
+    }
+
+    async fn get_related_items(&self, item_id: usize, count: usize) -> Result<Vec<CatalogItem>> {
+        self.related.get_or_fetch((item_id, count), || self.inner.get_related_items(item_id, count)).await
+    }
+
+    async fn search_by_name(&self, name: &str, page_index: usize, page_size: usize) -> Result<CatalogResult> {
+        self.by_name.get_or_fetch((name.to_string(), page_index, page_size), || self.inner.search_by_name(name, page_index, page_size)).await
+    }
+
+    async fn search_semantic(&self, text: &str, page_index: usize, page_size: usize) -> Result<CatalogResult> {
+        self.semantic.get_or_fetch((text.to_string(), page_index, page_size), || self.inner.search_semantic(text, page_index, page_size)).await
+    }
+
+    async fn update_catalog_item(&self, item: CatalogItem) -> Result<()> {
+        let r = self.inner.update_catalog_item(item).await;
+        self.invalidate_items();
+        r
+    }
+
+    async fn create_catalog_item(&self, item: CatalogItem) -> Result<()> {
+        let r = self.inner.create_catalog_item(item).await;
+        self.invalidate_items();
+        r
+    }
+
+    async fn delete_catalog_item(&self, item_id: usize) -> Result<bool> {
+        let r = self.inner.delete_catalog_item(item_id).await;
+        self.invalidate_items();
+        r
+    }
+}
+
+async fn invalidate_on_price_changed(service: Weak<CachedCatalogService>) {
+    let (mut rx, mut unsubscribe) = ProductPriceChanged::dispatcher().read().await.add_channel(None).await;
+
+    tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            let Some(service) = service.upgrade() else {
+                break;
+            };
+            log::info!("catalog cache invalidated, price of product {} changed", event.product_id);
+            service.invalidate_items();
+        }
+        unsubscribe.unsubscribe().await;
+    });
+}
+
+/// Wraps the service of `context` in a [`CachedCatalogService`] that is invalidated by `ProductPriceChanged` events.
+pub async fn make_cached_service(context: CatalogServiceContext, config: CatalogCacheConfig) -> CatalogServiceContext {
+    let service = Arc::new(CachedCatalogService::new(context.service, config));
+    invalidate_on_price_changed(Arc::downgrade(&service)).await;
+    CatalogServiceContext { service }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use rabbit_mq_bus::Dispatchable;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct CountingCatalogService {
+        calls: AtomicUsize,
+    }
+
+    impl CountingCatalogService {
+        async fn count(&self) {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    #[async_trait]
+    impl CatalogService for CountingCatalogService {
+        async fn get_catalog_items(&self, page_index: usize, page_size: usize, _brand: Option<usize>, _type_id: Option<usize>) -> Result<CatalogResult> {
+            self.count().await;
+            Ok(CatalogResult { page_index, page_size, count: 0, data: vec![] })
+        }
+        async fn get_brands(&self) -> Result<Vec<CatalogBrand>> {
+            self.count().await;
+            Ok(vec![CatalogBrand::new()])
+        }
+        async fn get_types(&self) -> Result<Vec<CatalogItemType>> {
+            self.count().await;
+            Ok(vec![])
+        }
+        async fn get_catalog_item(&self, _item_id: usize) -> Result<Option<CatalogItem>> {
+            self.count().await;
+            Ok(Some(CatalogItem::new()))
+        }
+        async fn get_catalog_items_by_ids(&self, _item_ids: Vec<i32>) -> Result<Vec<CatalogItem>> {
+            self.count().await;
+            Ok(vec![])
+        }
+        async fn search_by_name(&self, _name: &str, page_index: usize, page_size: usize) -> Result<CatalogResult> {
+            self.count().await;
+            Ok(CatalogResult { page_index, page_size, count: 0, data: vec![] })
+        }
+        async fn search_semantic(&self, _text: &str, page_index: usize, page_size: usize) -> Result<CatalogResult> {
+            self.count().await;
+            Ok(CatalogResult { page_index, page_size, count: 0, data: vec![] })
+        }
+        async fn update_catalog_item(&self, _item: CatalogItem) -> Result<()> {
+            Ok(())
+        }
+        async fn create_catalog_item(&self, _item: CatalogItem) -> Result<()> {
+            Ok(())
+        }
+        async fn delete_catalog_item(&self, _item_id: usize) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    fn make_cached(config: CatalogCacheConfig) -> (Arc<CountingCatalogService>, CachedCatalogService) {
+        let inner = Arc::new(CountingCatalogService::default());
+        (inner.clone(), CachedCatalogService::new(inner, config))
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_calls_are_coalesced() {
+        let (inner, cached) = make_cached(CatalogCacheConfig::default());
+
+        let (a, b, c) = tokio::join!(cached.get_brands(), cached.get_brands(), cached.get_brands());
+        assert!(a.is_ok() && b.is_ok() && c.is_ok());
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+
+        cached.get_catalog_items(0, 9, None, None).await.unwrap();
+        cached.get_catalog_items(1, 9, None, None).await.unwrap();
+        cached.get_catalog_items(0, 9, None, None).await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_search_results_are_cached() {
+        let (inner, cached) = make_cached(CatalogCacheConfig::default());
+
+        cached.search_by_name("bike", 0, 9).await.unwrap();
+        cached.search_by_name("bike", 0, 9).await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+
+        // another page, text or kind of search is another entry
+        cached.search_by_name("bike", 1, 9).await.unwrap();
+        cached.search_by_name("helmet", 0, 9).await.unwrap();
+        cached.search_semantic("bike", 0, 9).await.unwrap();
+        cached.search_semantic("bike", 0, 9).await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 4);
+
+        cached.invalidate_items();
+        cached.search_semantic("bike", 0, 9).await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_ttl_expiry() {
+        let (inner, cached) = make_cached(CatalogCacheConfig {
+            types_ttl: Duration::from_millis(50),
+            ..Default::default()
+        });
+
+        cached.get_types().await.unwrap();
+        cached.get_types().await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        cached.get_types().await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_on_price_changed() {
+        let inner = Arc::new(CountingCatalogService::default());
+        let context = make_cached_service(CatalogServiceContext { service: inner.clone() }, CatalogCacheConfig::default()).await;
+
+        context.service.get_catalog_item(1).await.unwrap();
+        context.service.get_brands().await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+
+        ProductPriceChanged::dispatcher().read().await.dispatch(ProductPriceChanged::new()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        context.service.get_catalog_item(1).await.unwrap();
+        context.service.get_brands().await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+    }
+}
@@ -5,4 +5,10 @@ pub mod types;
 #[cfg(feature = "ssr")]
 pub mod server;
 
+#[cfg(feature = "ssr")]
+pub mod cache;
+
+#[cfg(feature = "mock-backends")]
+pub mod mock;
+
 pub(crate) use auth::AppError;
@@ -0,0 +1,276 @@
+//! In-memory `CatalogService` seeded from a catalog fixture in the format of the .NET
+//! `Catalog.API/Setup/catalog.json`, used by the `mock-backends` feature.
+
+use crate::service::{CatalogResult, CatalogService, CatalogServiceContext};
+use async_trait::async_trait;
+use std::sync::{Arc, RwLock};
+
+use anyhow::{Result, anyhow};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::types::{CatalogBrand, CatalogItem, CatalogItemType};
+
+pub const FIXTURE_ENV: &str = "CATALOG_MOCK_FIXTURE";
+const DEFAULT_FIXTURE: &str = "../../Catalog.API/Setup/catalog.json";
+
+/// Three items of two brands and two types, for the tests of the mock and of the services using it.
+pub const SAMPLE_FIXTURE: &str = r#"[
+    { "Id": 1, "Type": "Footwear", "Brand": "Daybird", "Name": "Wanderer Black Hiking Boots", "Description": "Waterproof leather boots", "Price": 109.99 },
+    { "Id": 2, "Type": "Climbing", "Brand": "Gravitator", "Name": "Summit Pro Harness", "Description": "Lightweight climbing harness", "Price": 89.99 },
+    { "Id": 3, "Type": "Footwear", "Brand": "Gravitator", "Name": "Alpine Trail Shoes", "Description": "Light shoes for the trail", "Price": 59.5 }
+]"#;
+
+/// One entry of `catalog.json`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CatalogSourceEntry {
+    id: i32,
+    #[serde(rename = "Type")]
+    type_name: String,
+    brand: String,
+    name: String,
+    description: String,
+    price: Decimal,
+}
+
+struct CatalogData {
+    items: Vec<CatalogItem>,
+    brands: Vec<CatalogBrand>,
+    types: Vec<CatalogItemType>,
+}
+
+pub struct MockCatalogService {
+    data: RwLock<CatalogData>,
+}
+
+fn id_of(names: &mut Vec<String>, name: &str) -> usize {
+    match names.iter().position(|x| x == name) {
+        Some(i) => i + 1,
+        None => {
+            names.push(name.to_string());
+            names.len()
+        }
+    }
+}
+
+impl MockCatalogService {
+    /// Brands and types get ids in order of first appearance, like the .NET seeding does.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let entries: Vec<CatalogSourceEntry> = serde_json::from_str(json)?;
+
+        let mut brand_names = Vec::<String>::new();
+        let mut type_names = Vec::<String>::new();
+
+        let mut items = entries
+            .into_iter()
+            .map(|e| CatalogItem {
+                id: e.id,
+                name: e.name,
+                description: e.description,
+                price: e.price,
+                picture_file_name: Some(format!("{}.webp", e.id)),
+                catalog_type_id: id_of(&mut type_names, &e.type_name),
+                catalog_brand_id: id_of(&mut brand_names, &e.brand),
+                available_stock: 100,
+                restock_threshold: 10,
+                max_stock_threshold: 200,
+                ..CatalogItem::new()
+            })
+            .collect::<Vec<_>>();
+
+        let brands = brand_names.into_iter().enumerate().map(|(i, brand)| CatalogBrand { id: i + 1, brand }).collect::<Vec<_>>();
+        let types = type_names.into_iter().enumerate().map(|(i, type_name)| CatalogItemType { id: i + 1, type_name }).collect::<Vec<_>>();
+
+        // the catalog-api includes the brand with single items, the mock includes brand and type everywhere
+        for item in items.iter_mut() {
+            item.catalog_brand = brands.get(item.catalog_brand_id - 1).cloned();
+            item.catalog_type = types.get(item.catalog_type_id - 1).cloned();
+        }
+
+        Ok(MockCatalogService {
+            data: RwLock::new(CatalogData { items, brands, types }),
+        })
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        let json = std::fs::read_to_string(path).map_err(|e| anyhow!("cannot read catalog fixture {path}: {e}"))?;
+        Self::from_json(&json)
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, CatalogData> {
+        self.data.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, CatalogData> {
+        self.data.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn page<F: Fn(&CatalogItem) -> bool>(&self, page_index: usize, page_size: usize, filter: F) -> CatalogResult {
+        let data = self.read();
+        let mut items = data.items.iter().filter(|x| filter(x)).cloned().collect::<Vec<_>>();
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        CatalogResult {
+            page_index,
+            page_size,
+            count: items.len(),
+            data: items.into_iter().skip(page_index.saturating_mul(page_size)).take(page_size).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl CatalogService for MockCatalogService {
+    async fn get_catalog_items(&self, page_index: usize, page_size: usize, brand: Option<usize>, type_id: Option<usize>) -> Result<CatalogResult> {
+        Ok(self.page(page_index, page_size, |x| brand.is_none_or(|b| x.catalog_brand_id == b) && type_id.is_none_or(|t| x.catalog_type_id == t)))
+    }
+
+    async fn get_brands(&self) -> Result<Vec<CatalogBrand>> {
+        Ok(self.read().brands.clone())
+    }
+
+    async fn get_types(&self) -> Result<Vec<CatalogItemType>> {
+        Ok(self.read().types.clone())
+    }
+
+    async fn get_catalog_item(&self, item_id: usize) -> Result<Option<CatalogItem>> {
+        Ok(self.read().items.iter().find(|x| x.id as usize == item_id).cloned())
+    }
+
+    async fn get_catalog_items_by_ids(&self, item_ids: Vec<i32>) -> Result<Vec<CatalogItem>> {
+        Ok(self.read().items.iter().filter(|x| item_ids.contains(&x.id)).cloned().collect())
+    }
+
+    async fn search_by_name(&self, name: &str, page_index: usize, page_size: usize) -> Result<CatalogResult> {
+        let name = name.to_lowercase();
+        Ok(self.page(page_index, page_size, |x| x.name.to_lowercase().starts_with(&name)))
+    }
+
+    /// There is no embedding model here, any word of the text matching name or description counts.
+    async fn search_semantic(&self, text: &str, page_index: usize, page_size: usize) -> Result<CatalogResult> {
+        let words = text.split_whitespace().map(|w| w.to_lowercase()).collect::<Vec<_>>();
+        Ok(self.page(page_index, page_size, |x| {
+            let haystack = format!("{} {}", x.name, x.description).to_lowercase();
+            words.iter().any(|w| haystack.contains(w))
+        }))
+    }
+
+    async fn update_catalog_item(&self, item: CatalogItem) -> Result<()> {
+        let mut data = self.write();
+        let existing = data.items.iter_mut().find(|x| x.id == item.id).ok_or_else(|| anyhow!("Catalog item {} not found", item.id))?;
+        *existing = item;
+        Ok(())
+    }
+
+    async fn create_catalog_item(&self, item: CatalogItem) -> Result<()> {
+        let mut data = self.write();
+        let id = if item.id > 0 { item.id } else { data.items.iter().map(|x| x.id).max().unwrap_or(0) + 1 };
+        if data.items.iter().any(|x| x.id == id) {
+            return Err(anyhow!("Catalog item {id} already exists"));
+        }
+        data.items.push(CatalogItem { id, ..item });
+        Ok(())
+    }
+
+    async fn delete_catalog_item(&self, item_id: usize) -> Result<bool> {
+        let mut data = self.write();
+        let len = data.items.len();
+        data.items.retain(|x| x.id as usize != item_id);
+        Ok(data.items.len() != len)
+    }
+}
+
+/// Reads the fixture from `CATALOG_MOCK_FIXTURE`, defaulting to the .NET seed file of this repository.
+pub fn make_service_from_env() -> Result<CatalogServiceContext> {
+    let path = std::env::var(FIXTURE_ENV).unwrap_or_else(|_| DEFAULT_FIXTURE.to_string());
+
+    Ok(CatalogServiceContext {
+        service: Arc::new(MockCatalogService::from_file(&path)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_seeded_from_fixture() {
+        let service = MockCatalogService::from_json(SAMPLE_FIXTURE).unwrap();
+
+        let brands = service.get_brands().await.unwrap();
+        assert_eq!(brands.iter().map(|x| (x.id, x.brand.as_str())).collect::<Vec<_>>(), vec![(1, "Daybird"), (2, "Gravitator")]);
+
+        let r = service.get_catalog_items(0, 10, Some(2), Some(1)).await.unwrap();
+        assert_eq!(r.count, 1);
+        assert_eq!(r.data[0].id, 3);
+        assert_eq!(r.data[0].price, Decimal::new(5950, 2));
+
+        let r = service.get_catalog_items(1, 2, None, None).await.unwrap();
+        assert_eq!(r.count, 3);
+        assert_eq!(r.data.iter().map(|x| x.id).collect::<Vec<_>>(), vec![1]);
+
+        assert!(service.delete_catalog_item(2).await.unwrap());
+        assert!(!service.delete_catalog_item(2).await.unwrap());
+        assert_eq!(service.search_semantic("climbing boots", 0, 10).await.unwrap().count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_related_items_and_neighbours() {
+        use crate::service::{CatalogFilter, get_item_neighbours};
+
+        let service = MockCatalogService::from_json(SAMPLE_FIXTURE).unwrap();
+
+        // same brand and type first, then same brand, then same type
+        let related = service.get_related_items(2, 5).await.unwrap();
+        assert_eq!(related.iter().map(|x| x.id).collect::<Vec<_>>(), vec![3]);
+        let related = service.get_related_items(1, 5).await.unwrap();
+        assert_eq!(related.iter().map(|x| x.id).collect::<Vec<_>>(), vec![3]);
+
+        // sorted by name: 3 Alpine, 2 Summit, 1 Wanderer; page size 2 puts 1 on the second page
+        let filter = CatalogFilter::default();
+        let n = get_item_neighbours(&service, 2, &filter, 0, 2).await.unwrap();
+        assert_eq!(n.prev.map(|x| (x.id, x.page_index)), Some((3, 0)));
+        assert_eq!(n.next.map(|x| (x.id, x.page_index)), Some((1, 1)));
+
+        let n = get_item_neighbours(&service, 1, &filter, 1, 2).await.unwrap();
+        assert_eq!(n.prev.map(|x| (x.id, x.page_index)), Some((2, 0)));
+        assert_eq!(n.next, None);
+
+        let filter = CatalogFilter { brand: Some(2), ..Default::default() };
+        let n = get_item_neighbours(&service, 3, &filter, 0, 9).await.unwrap();
+        assert_eq!((n.prev, n.next.map(|x| x.id)), (None, Some(2)));
+    }
+
+    #[tokio::test]
+    async fn test_sorted_pages() {
+        use crate::service::{CatalogFilter, CatalogSort, get_filtered_items};
+
+        let service = MockCatalogService::from_json(SAMPLE_FIXTURE).unwrap();
+
+        let ids = |r: CatalogResult| r.data.iter().map(|x| x.id).collect::<Vec<_>>();
+
+        let filter = CatalogFilter {
+            sort: Some(CatalogSort::PriceDesc),
+            ..Default::default()
+        };
+        assert_eq!(ids(get_filtered_items(&service, &filter, 0, 2).await.unwrap()), vec![1, 2]);
+        let r = get_filtered_items(&service, &filter, 1, 2).await.unwrap();
+        assert_eq!(r.count, 3);
+        assert_eq!(ids(r), vec![3]);
+
+        let filter = CatalogFilter {
+            sort: Some(CatalogSort::Newest),
+            ..Default::default()
+        };
+        assert_eq!(ids(get_filtered_items(&service, &filter, 0, 9).await.unwrap()), vec![3, 2, 1]);
+
+        // crafted pages neither overflow nor return more than the largest page
+        let r = get_filtered_items(&service, &filter, usize::MAX, 10).await.unwrap();
+        assert_eq!((r.count, r.data.len()), (3, 0));
+        let r = get_filtered_items(&service, &CatalogFilter::default(), usize::MAX, usize::MAX).await.unwrap();
+        assert_eq!((r.page_size, r.data.len()), (crate::service::MAX_PAGE_SIZE, 0));
+
+        assert_eq!("price_asc".parse::<CatalogSort>(), Ok(CatalogSort::PriceAsc));
+        assert!("cheapest".parse::<CatalogSort>().is_err());
+    }
+}
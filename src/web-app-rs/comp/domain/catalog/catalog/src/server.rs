@@ -1,9 +1,10 @@
-use crate::service::{CatalogResult, CatalogService, CatalogServiceContext};
+use crate::service::{CatalogResult, CatalogService, CatalogServiceContext, push_related, related_by_brand_and_type};
 use async_trait::async_trait;
 use std::sync::Arc;
 
 use anyhow::Result;
 pub use reqwest::Client as HttpClient;
+use resilient_http::ResilientClient;
 
 use crate::types::{CatalogBrand, CatalogItem, CatalogItemType};
 use reqwest::Url;
@@ -13,7 +14,7 @@ use url_mapper::UrlMapService;
 use api_version::versioning::QueryStringApiVersion;
 
 struct CatalogServiceApi {
-    http_client: HttpClient,
+    http_client: ResilientClient,
     base_url: Url,
     api_version: QueryStringApiVersion,
 }
@@ -32,16 +33,55 @@ fn get_all_catalog_items_uri(base_uri: &Url, page_index: usize, page_size: usize
     Ok(base_uri.join(&u)?)
 }
 
+fn get_all_catalog_items_uri_v2(base_uri: &Url, page_index: usize, page_size: usize, name: Option<&str>, brand: Option<usize>, type_id: Option<usize>) -> Result<Url> {
+    let mut uri = base_uri.join("items")?;
+    {
+        let mut query = uri.query_pairs_mut();
+        query.append_pair("pageIndex", &page_index.to_string()).append_pair("pageSize", &page_size.to_string());
+        if let Some(name) = name {
+            query.append_pair("name", name);
+        }
+        if let Some(type_id) = type_id {
+            query.append_pair("type", &type_id.to_string());
+        }
+        if let Some(brand) = brand {
+            query.append_pair("brand", &brand.to_string());
+        }
+    }
+    Ok(uri)
+}
+
+fn get_catalog_items_search_uri(base_uri: &Url, path: &str, text: &str, page_index: usize, page_size: usize) -> Result<Url> {
+    let mut uri = base_uri.join(path)?;
+    uri.path_segments_mut().map_err(|_| anyhow::anyhow!("cannot be a base url: {base_uri}"))?.pop_if_empty().push(text);
+    uri.query_pairs_mut().append_pair("pageIndex", &page_index.to_string()).append_pair("pageSize", &page_size.to_string());
+    Ok(uri)
+}
+
+fn get_catalog_items_semantic_uri_v2(base_uri: &Url, text: &str, page_index: usize, page_size: usize) -> Result<Url> {
+    let mut uri = base_uri.join("items/withsemanticrelevance")?;
+    uri.query_pairs_mut().append_pair("text", text).append_pair("pageIndex", &page_index.to_string()).append_pair("pageSize", &page_size.to_string());
+    Ok(uri)
+}
+
 impl CatalogServiceApi {
-    pub fn new(http_client: HttpClient, base_url: Url, api_version: QueryStringApiVersion) -> Self {
+    pub fn new(http_client: ResilientClient, base_url: Url, api_version: QueryStringApiVersion) -> Self {
         CatalogServiceApi { http_client, base_url, api_version }
     }
+
+    fn is_v2(&self) -> bool {
+        self.api_version.major_version() >= 2
+    }
 }
 
 #[async_trait]
 impl CatalogService for CatalogServiceApi {
     async fn get_catalog_items(&self, page_index: usize, page_size: usize, brand: Option<usize>, type_id: Option<usize>) -> Result<CatalogResult> {
-        let mut uri = get_all_catalog_items_uri(&self.base_url, page_index, page_size, brand, type_id)?;
+        let mut uri = if self.is_v2() {
+            get_all_catalog_items_uri_v2(&self.base_url, page_index, page_size, None, brand, type_id)?
+        } else {
+            get_all_catalog_items_uri(&self.base_url, page_index, page_size, brand, type_id)?
+        };
         log::info!("uri: {}", uri);
 
         self.api_version.append_to_url(&mut uri);
@@ -91,6 +131,80 @@ impl CatalogService for CatalogServiceApi {
 
         Ok(r)
     }
+
+    async fn search_by_name(&self, name: &str, page_index: usize, page_size: usize) -> Result<CatalogResult> {
+        let mut uri = if self.is_v2() {
+            get_all_catalog_items_uri_v2(&self.base_url, page_index, page_size, Some(name), None, None)?
+        } else {
+            get_catalog_items_search_uri(&self.base_url, "items/by/", name, page_index, page_size)?
+        };
+        self.api_version.append_to_url(&mut uri);
+
+        let r = self.http_client.get(uri).send().await?.error_for_status()?.json::<CatalogResult>().await?;
+
+        Ok(r)
+    }
+
+    async fn search_semantic(&self, text: &str, page_index: usize, page_size: usize) -> Result<CatalogResult> {
+        let mut uri = if self.is_v2() {
+            get_catalog_items_semantic_uri_v2(&self.base_url, text, page_index, page_size)?
+        } else {
+            get_catalog_items_search_uri(&self.base_url, "items/withsemanticrelevance/", text, page_index, page_size)?
+        };
+        self.api_version.append_to_url(&mut uri);
+
+        let r = self.http_client.get(uri).send().await?.error_for_status()?.json::<CatalogResult>().await?;
+
+        Ok(r)
+    }
+
+    async fn update_catalog_item(&self, item: CatalogItem) -> Result<()> {
+        // v1 takes the id from the body, v2 from the path
+        let mut uri = if self.is_v2() { self.base_url.join(format!("items/{}", item.id).as_str())? } else { self.base_url.join("items")? };
+        self.api_version.append_to_url(&mut uri);
+
+        self.http_client.put(uri).json(&item).send().await?.error_for_status()?;
+
+        Ok(())
+    }
+
+    async fn create_catalog_item(&self, item: CatalogItem) -> Result<()> {
+        let mut uri = self.base_url.join("items")?;
+        self.api_version.append_to_url(&mut uri);
+
+        self.http_client.post(uri).json(&item).send().await?.error_for_status()?;
+
+        Ok(())
+    }
+
+    /// With v2 the semantic neighbours of the item name come first, brand and type fill up the rest.
+    async fn get_related_items(&self, item_id: usize, count: usize) -> Result<Vec<CatalogItem>> {
+        let Some(item) = self.get_catalog_item(item_id).await? else {
+            return Ok(vec![]);
+        };
+
+        let mut related = Vec::new();
+        if self.is_v2() {
+            match self.search_semantic(&item.name, 0, count + 1).await {
+                Ok(r) => push_related(&mut related, &item, r.data, count),
+                Err(e) => log::warn!("semantic search for related items of {item_id} failed: {e}"),
+            }
+        }
+        related_by_brand_and_type(self, &item, related, count).await
+    }
+
+    async fn delete_catalog_item(&self, item_id: usize) -> Result<bool> {
+        let mut uri = self.base_url.join(format!("items/{item_id}").as_str())?;
+        self.api_version.append_to_url(&mut uri);
+
+        let r = self.http_client.delete(uri).send().await?;
+        if r.status() == reqwest::StatusCode::NOT_FOUND {
+            Ok(false)
+        } else {
+            r.error_for_status()?;
+            Ok(true)
+        }
+    }
 }
 
 pub fn make_service(http_client: HttpClient, url_map_service: UrlMapService, api_version: QueryStringApiVersion) -> Result<CatalogServiceContext> {
@@ -100,7 +214,45 @@ pub fn make_service(http_client: HttpClient, url_map_service: UrlMapService, api
     let base_url = Url::parse(base_url)?;
     let base_url = base_url.join(CATALOG_SERVICE_BASE_URL)?;
 
+    // resilience settings are keyed like the url mapping, see `ResilienceConfig::from_env`
+    let http_client = ResilientClient::from_env(http_client, BASE_MAP_TO_PATH)?;
+
     Ok(CatalogServiceContext {
         service: Arc::new(CatalogServiceApi::new(http_client, base_url, api_version)),
     })
 }
+
+/// Keeps the timeout / unavailable errors of the http client, everything else becomes a `ServerFnError`.
+pub fn to_app_error(e: anyhow::Error) -> crate::AppError {
+    resilient_http::Error::app_error_from_anyhow(&e).unwrap_or_else(|| crate::AppError::ServerFnError(leptos::prelude::ServerFnErrorErr::ServerError(e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_items_search_uri() {
+        let base_url = Url::parse("http://catalog-api/api/catalog/").unwrap();
+
+        let uri = get_catalog_items_search_uri(&base_url, "items/by/", "Wanderer", 0, 9).unwrap();
+        assert_eq!(uri.as_str(), "http://catalog-api/api/catalog/items/by/Wanderer?pageIndex=0&pageSize=9");
+
+        let uri = get_catalog_items_search_uri(&base_url, "items/withsemanticrelevance/", "red shoes/boots", 2, 9).unwrap();
+        assert_eq!(uri.as_str(), "http://catalog-api/api/catalog/items/withsemanticrelevance/red%20shoes%2Fboots?pageIndex=2&pageSize=9");
+    }
+
+    #[test]
+    fn test_catalog_items_uri_v2() {
+        let base_url = Url::parse("http://catalog-api/api/catalog/").unwrap();
+
+        let uri = get_all_catalog_items_uri_v2(&base_url, 1, 9, None, Some(2), Some(3)).unwrap();
+        assert_eq!(uri.as_str(), "http://catalog-api/api/catalog/items?pageIndex=1&pageSize=9&type=3&brand=2");
+
+        let uri = get_all_catalog_items_uri_v2(&base_url, 0, 9, Some("Alpine Fusion"), None, None).unwrap();
+        assert_eq!(uri.as_str(), "http://catalog-api/api/catalog/items?pageIndex=0&pageSize=9&name=Alpine+Fusion");
+
+        let uri = get_catalog_items_semantic_uri_v2(&base_url, "warm jacket", 0, 9).unwrap();
+        assert_eq!(uri.as_str(), "http://catalog-api/api/catalog/items/withsemanticrelevance?text=warm+jacket&pageIndex=0&pageSize=9");
+    }
+}
@@ -14,29 +14,83 @@ fn use_catalog_service_context() -> Result<CatalogServiceContext, crate::AppErro
 }
 
 #[server]
-pub async fn get_catalog_items(page_index: usize, page_size: usize, brand: Option<usize>, type_id: Option<usize>) -> Result<CatalogResult, crate::AppError> {
+pub async fn get_catalog_items(page_index: usize, page_size: usize, brand: Option<usize>, type_id: Option<usize>, sort: Option<CatalogSort>) -> Result<CatalogResult, crate::AppError> {
     let ctx = use_catalog_service_context()?;
     //let ctx = expect_context::<CatalogServiceContext>();
-    ctx.service
-        .get_catalog_items(page_index, page_size, brand, type_id)
-        .await
-        .map_err(|e| crate::AppError::ServerFnError(ServerFnErrorErr::ServerError(e.to_string())))
+    let filter = CatalogFilter { brand, type_id, sort, ..Default::default() };
+    get_filtered_items(ctx.service.as_ref(), &filter, page_index, page_size).await.map_err(crate::server::to_app_error)
 }
 
 #[server]
 pub async fn get_brands() -> Result<Vec<CatalogBrand>, crate::AppError> {
     let ctx = use_catalog_service_context()?;
-    ctx.service.get_brands().await.map_err(|e| crate::AppError::ServerFnError(ServerFnErrorErr::ServerError(e.to_string())))
+    ctx.service.get_brands().await.map_err(crate::server::to_app_error)
 }
 
 #[server]
 pub async fn get_types() -> Result<Vec<CatalogItemType>, crate::AppError> {
     let ctx = use_catalog_service_context()?;
-    ctx.service.get_types().await.map_err(|e| crate::AppError::ServerFnError(ServerFnErrorErr::ServerError(e.to_string())))
+    ctx.service.get_types().await.map_err(crate::server::to_app_error)
 }
 
 #[server]
 pub async fn get_catalog_item(item_id: usize) -> Result<Option<CatalogItem>, crate::AppError> {
     let ctx = use_catalog_service_context()?;
-    ctx.service.get_catalog_item(item_id).await.map_err(|e| crate::AppError::ServerFnError(ServerFnErrorErr::ServerError(e.to_string())))
+    ctx.service.get_catalog_item(item_id).await.map_err(crate::server::to_app_error)
+}
+
+#[server]
+pub async fn get_related_items(item_id: usize, count: usize) -> Result<Vec<CatalogItem>, crate::AppError> {
+    let ctx = use_catalog_service_context()?;
+    ctx.service.get_related_items(item_id, count).await.map_err(crate::server::to_app_error)
+}
+
+#[server]
+pub async fn get_item_neighbours(item_id: i32, filter: CatalogFilter, page_index: usize, page_size: usize) -> Result<CatalogItemNeighbours, crate::AppError> {
+    let ctx = use_catalog_service_context()?;
+    crate::service::get_item_neighbours(ctx.service.as_ref(), item_id, &filter, page_index, page_size).await.map_err(crate::server::to_app_error)
+}
+
+#[server]
+pub async fn search_by_name(name: String, page_index: usize, page_size: usize, sort: Option<CatalogSort>) -> Result<CatalogResult, crate::AppError> {
+    let ctx = use_catalog_service_context()?;
+    let filter = CatalogFilter {
+        search: Some(name),
+        sort,
+        ..Default::default()
+    };
+    get_filtered_items(ctx.service.as_ref(), &filter, page_index, page_size).await.map_err(crate::server::to_app_error)
+}
+
+#[server]
+pub async fn search_semantic(text: String, page_index: usize, page_size: usize, sort: Option<CatalogSort>) -> Result<CatalogResult, crate::AppError> {
+    let ctx = use_catalog_service_context()?;
+    let filter = CatalogFilter {
+        search: Some(text),
+        semantic: true,
+        sort,
+        ..Default::default()
+    };
+    get_filtered_items(ctx.service.as_ref(), &filter, page_index, page_size).await.map_err(crate::server::to_app_error)
+}
+
+#[server]
+#[middleware(auth::RequireRole(auth::ADMIN_ROLE))]
+pub async fn create_catalog_item(item: CatalogItem) -> Result<(), crate::AppError> {
+    let ctx = use_catalog_service_context()?;
+    ctx.service.create_catalog_item(item).await.map_err(crate::server::to_app_error)
+}
+
+#[server]
+#[middleware(auth::RequireRole(auth::ADMIN_ROLE))]
+pub async fn update_catalog_item(item: CatalogItem) -> Result<(), crate::AppError> {
+    let ctx = use_catalog_service_context()?;
+    ctx.service.update_catalog_item(item).await.map_err(crate::server::to_app_error)
+}
+
+#[server]
+#[middleware(auth::RequireRole(auth::ADMIN_ROLE))]
+pub async fn delete_catalog_item(item_id: usize) -> Result<bool, crate::AppError> {
+    let ctx = use_catalog_service_context()?;
+    ctx.service.delete_catalog_item(item_id).await.map_err(crate::server::to_app_error)
 }
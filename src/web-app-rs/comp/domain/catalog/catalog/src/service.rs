@@ -19,6 +19,77 @@ pub struct CatalogResult {
     pub data: Vec<CatalogItem>,
 }
 
+/// Order of a catalog listing, the catalog-api itself only returns items ordered by name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogSort {
+    Name,
+    PriceAsc,
+    PriceDesc,
+    /// There is no creation date in the catalog, higher ids are newer.
+    Newest,
+}
+
+impl CatalogSort {
+    pub const ALL: [CatalogSort; 4] = [CatalogSort::Name, CatalogSort::PriceAsc, CatalogSort::PriceDesc, CatalogSort::Newest];
+
+    /// The value of the `sort` query parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CatalogSort::Name => "name",
+            CatalogSort::PriceAsc => "price_asc",
+            CatalogSort::PriceDesc => "price_desc",
+            CatalogSort::Newest => "newest",
+        }
+    }
+
+    fn sort(&self, items: &mut [CatalogItem]) {
+        match self {
+            CatalogSort::Name => items.sort_by(|a, b| a.name.cmp(&b.name)),
+            CatalogSort::PriceAsc => items.sort_by(|a, b| a.price.cmp(&b.price).then_with(|| a.name.cmp(&b.name))),
+            CatalogSort::PriceDesc => items.sort_by(|a, b| b.price.cmp(&a.price).then_with(|| a.name.cmp(&b.name))),
+            CatalogSort::Newest => items.sort_by(|a, b| b.id.cmp(&a.id)),
+        }
+    }
+}
+
+impl std::str::FromStr for CatalogSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        CatalogSort::ALL.into_iter().find(|x| x.as_str() == s).ok_or_else(|| format!("unknown sort order {s}"))
+    }
+}
+
+/// A catalog listing, a search takes precedence over brand and type.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogFilter {
+    pub brand: Option<usize>,
+    pub type_id: Option<usize>,
+    pub search: Option<String>,
+    #[serde(default)]
+    pub semantic: bool,
+    #[serde(default)]
+    pub sort: Option<CatalogSort>,
+}
+
+/// An item next to another one in a filtered listing, with the page of the listing it is on.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogItemNeighbour {
+    pub id: i32,
+    pub name: String,
+    pub page_index: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogItemNeighbours {
+    pub prev: Option<CatalogItemNeighbour>,
+    pub next: Option<CatalogItemNeighbour>,
+}
+
 #[async_trait]
 pub trait CatalogService: Send + Sync {
     async fn get_catalog_items(&self, page_index: usize, page_size: usize, brand: Option<usize>, type_id: Option<usize>) -> Result<CatalogResult>;
@@ -30,6 +101,120 @@ pub trait CatalogService: Send + Sync {
     async fn get_catalog_item(&self, item_id: usize) -> Result<Option<CatalogItem>>;
 
     async fn get_catalog_items_by_ids(&self, item_ids: Vec<i32>) -> Result<Vec<CatalogItem>>;
+
+    async fn search_by_name(&self, name: &str, page_index: usize, page_size: usize) -> Result<CatalogResult>;
+
+    async fn search_semantic(&self, text: &str, page_index: usize, page_size: usize) -> Result<CatalogResult>;
+
+    async fn update_catalog_item(&self, item: CatalogItem) -> Result<()>;
+
+    async fn create_catalog_item(&self, item: CatalogItem) -> Result<()>;
+
+    /// Returns false if the item does not exist.
+    async fn delete_catalog_item(&self, item_id: usize) -> Result<bool>;
+
+    /// Up to `count` other items of the same brand and type, then of the same brand, then of the same type.
+    async fn get_related_items(&self, item_id: usize, count: usize) -> Result<Vec<CatalogItem>> {
+        match self.get_catalog_item(item_id).await? {
+            Some(item) => related_by_brand_and_type(self, &item, Vec::new(), count).await,
+            None => Ok(vec![]),
+        }
+    }
+}
+
+pub(crate) fn push_related(related: &mut Vec<CatalogItem>, item: &CatalogItem, candidates: Vec<CatalogItem>, count: usize) {
+    for candidate in candidates {
+        if related.len() >= count {
+            break;
+        }
+        if candidate.id != item.id && !related.iter().any(|x| x.id == candidate.id) {
+            related.push(candidate);
+        }
+    }
+}
+
+/// Fills `related` up to `count`, the page size covers the item itself and the ones already taken.
+pub async fn related_by_brand_and_type<S: CatalogService + ?Sized>(service: &S, item: &CatalogItem, mut related: Vec<CatalogItem>, count: usize) -> Result<Vec<CatalogItem>> {
+    let brand = Some(item.catalog_brand_id);
+    let type_id = Some(item.catalog_type_id);
+    for (brand, type_id) in [(brand, type_id), (brand, None), (None, type_id)] {
+        if related.len() >= count {
+            break;
+        }
+        let candidates = service.get_catalog_items(0, count + related.len() + 1, brand, type_id).await?.data;
+        push_related(&mut related, item, candidates, count);
+    }
+    Ok(related)
+}
+
+async fn get_unsorted_items<S: CatalogService + ?Sized>(service: &S, filter: &CatalogFilter, page_index: usize, page_size: usize) -> Result<CatalogResult> {
+    match &filter.search {
+        Some(text) if filter.semantic => service.search_semantic(text, page_index, page_size).await,
+        Some(text) => service.search_by_name(text, page_index, page_size).await,
+        None => service.get_catalog_items(page_index, page_size, filter.brand, filter.type_id).await,
+    }
+}
+
+/// Larger pages of the catalog are cut to this size.
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// One page of the listing selected by `filter`, `page_size` between 1 and `MAX_PAGE_SIZE`.
+/// Orders the catalog-api does not return are applied here to the whole listing, which is fetched for that.
+pub async fn get_filtered_items<S: CatalogService + ?Sized>(service: &S, filter: &CatalogFilter, page_index: usize, page_size: usize) -> Result<CatalogResult> {
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    let sort = match filter.sort {
+        // semantic search results are ordered by relevance
+        Some(CatalogSort::Name) if !filter.semantic => None,
+        sort => sort,
+    };
+    let Some(sort) = sort else {
+        return get_unsorted_items(service, filter, page_index, page_size).await;
+    };
+
+    let first = get_unsorted_items(service, filter, 0, page_size).await?;
+    let mut items = if first.count > first.data.len() { get_unsorted_items(service, filter, 0, first.count).await?.data } else { first.data };
+    sort.sort(&mut items);
+
+    Ok(CatalogResult {
+        page_index,
+        page_size,
+        count: first.count,
+        data: items.into_iter().skip(page_index.saturating_mul(page_size)).take(page_size).collect(),
+    })
+}
+
+/// The items before and after `item_id` in the listing of `filter`, looking into the adjacent pages at the page borders.
+/// Both are `None` if the item is not on page `page_index`.
+pub async fn get_item_neighbours<S: CatalogService + ?Sized>(service: &S, item_id: i32, filter: &CatalogFilter, page_index: usize, page_size: usize) -> Result<CatalogItemNeighbours> {
+    let neighbour = |item: &CatalogItem, page_index: usize| CatalogItemNeighbour {
+        id: item.id,
+        name: item.name.clone(),
+        page_index,
+    };
+
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    let page = get_filtered_items(service, filter, page_index, page_size).await?;
+    let Some(pos) = page.data.iter().position(|x| x.id == item_id) else {
+        return Ok(CatalogItemNeighbours::default());
+    };
+
+    let prev = if pos > 0 {
+        Some(neighbour(&page.data[pos - 1], page_index))
+    } else if page_index > 0 {
+        get_filtered_items(service, filter, page_index - 1, page_size).await?.data.last().map(|x| neighbour(x, page_index - 1))
+    } else {
+        None
+    };
+
+    let next = if pos + 1 < page.data.len() {
+        Some(neighbour(&page.data[pos + 1], page_index))
+    } else if page_index.saturating_add(1).saturating_mul(page_size) < page.count {
+        get_filtered_items(service, filter, page_index + 1, page_size).await?.data.first().map(|x| neighbour(x, page_index + 1))
+    } else {
+        None
+    };
+
+    Ok(CatalogItemNeighbours { prev, next })
 }
 
 #[derive(Clone)]
@@ -32,10 +32,15 @@ impl CatalogItem {
             description: String::new(),
             price: Decimal::zero(),
             picture_url: None,
+            picture_file_name: None,
             catalog_type_id: Default::default(),
             catalog_type: None,
             catalog_brand_id: Default::default(),
             catalog_brand: None,
+            available_stock: Default::default(),
+            restock_threshold: Default::default(),
+            max_stock_threshold: Default::default(),
+            on_reorder: false,
         }
     }
 }
@@ -64,6 +69,9 @@ pub struct CatalogItem {
 
     pub picture_url: Option<String>,
 
+    #[serde(default)]
+    pub picture_file_name: Option<String>,
+
     pub catalog_type_id: usize,
 
     pub catalog_type: Option<CatalogItemType>,
@@ -71,4 +79,16 @@ pub struct CatalogItem {
     pub catalog_brand_id: usize,
 
     pub catalog_brand: Option<CatalogBrand>,
+
+    #[serde(default)]
+    pub available_stock: i32,
+
+    #[serde(default)]
+    pub restock_threshold: i32,
+
+    #[serde(default)]
+    pub max_stock_threshold: i32,
+
+    #[serde(default)]
+    pub on_reorder: bool,
 }
@@ -0,0 +1,162 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use reqwest::{Request, Response};
+use tower::{Layer, Service};
+
+use crate::{BoxFuture, Error, is_failure_status};
+
+enum State {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// One probe request is in flight.
+    HalfOpen,
+}
+
+pub(crate) struct CircuitBreaker {
+    state: Mutex<State>,
+    failure_threshold: u32,
+    open_for: Duration,
+}
+
+impl CircuitBreaker {
+    fn new(failure_threshold: u32, open_for: Duration) -> Self {
+        Self {
+            state: Mutex::new(State::Closed { failures: 0 }),
+            failure_threshold,
+            open_for,
+        }
+    }
+
+    fn try_acquire(self: &Arc<Self>) -> Option<Permit> {
+        let mut state = self.state.lock().unwrap();
+        let probe = match *state {
+            State::Closed { .. } => false,
+            State::Open { until } if Instant::now() >= until => {
+                *state = State::HalfOpen;
+                true
+            }
+            State::Open { .. } | State::HalfOpen => return None,
+        };
+        Some(Permit { breaker: self.clone(), probe })
+    }
+
+    /// The probe went away without a result, the next call probes again.
+    fn release_probe(&self) {
+        let mut state = self.state.lock().unwrap();
+        if matches!(*state, State::HalfOpen) {
+            *state = State::Open { until: Instant::now() };
+        }
+    }
+
+    fn on_result(&self, service: &str, failed: bool) {
+        let mut state = self.state.lock().unwrap();
+        *state = match (&*state, failed) {
+            (_, false) => State::Closed { failures: 0 },
+            (State::Closed { failures }, true) if failures + 1 < self.failure_threshold => State::Closed { failures: failures + 1 },
+            (_, true) => {
+                log::warn!("{service}: circuit breaker open for {:?}", self.open_for);
+                State::Open { until: Instant::now() + self.open_for }
+            }
+        };
+    }
+}
+
+/// A call let through by the breaker. Dropping the permit of the probe without `complete`, e.g. when the caller gave up
+/// on the request, frees the half-open state for the next call.
+struct Permit {
+    breaker: Arc<CircuitBreaker>,
+    probe: bool,
+}
+
+impl Permit {
+    fn complete(mut self, service: &str, failed: bool) {
+        self.probe = false;
+        self.breaker.on_result(service, failed);
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if self.probe {
+            self.breaker.release_probe();
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct CircuitBreakerLayer {
+    service: Arc<str>,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl CircuitBreakerLayer {
+    pub(crate) fn new(service: Arc<str>, failure_threshold: u32, open_for: Duration) -> Self {
+        Self {
+            service,
+            breaker: Arc::new(CircuitBreaker::new(failure_threshold, open_for)),
+        }
+    }
+}
+
+impl<S> Layer<S> for CircuitBreakerLayer {
+    type Service = CircuitBreakerService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CircuitBreakerService {
+            inner,
+            service: self.service.clone(),
+            breaker: self.breaker.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct CircuitBreakerService<S> {
+    inner: S,
+    service: Arc<str>,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl<S> Service<Request> for CircuitBreakerService<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Error;
+    type Future = BoxFuture<Result<Response, Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        if self.breaker.failure_threshold == 0 {
+            return Box::pin(self.inner.call(request));
+        }
+
+        let service = self.service.clone();
+        let Some(permit) = self.breaker.try_acquire() else {
+            return Box::pin(async move { Err(Error::CircuitOpen { service: service.to_string() }) });
+        };
+
+        let fut = self.inner.call(request);
+        Box::pin(async move {
+            let r = fut.await;
+            let failed = match &r {
+                Ok(response) => is_failure_status(response.status()),
+                Err(_) => true,
+            };
+            permit.complete(&service, failed);
+            r
+        })
+    }
+}
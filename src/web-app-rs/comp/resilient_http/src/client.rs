@@ -0,0 +1,308 @@
+use std::{
+    fmt::Display,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use http_body_util::BodyExt;
+use reqwest::{
+    IntoUrl, Method, Request, Response,
+    header::{HeaderName, HeaderValue},
+};
+use serde::Serialize;
+use tower::{Service, ServiceBuilder, ServiceExt, util::BoxCloneSyncService};
+
+use crate::{BoxFuture, CircuitBreakerLayer, Error, HedgeLayer, ResilienceConfig, RetryLayer, TimeoutLayer};
+
+/// The innermost service, sends the request with the `reqwest::Client` and reads the whole body, a backend that stalls
+/// after the headers runs into the timeout like one that does not answer.
+#[derive(Clone)]
+struct Execute {
+    client: reqwest::Client,
+    service: Arc<str>,
+}
+
+impl Service<Request> for Execute {
+    type Response = Response;
+    type Error = Error;
+    type Future = BoxFuture<Result<Response, Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let client = self.client.clone();
+        let service = self.service.clone();
+        Box::pin(async move {
+            let request_error = |source| Error::Request { service: service.to_string(), source };
+            let response: http::Response<reqwest::Body> = client.execute(request).await.map_err(request_error)?.into();
+            let (parts, body) = response.into_parts();
+            let body = body.collect().await.map_err(request_error)?.to_bytes();
+            Ok(Response::from(http::Response::from_parts(parts, body)))
+        })
+    }
+}
+
+/// `reqwest::Client` lookalike that sends the requests of one downstream service through the resilience stack.
+#[derive(Clone)]
+pub struct ResilientClient {
+    client: reqwest::Client,
+    service: Arc<str>,
+    stack: BoxCloneSyncService<Request, Response, Error>,
+}
+
+impl ResilientClient {
+    pub fn new(client: reqwest::Client, service_key: &str, config: ResilienceConfig) -> Self {
+        let service: Arc<str> = Arc::from(service_key);
+        let execute = Execute {
+            client: client.clone(),
+            service: service.clone(),
+        };
+        Self::with_service(client, service, config, execute)
+    }
+
+    /// Config from [`ResilienceConfig::from_env`].
+    pub fn from_env(client: reqwest::Client, service_key: &str) -> anyhow::Result<Self> {
+        Ok(Self::new(client, service_key, ResilienceConfig::from_env(service_key)?))
+    }
+
+    fn with_service<S>(client: reqwest::Client, service: Arc<str>, config: ResilienceConfig, inner: S) -> Self
+    where
+        S: Service<Request, Response = Response, Error = Error> + Clone + Send + Sync + 'static,
+        S::Future: Send + 'static,
+    {
+        let stack = ServiceBuilder::new()
+            .layer(RetryLayer::new(config.retries, config.retry_base))
+            .layer(CircuitBreakerLayer::new(service.clone(), config.breaker_failures, config.breaker_open_for))
+            .layer(HedgeLayer::new(config.hedge_after))
+            .layer(TimeoutLayer::new(service.clone(), config.timeout))
+            .service(inner);
+
+        Self {
+            client,
+            service,
+            stack: BoxCloneSyncService::new(stack),
+        }
+    }
+
+    pub fn request<U: IntoUrl>(&self, method: Method, url: U) -> RequestBuilder {
+        RequestBuilder {
+            client: self.clone(),
+            inner: self.client.request(method, url),
+        }
+    }
+
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    pub fn post<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::POST, url)
+    }
+
+    pub fn put<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::PUT, url)
+    }
+
+    pub fn delete<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::DELETE, url)
+    }
+
+    pub async fn execute(&self, request: Request) -> Result<Response, Error> {
+        self.stack.clone().oneshot(request).await
+    }
+}
+
+/// Wraps `reqwest::RequestBuilder`, `send` goes through the [`ResilientClient`].
+pub struct RequestBuilder {
+    client: ResilientClient,
+    inner: reqwest::RequestBuilder,
+}
+
+impl RequestBuilder {
+    pub fn header<K, V>(self, key: K, value: V) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        Self { inner: self.inner.header(key, value), ..self }
+    }
+
+    pub fn bearer_auth<T: Display>(self, token: T) -> Self {
+        Self { inner: self.inner.bearer_auth(token), ..self }
+    }
+
+    pub fn json<T: Serialize + ?Sized>(self, json: &T) -> Self {
+        Self { inner: self.inner.json(json), ..self }
+    }
+
+    pub fn query<T: Serialize + ?Sized>(self, query: &T) -> Self {
+        Self { inner: self.inner.query(query), ..self }
+    }
+
+    pub async fn send(self) -> Result<Response, Error> {
+        let request = self.inner.build().map_err(|source| Error::Request {
+            service: self.client.service.to_string(),
+            source,
+        })?;
+        self.client.execute(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use super::*;
+
+    /// Answers the n-th call (0 based) with the status and delay returned by `reply(n)`.
+    #[derive(Clone)]
+    struct Scripted {
+        calls: Arc<AtomicUsize>,
+        reply: fn(usize) -> (u16, Duration),
+    }
+
+    impl Service<Request> for Scripted {
+        type Response = Response;
+        type Error = Error;
+        type Future = BoxFuture<Result<Response, Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _request: Request) -> Self::Future {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            let (status, delay) = (self.reply)(n);
+            Box::pin(async move {
+                tokio::time::sleep(delay).await;
+                Ok(Response::from(http::Response::builder().status(status).body(n.to_string()).unwrap()))
+            })
+        }
+    }
+
+    fn make_client(config: ResilienceConfig, reply: fn(usize) -> (u16, Duration)) -> (Arc<AtomicUsize>, ResilientClient) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let scripted = Scripted { calls: calls.clone(), reply };
+        let client = ResilientClient::with_service(reqwest::Client::new(), Arc::from("http://test-api"), config, scripted);
+        (calls, client)
+    }
+
+    fn config() -> ResilienceConfig {
+        ResilienceConfig {
+            timeout: Duration::from_millis(200),
+            retries: 2,
+            retry_base: Duration::from_millis(1),
+            breaker_failures: 0,
+            breaker_open_for: Duration::from_secs(30),
+            hedge_after: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_idempotent_only() {
+        let (calls, client) = make_client(config(), |n| (if n < 2 { 503 } else { 200 }, Duration::ZERO));
+
+        let r = client.get("http://test-api/items").send().await.unwrap();
+        assert_eq!(r.status(), 200);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let (calls, client) = make_client(config(), |_| (503, Duration::ZERO));
+        let r = client.post("http://test-api/items").send().await.unwrap();
+        assert_eq!(r.status(), 503);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let (_, client) = make_client(ResilienceConfig { retries: 0, ..config() }, |_| (200, Duration::from_secs(5)));
+
+        let r = client.get("http://test-api/items").send().await;
+        assert!(matches!(r, Err(Error::Timeout { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_stalled_body() {
+        use tokio::io::AsyncWriteExt;
+
+        // the headers promise a body that never comes
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 10\r\n\r\n{").await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let client = ResilientClient::new(reqwest::Client::new(), "http://test-api", ResilienceConfig { retries: 0, ..config() });
+        let r = tokio::time::timeout(Duration::from_secs(2), client.get(format!("http://{addr}/items")).send()).await.unwrap();
+        assert!(matches!(r, Err(Error::Timeout { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker() {
+        let config = ResilienceConfig {
+            retries: 0,
+            breaker_failures: 2,
+            breaker_open_for: Duration::from_millis(50),
+            ..config()
+        };
+        let (calls, client) = make_client(config, |n| (if n < 2 { 500 } else { 200 }, Duration::ZERO));
+
+        client.get("http://test-api/items").send().await.unwrap();
+        client.get("http://test-api/items").send().await.unwrap();
+        let r = client.get("http://test-api/items").send().await;
+        assert!(matches!(r, Err(Error::CircuitOpen { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let r = client.get("http://test-api/items").send().await.unwrap();
+        assert_eq!(r.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_dropped_probe() {
+        let config = ResilienceConfig {
+            retries: 0,
+            breaker_failures: 1,
+            breaker_open_for: Duration::from_millis(50),
+            ..config()
+        };
+        let (calls, client) = make_client(config, |n| match n {
+            0 => (500, Duration::ZERO),
+            1 => (200, Duration::from_millis(150)),
+            _ => (200, Duration::ZERO),
+        });
+
+        client.get("http://test-api/items").send().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        // the caller gives up on the probe, it must not keep the breaker half open
+        let r = tokio::time::timeout(Duration::from_millis(20), client.get("http://test-api/items").send()).await;
+        assert!(r.is_err());
+
+        let r = client.get("http://test-api/items").send().await.unwrap();
+        assert_eq!(r.status(), 200);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_hedging() {
+        let config = ResilienceConfig {
+            retries: 0,
+            hedge_after: Some(Duration::from_millis(20)),
+            ..config()
+        };
+        let (calls, client) = make_client(config, |n| (200, if n == 0 { Duration::from_millis(150) } else { Duration::ZERO }));
+
+        let r = client.get("http://test-api/items").send().await.unwrap();
+        assert_eq!(r.text().await.unwrap(), "1");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+#[derive(Clone, Debug)]
+pub struct ResilienceConfig {
+    /// Per attempt, hedged requests get their own timeout.
+    pub timeout: Duration,
+    /// Retries after the first attempt, 0 disables retrying.
+    pub retries: u32,
+    /// Backoff before retry n is a random duration in `0..=retry_base * 2^n` (full jitter).
+    pub retry_base: Duration,
+    /// Consecutive failures that open the breaker, 0 disables the breaker.
+    pub breaker_failures: u32,
+    pub breaker_open_for: Duration,
+    /// Send a second request if the first one didn't answer within this time, `None` disables hedging.
+    pub hedge_after: Option<Duration>,
+}
+
+impl Default for ResilienceConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            retries: 2,
+            retry_base: Duration::from_millis(100),
+            breaker_failures: 5,
+            breaker_open_for: Duration::from_secs(30),
+            hedge_after: None,
+        }
+    }
+}
+
+/// "http://catalog-api" -> "CATALOG_API"
+fn env_prefix(service_key: &str) -> String {
+    let name = service_key.split("://").last().unwrap_or(service_key);
+    name.trim_end_matches('/').replace(['-', '.', '/'], "_").to_uppercase()
+}
+
+impl ResilienceConfig {
+    /// Reads the settings of the service with the `url_mapper` key `service_key`, e.g. for "http://catalog-api":
+    /// `CATALOG_API_TIMEOUT_MS`, `CATALOG_API_RETRIES`, `CATALOG_API_RETRY_BASE_MS`, `CATALOG_API_BREAKER_FAILURES`,
+    /// `CATALOG_API_BREAKER_OPEN_MS` and `CATALOG_API_HEDGE_AFTER_MS` (0 disables hedging).
+    /// Unset variables keep the default.
+    pub fn from_env(service_key: &str) -> Result<Self> {
+        Self::from_vars(service_key, |name| std::env::var(name).ok())
+    }
+
+    /// [`Self::from_env`] with the variables looked up by `lookup`.
+    pub fn from_vars(service_key: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let prefix = env_prefix(service_key);
+        let var = |name: &str| -> Result<Option<u64>> {
+            let name = format!("{prefix}_{name}");
+            match lookup(&name) {
+                Some(v) => Ok(Some(v.trim().parse::<u64>().map_err(|e| anyhow::anyhow!("{name}: {e}"))?)),
+                None => Ok(None),
+            }
+        };
+
+        let default = Self::default();
+        Ok(Self {
+            timeout: var("TIMEOUT_MS")?.map(Duration::from_millis).unwrap_or(default.timeout),
+            retries: var("RETRIES")?.map(|v| v as u32).unwrap_or(default.retries),
+            retry_base: var("RETRY_BASE_MS")?.map(Duration::from_millis).unwrap_or(default.retry_base),
+            breaker_failures: var("BREAKER_FAILURES")?.map(|v| v as u32).unwrap_or(default.breaker_failures),
+            breaker_open_for: var("BREAKER_OPEN_MS")?.map(Duration::from_millis).unwrap_or(default.breaker_open_for),
+            hedge_after: match var("HEDGE_AFTER_MS")? {
+                Some(0) => None,
+                Some(v) => Some(Duration::from_millis(v)),
+                None => default.hedge_after,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_prefix() {
+        assert_eq!(env_prefix("http://catalog-api"), "CATALOG_API");
+        assert_eq!(env_prefix("https://ordering-api/"), "ORDERING_API");
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let vars = std::collections::HashMap::from([("TEST_CFG_API_TIMEOUT_MS", "1500"), ("TEST_CFG_API_HEDGE_AFTER_MS", "200")]);
+
+        let config = ResilienceConfig::from_vars("http://test-cfg-api", |name| vars.get(name).map(|v| v.to_string())).unwrap();
+        assert_eq!(config.timeout, Duration::from_millis(1500));
+        assert_eq!(config.hedge_after, Some(Duration::from_millis(200)));
+        assert_eq!(config.retries, ResilienceConfig::default().retries);
+    }
+}
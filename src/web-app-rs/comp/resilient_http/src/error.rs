@@ -0,0 +1,40 @@
+use app_err::AppError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("{service}: request timed out")]
+    Timeout { service: String },
+    #[error("{service}: circuit breaker is open")]
+    CircuitOpen { service: String },
+    #[error("{service}: {source}")]
+    Request {
+        service: String,
+        #[source]
+        source: reqwest::Error,
+    },
+}
+
+impl From<&Error> for AppError {
+    fn from(value: &Error) -> Self {
+        match value {
+            Error::Timeout { service } => AppError::Timeout(service.clone()),
+            Error::CircuitOpen { service } => AppError::ServiceUnavailable(service.clone()),
+            Error::Request { service, source } if source.is_connect() => AppError::ServiceUnavailable(service.clone()),
+            Error::Request { source, .. } => AppError::Other(source.to_string()),
+        }
+    }
+}
+
+impl From<Error> for AppError {
+    fn from(value: Error) -> Self {
+        AppError::from(&value)
+    }
+}
+
+impl Error {
+    /// The `AppError` of an `anyhow::Error` raised by the resilience stack, `None` for other errors.
+    pub fn app_error_from_anyhow(e: &anyhow::Error) -> Option<AppError> {
+        e.downcast_ref::<Error>().map(AppError::from)
+    }
+}
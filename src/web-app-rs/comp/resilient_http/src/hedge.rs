@@ -0,0 +1,75 @@
+use std::{
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use reqwest::{Request, Response};
+use tower::{Layer, Service, ServiceExt};
+
+use crate::{BoxFuture, Error, is_idempotent};
+
+#[derive(Clone)]
+pub(crate) struct HedgeLayer {
+    hedge_after: Option<Duration>,
+}
+
+impl HedgeLayer {
+    pub(crate) fn new(hedge_after: Option<Duration>) -> Self {
+        Self { hedge_after }
+    }
+}
+
+impl<S> Layer<S> for HedgeLayer {
+    type Service = Hedge<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Hedge { inner, hedge_after: self.hedge_after }
+    }
+}
+
+/// Sends a second request if the first one is slow and returns whichever answers first.
+#[derive(Clone)]
+pub(crate) struct Hedge<S> {
+    inner: S,
+    hedge_after: Option<Duration>,
+}
+
+impl<S> Service<Request> for Hedge<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Error;
+    type Future = BoxFuture<Result<Response, Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let hedged = match self.hedge_after {
+            Some(hedge_after) if is_idempotent(&request) => request.try_clone().map(|r| (r, hedge_after)),
+            _ => None,
+        };
+        let Some((hedged_request, hedge_after)) = hedged else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        let mut hedge_inner = self.inner.clone();
+        let mut first = Box::pin(self.inner.call(request));
+        Box::pin(async move {
+            tokio::select! {
+                r = &mut first => return r,
+                _ = tokio::time::sleep(hedge_after) => {}
+            }
+
+            log::info!("hedging {} {}", hedged_request.method(), hedged_request.url());
+            let second = hedge_inner.ready().await?.call(hedged_request);
+            tokio::select! {
+                r = first => r,
+                r = second => r,
+            }
+        })
+    }
+}
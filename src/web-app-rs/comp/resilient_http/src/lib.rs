@@ -0,0 +1,34 @@
+//! Resilience pipeline for the http clients of the downstream services.
+//!
+//! The stack (outer to inner) is retry -> circuit breaker -> hedging -> timeout -> `reqwest::Client`.
+//! Retries and hedging apply only to idempotent requests (GET, HEAD).
+
+mod breaker;
+mod client;
+mod config;
+mod error;
+mod hedge;
+mod retry;
+mod timeout;
+
+pub use client::*;
+pub use config::*;
+pub use error::*;
+
+pub(crate) use breaker::CircuitBreakerLayer;
+pub(crate) use hedge::HedgeLayer;
+pub(crate) use retry::RetryLayer;
+pub(crate) use timeout::TimeoutLayer;
+
+use std::{future::Future, pin::Pin};
+
+pub(crate) type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
+
+pub(crate) fn is_idempotent(request: &reqwest::Request) -> bool {
+    matches!(*request.method(), reqwest::Method::GET | reqwest::Method::HEAD)
+}
+
+/// 5xx and 429 responses count as failures for retries and the circuit breaker.
+pub(crate) fn is_failure_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
@@ -0,0 +1,98 @@
+use std::{
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use rand::Rng;
+use reqwest::{Request, Response};
+use tower::{Layer, Service, ServiceExt};
+
+use crate::{BoxFuture, Error, is_failure_status, is_idempotent};
+
+#[derive(Clone)]
+pub(crate) struct RetryLayer {
+    retries: u32,
+    base: Duration,
+}
+
+impl RetryLayer {
+    pub(crate) fn new(retries: u32, base: Duration) -> Self {
+        Self { retries, base }
+    }
+}
+
+impl<S> Layer<S> for RetryLayer {
+    type Service = Retry<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Retry { inner, retries: self.retries, base: self.base }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Retry<S> {
+    inner: S,
+    retries: u32,
+    base: Duration,
+}
+
+/// Full jitter: a random duration in `0..=base * 2^attempt`.
+fn backoff(base: Duration, attempt: u32) -> Duration {
+    let max = base.saturating_mul(2u32.saturating_pow(attempt));
+    Duration::from_millis(rand::thread_rng().gen_range(0..=max.as_millis() as u64))
+}
+
+fn should_retry(r: &Result<Response, Error>) -> bool {
+    match r {
+        Ok(response) => is_failure_status(response.status()),
+        Err(Error::CircuitOpen { .. }) => false,
+        Err(_) => true,
+    }
+}
+
+impl<S> Service<Request> for Retry<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Error;
+    type Future = BoxFuture<Result<Response, Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // the ready service is used for the first attempt, the retries wait for a clone to get ready
+        let inner = self.inner.clone();
+        let mut ready_inner = std::mem::replace(&mut self.inner, inner);
+
+        if self.retries == 0 || !is_idempotent(&request) {
+            return Box::pin(ready_inner.call(request));
+        }
+
+        let retries = self.retries;
+        let base = self.base;
+        Box::pin(async move {
+            let mut attempt = 0;
+            let mut next = request.try_clone();
+            let mut r = ready_inner.call(request).await;
+
+            while attempt < retries && should_retry(&r) {
+                let Some(request) = next else {
+                    break;
+                };
+                next = request.try_clone();
+
+                let delay = backoff(base, attempt);
+                log::info!("retry {} of {} {} in {:?}", attempt + 1, request.method(), request.url(), delay);
+                tokio::time::sleep(delay).await;
+
+                attempt += 1;
+                r = ready_inner.ready().await?.call(request).await;
+            }
+            r
+        })
+    }
+}
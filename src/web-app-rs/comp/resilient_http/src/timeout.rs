@@ -0,0 +1,70 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use reqwest::{Request, Response};
+use tower::{Layer, Service};
+
+use crate::{BoxFuture, Error};
+
+#[derive(Clone)]
+pub(crate) struct TimeoutLayer {
+    service: Arc<str>,
+    timeout: Duration,
+}
+
+impl TimeoutLayer {
+    pub(crate) fn new(service: Arc<str>, timeout: Duration) -> Self {
+        Self { service, timeout }
+    }
+}
+
+impl<S> Layer<S> for TimeoutLayer {
+    type Service = Timeout<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Timeout {
+            inner,
+            service: self.service.clone(),
+            timeout: self.timeout,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Timeout<S> {
+    inner: S,
+    service: Arc<str>,
+    timeout: Duration,
+}
+
+impl<S> Service<Request> for Timeout<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Error;
+    type Future = BoxFuture<Result<Response, Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let service = self.service.clone();
+        let timeout = self.timeout;
+        let fut = self.inner.call(request);
+        Box::pin(async move {
+            match tokio::time::timeout(timeout, fut).await {
+                Ok(r) => r,
+                Err(_) => {
+                    log::warn!("{service}: request timed out after {timeout:?}");
+                    Err(Error::Timeout { service: service.to_string() })
+                }
+            }
+        })
+    }
+}
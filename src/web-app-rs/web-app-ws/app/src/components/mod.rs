@@ -3,4 +3,4 @@ use leptos::prelude::*;
 
 mod nav_link;
 
-pub(crate) use nav_link::{NavLinkCb, NavLinkGr};
+pub(crate) use nav_link::{NavLinkCb, NavLinkGr, NavLinkScroll};
@@ -1,3 +1,5 @@
+use leptos::ev;
+use leptos_meta::Link;
 use leptos_router::location;
 
 use super::*;
@@ -19,6 +21,9 @@ fn path_from_url(url: &mut location::Url, q_param_name: &'static str, page_index
 
 const PAGE_INDEX_MIN: usize = 1;
 
+/// Pages shown on each side of the current one, the first and the last page are always shown.
+const WINDOW_RADIUS: usize = 2;
+
 #[derive(Clone, Debug)]
 pub struct NavLinkCb {
     pub page_index: usize,
@@ -27,65 +32,192 @@ pub struct NavLinkCb {
     pub url: location::Url,
 }
 
+impl NavLinkCb {
+    pub fn page_count(&self) -> usize {
+        page_count(self.count, self.page_size)
+    }
+
+    /// 1-based, clamped to the existing pages.
+    fn current_page(&self) -> usize {
+        (self.page_index + 1).clamp(PAGE_INDEX_MIN, self.page_count().max(PAGE_INDEX_MIN))
+    }
+}
+
+fn page_count(count: usize, page_size: usize) -> usize {
+    if page_size == 0 { 0 } else { count.div_ceil(page_size) }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PageLink {
+    Page(usize),
+    Ellipsis,
+}
+
+/// First and last page, the pages around `current` and ellipses for the gaps;
+/// a gap of a single page shows that page instead of an ellipsis.
+fn page_window(current: usize, page_count: usize, radius: usize) -> Vec<PageLink> {
+    let shown = (PAGE_INDEX_MIN..=page_count).filter(|&page| page == PAGE_INDEX_MIN || page == page_count || page.abs_diff(current) <= radius);
+
+    let mut links = Vec::new();
+    let mut last_shown = None;
+    for page in shown {
+        match last_shown.map(|last| page - last) {
+            Some(2) => links.push(PageLink::Page(page - 1)),
+            Some(gap) if gap > 2 => links.push(PageLink::Ellipsis),
+            _ => {}
+        }
+        links.push(PageLink::Page(page));
+        last_shown = Some(page);
+    }
+    links
+}
+
+/// Windowed pagination: first, previous, the pages around the current one with ellipses, next and last.
+/// Previous and next also go to the document head as `<link rel="prev|next">`.
 #[component]
 pub fn NavLinkGr(sig_cb: ReadSignal<Option<NavLinkCb>>, q_param_name: &'static str, css_active_class: String, fn_index_to_string: fn(usize) -> Option<String>) -> impl IntoView {
     view! {
         {move || {
             match sig_cb() {
-                None => {
-                    let _: () = view! {};
-                    ().into_any()
-                }
-                Some(cb) => {
-                    match cb.count {
-                        0 => {
-                            let _: () = view! {};
-                            ().into_any()
-                        }
-                        v => {
-                            let class = css_active_class.clone();
-                            let page_count = v / cb.page_size + 1;
-                            let page_range = std::ops::RangeInclusive::new(
-                                PAGE_INDEX_MIN,
-                                page_count,
-                            );
-                            let mut url = cb.url;
-                            let page_index = cb.page_index + 1;
-                            let current_page_index = if page_range.contains(&page_index) {
-                                page_index
-                            } else {
-                                *page_range.start()
-                            };
-                            page_range
-                                .map(move |i| {
-                                    let path = path_from_url(
-                                        &mut url,
-                                        q_param_name,
-                                        i,
-                                        fn_index_to_string,
-                                    );
-                                    if i == current_page_index {
-                                        // log !("NavLinkGr sig_cb: {:?} ", sig_cb());
-
-                                        // log!("NavLinkGr path: {}", path);
-                                        view! {
-                                            <a href=path class=class.clone() aria-current="page">
-                                                {i}
-                                            </a>
-                                        }
-                                            .into_any()
-                                    } else {
-
-                                        view! { <a href=path>{i}</a> }
-                                            .into_any()
-                                    }
-                                })
-                                .collect::<Vec<_>>()
+                Some(cb) if cb.page_count() > 1 => {
+                    let class = css_active_class.clone();
+                    let page_count = cb.page_count();
+                    let current_page_index = cb.current_page();
+                    let mut url = cb.url;
+                    let mut path = move |i: usize| path_from_url(&mut url, q_param_name, i, fn_index_to_string);
+
+                    // (label, title, rel, target page), no target at the first / last page
+                    let step_link = |label: &'static str, title: &'static str, rel: Option<&'static str>, target: Option<usize>, path: String| match target {
+                        Some(_) => {
+                            view! {
+                                <a href=path title=title rel=rel>
+                                    {label}
+                                </a>
+                            }
                                 .into_any()
                         }
-                    }
+                        None => view! { <span aria-disabled="true">{label}</span> }.into_any(),
+                    };
+
+                    let prev = (current_page_index > PAGE_INDEX_MIN).then(|| current_page_index - 1);
+                    let next = (current_page_index < page_count).then(|| current_page_index + 1);
+
+                    let prev_path = prev.map(&mut path);
+                    let next_path = next.map(&mut path);
+                    let head_links = (
+                        prev_path.clone().map(|href| view! { <Link rel="prev" href=href /> }),
+                        next_path.clone().map(|href| view! { <Link rel="next" href=href /> }),
+                    );
+
+                    let first = step_link("«", "First page", None, prev, path(PAGE_INDEX_MIN));
+                    let prev = step_link("‹", "Previous page", Some("prev"), prev, prev_path.unwrap_or_default());
+
+                    let pages = page_window(current_page_index, page_count, WINDOW_RADIUS)
+                        .into_iter()
+                        .map(|link| match link {
+                            PageLink::Page(i) if i == current_page_index => {
+                                view! {
+                                    <a href=path(i) class=class.clone() aria-current="page">
+                                        {i}
+                                    </a>
+                                }
+                                    .into_any()
+                            }
+                            PageLink::Page(i) => view! { <a href=path(i)>{i}</a> }.into_any(),
+                            PageLink::Ellipsis => view! { <span class="ellipsis">"…"</span> }.into_any(),
+                        })
+                        .collect::<Vec<_>>();
+
+                    let next = step_link("›", "Next page", Some("next"), next, next_path.unwrap_or_default());
+                    let last = step_link("»", "Last page", None, (current_page_index < page_count).then_some(page_count), path(page_count));
+
+                    view! { {head_links} {first} {prev} {pages} {next} {last} }.into_any()
+                }
+                _ => {
+                    let _: () = view! {};
+                    ().into_any()
                 }
             }
         }}
     }
 }
+
+/// Infinite scrolling over the pages of `sig_cb`: calls `on_next` with the next page index (0-based)
+/// when the end of the page comes into view. The "Load more" link goes to the next page without script.
+#[component]
+pub fn NavLinkScroll(sig_cb: ReadSignal<Option<NavLinkCb>>, q_param_name: &'static str, fn_index_to_string: fn(usize) -> Option<String>, on_next: Callback<usize>, #[prop(into)] sig_loading: Signal<bool>) -> impl IntoView {
+    let next_page_index = move || sig_cb.with(|cb| cb.as_ref().filter(|cb| cb.page_index + 1 < cb.page_count()).map(|cb| cb.page_index + 1));
+
+    let load_next = move || {
+        if let Some(page_index) = next_page_index()
+            && !sig_loading.get_untracked()
+        {
+            on_next.run(page_index);
+        }
+    };
+
+    // less than half a screen left to scroll
+    let near_end = move || {
+        let window = window();
+        let viewport = window.inner_height().ok().and_then(|h| h.as_f64()).unwrap_or_default();
+        let scrolled = window.scroll_y().unwrap_or_default() + viewport;
+        document().body().is_some_and(|body| scrolled >= body.scroll_height() as f64 - viewport / 2.0)
+    };
+
+    let handle = window_event_listener(ev::scroll, move |_| {
+        if near_end() {
+            load_next();
+        }
+    });
+    on_cleanup(move || handle.remove());
+
+    view! {
+        <div>
+            {move || {
+                sig_cb()
+                    .zip(next_page_index())
+                    .map(|(cb, page_index)| {
+                        let mut url = cb.url;
+                        let href = path_from_url(&mut url, q_param_name, page_index + 1, fn_index_to_string);
+                        view! {
+                            <a
+                                href=href
+                                rel="next"
+                                on:click=move |e| {
+                                    e.prevent_default();
+                                    load_next();
+                                }
+                            >
+                                {move || if sig_loading.get() { "Loading…" } else { "Load more" }}
+                            </a>
+                        }
+                    })
+            }}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PageLink::*;
+
+    #[test]
+    fn test_page_count() {
+        assert_eq!(page_count(0, 9), 0);
+        assert_eq!(page_count(9, 9), 1);
+        assert_eq!(page_count(10, 9), 2);
+        assert_eq!(page_count(18, 9), 2);
+        assert_eq!(page_count(5, 0), 0);
+    }
+
+    #[test]
+    fn test_page_window() {
+        assert_eq!(page_window(1, 3, 2), vec![Page(1), Page(2), Page(3)]);
+        assert_eq!(page_window(1, 20, 2), vec![Page(1), Page(2), Page(3), Ellipsis, Page(20)]);
+        assert_eq!(page_window(10, 20, 2), vec![Page(1), Ellipsis, Page(8), Page(9), Page(10), Page(11), Page(12), Ellipsis, Page(20)]);
+        // a single hidden page is shown instead of an ellipsis
+        assert_eq!(page_window(5, 20, 2), vec![Page(1), Page(2), Page(3), Page(4), Page(5), Page(6), Page(7), Ellipsis, Page(20)]);
+        assert_eq!(page_window(20, 20, 2), vec![Page(1), Ellipsis, Page(18), Page(19), Page(20)]);
+    }
+}
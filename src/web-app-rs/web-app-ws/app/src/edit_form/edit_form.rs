@@ -35,10 +35,11 @@ where
 }
 
 #[component]
-pub fn EditForm<T>(init_data_resource: Resource<Result<T, crate::AppError>>, children: Children, form_action: Action<T, Result<(T, Option<FormErrors>), crate::AppError>>, on_ok: fn(T)) -> impl IntoView
+pub fn EditForm<T>(init_data_resource: Resource<Result<T, crate::AppError>>, children: Children, form_action: Action<T, Result<(T, Option<FormErrors>), crate::AppError>>, on_ok: impl Fn(T) + Send + Sync + 'static) -> impl IntoView
 where
     T: Default + Sync + Clone + Send + 'static,
 {
+    let on_ok = Callback::new(on_ok);
     let form_data_validated = RwSignal::new((T::default(), FormErrors::default()));
     let form_data_current = RwSignal::new(T::default());
     let errors = RwSignal::new(Vec::<crate::AppError>::default());
@@ -68,7 +69,7 @@ where
                             form_data_current.set(data);
                         }
                         Ok((data, None)) => {
-                            on_ok(data);
+                            on_ok.run(data);
                         }
                         Err(e) => {
                             errors.set(vec![e]);
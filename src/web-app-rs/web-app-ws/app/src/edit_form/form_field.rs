@@ -8,22 +8,26 @@ pub trait FormField<FormData: Send + Clone + 'static> {
 #[derive(Clone)]
 pub enum Field<FormData: Send + Clone + 'static> {
     TextFormField(super::FormFieldText<FormData>),
+    SelectFormField(super::FormFieldSelect<FormData>),
 }
 
 impl<FormData: Send + Clone + 'static> FormField<FormData> for Field<FormData> {
     fn update_value(&self, data: &FormData) -> FormData {
         match self {
             Field::TextFormField(field) => field.update_value(data),
+            Field::SelectFormField(field) => field.update_value(data),
         }
     }
     fn value_validated_signal(&self) -> RwSignal<Option<Vec<String>>> {
         match self {
             Field::TextFormField(field) => field.value_validated_signal(),
+            Field::SelectFormField(field) => field.value_validated_signal(),
         }
     }
     fn name(&self) -> &'static str {
         match self {
             Field::TextFormField(field) => field.name(),
+            Field::SelectFormField(field) => field.name(),
         }
     }
 }
@@ -0,0 +1,73 @@
+use leptos::{html::Select, prelude::*};
+
+use super::{Field, FormField, FormFieldSelect};
+
+/// `options` are (value, label) pairs.
+#[component]
+pub fn InPutSelect<T: Sync + Send + Clone + 'static + std::fmt::Debug>(name: &'static str, setter: fn(&T, &str) -> T, getter: fn(&T) -> &str, options: Vec<(String, String)>) -> impl IntoView {
+    // Get the form state from context
+    let form_state = expect_context::<super::FormState<T, Field<T>>>();
+
+    let select_ref = NodeRef::<Select>::new();
+
+    let field_cb = Field::SelectFormField(FormFieldSelect::new(name, setter, RwSignal::new(None), select_ref));
+
+    let validated_sig = field_cb.value_validated_signal();
+
+    let value_sig_ = Signal::derive(move || {
+        let (v, e) = form_state.form_data_validated.get();
+        let value = getter(&v).to_string();
+        let is_err = match e.get(name) {
+            Some(errors) => {
+                validated_sig.set(Some(errors.clone()));
+                true
+            }
+            None => {
+                validated_sig.set(None);
+                false
+            }
+        };
+
+        (value, is_err)
+    });
+    form_state.fields.lock().unwrap().push(field_cb.clone());
+
+    let vf = move || {
+        let field_cb = field_cb.clone();
+        let (value, is_err) = value_sig_.get();
+        view! {
+            <select
+                name=name
+                class=if is_err { "invalid" } else { "" }
+                aria-invalid=is_err.to_string()
+                prop:value=value.clone()
+                node_ref=select_ref
+                on:change=move |ev| {
+                    ev.prevent_default();
+                    let new_data = field_cb.update_value(&form_state.form_data_current.get());
+                    form_state
+                        .form_data_current
+                        .update(|v| {
+                            *v = new_data;
+                        });
+                }
+            >
+                <option value="" selected=value.is_empty()>
+                    "-- select --"
+                </option>
+                {options
+                    .iter()
+                    .map(|(option_value, label)| {
+                        view! {
+                            <option value=option_value.clone() selected=*option_value == value>
+                                {label.clone()}
+                            </option>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </select>
+        }
+    };
+
+    view! { {vf} }
+}
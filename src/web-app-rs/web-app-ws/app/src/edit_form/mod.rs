@@ -10,5 +10,11 @@ use form_field_text::*;
 mod in_put_text;
 pub(crate) use in_put_text::*;
 
+mod form_field_select;
+use form_field_select::*;
+
+mod in_put_select;
+pub(crate) use in_put_select::*;
+
 mod validation_component;
 pub(crate) use validation_component::*;
@@ -8,8 +8,7 @@ pub fn CartMenu() -> impl IntoView {
     Effect::new(move || {
         Action::new(move |_: &()| {
             let context = expect_context::<BasketStateInfoContext>();
-            let auth_context = expect_context::<auth::client::UserInfoCntxt>();
-            basket_ordering::basket_state::client::refresh_basket_state_info(context, auth_context)
+            basket_ordering::basket_state::client::refresh_basket_state_info(context)
         })
         .dispatch(());
     });
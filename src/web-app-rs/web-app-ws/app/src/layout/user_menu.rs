@@ -39,6 +39,14 @@ pub fn UserMenu() -> impl IntoView {
                             <a class="dropdown-item" href="user/orders">
                                 My orders
                             </a>
+                            <a class="dropdown-item" href="user/addresses">
+                                My addresses
+                            </a>
+                            <Show when=move || auth::client::has_role(auth::ADMIN_ROLE)>
+                                <a class="dropdown-item" href="admin/catalog">
+                                    Catalog admin
+                                </a>
+                            </Show>
                             <a class="dropdown-item" href="user/logout">
                                 Log out
                             </a>
@@ -96,12 +96,8 @@ pub mod app {
                         <Route path=StaticSegment("/") view=CatalogPage />
                         <Route path=path!("/item/:item_id") view=ItemPage />
 
-                        <ProtectedRoute
-                            path=path!("/cart")
-                            view=CartPage
-                            condition=move || Some(auth::client::is_logged_in())
-                            redirect_path=|| auth::login_url_from_current_url()
-                        />
+                        // guests have a basket in their session, checkout needs the sign-in
+                        <Route path=path!("/cart") view=CartPage />
 
                         <ProtectedRoute
                             path=path!("/checkout")
@@ -115,6 +111,49 @@ pub mod app {
                             condition=move || Some(auth::client::is_logged_in())
                             redirect_path=|| auth::login_url_from_current_url()
                         />
+                        <ProtectedRoute
+                            path=path!("/user/orders/:id")
+                            view=OrderDetailsPage
+                            condition=move || Some(auth::client::is_logged_in())
+                            redirect_path=|| auth::login_url_from_current_url()
+                        />
+                        <ProtectedRoute
+                            path=path!("/user/addresses")
+                            view=AddressesPage
+                            condition=move || Some(auth::client::is_logged_in())
+                            redirect_path=|| auth::login_url_from_current_url()
+                        />
+                        <ProtectedRoute
+                            path=path!("/user/addresses/new")
+                            view=AddressPage
+                            condition=move || Some(auth::client::is_logged_in())
+                            redirect_path=|| auth::login_url_from_current_url()
+                        />
+                        <ProtectedRoute
+                            path=path!("/user/addresses/:address_id")
+                            view=AddressPage
+                            condition=move || Some(auth::client::is_logged_in())
+                            redirect_path=|| auth::login_url_from_current_url()
+                        />
+
+                        <ProtectedRoute
+                            path=path!("/admin/catalog")
+                            view=|| view! { <AdminOnly><AdminCatalogPage /></AdminOnly> }
+                            condition=move || Some(auth::client::is_logged_in())
+                            redirect_path=|| auth::login_url_from_current_url()
+                        />
+                        <ProtectedRoute
+                            path=path!("/admin/catalog/new")
+                            view=|| view! { <AdminOnly><AdminCatalogItemPage /></AdminOnly> }
+                            condition=move || Some(auth::client::is_logged_in())
+                            redirect_path=|| auth::login_url_from_current_url()
+                        />
+                        <ProtectedRoute
+                            path=path!("/admin/catalog/:item_id")
+                            view=|| view! { <AdminOnly><AdminCatalogItemPage /></AdminOnly> }
+                            condition=move || Some(auth::client::is_logged_in())
+                            redirect_path=|| auth::login_url_from_current_url()
+                        />
                     </Routes>
 
                     <FooterBar />
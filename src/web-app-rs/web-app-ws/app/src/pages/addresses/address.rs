@@ -0,0 +1,253 @@
+use leptos_meta::Title;
+use leptos_router::{hooks::use_navigate, hooks::use_params, params::Params};
+use stylers::style_sheet;
+
+#[cfg(feature = "ssr")]
+use basket_ordering::addresses::{
+    service::AddressServiceContext,
+    types::{SavedAddress, ShippingAddress},
+};
+use error_template::ErrorTemplate;
+
+#[cfg(feature = "ssr")]
+use valitron::{
+    available::{Message, Required, Trim},
+    register::string::Validator,
+    rule::string::StringRuleExt,
+};
+
+use crate::edit_form::*;
+use field::*;
+
+use super::*;
+
+#[derive(Params, PartialEq, Clone, Debug)]
+pub struct AddressIdParams {
+    address_id: Option<i64>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddressFormData {
+    address_id: Option<i64>,
+    street: String,
+    city: String,
+    state: String,
+    zip_code: String,
+    country: String,
+}
+
+impl AddressFormData {
+    fn set_street(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.street = value.to_string();
+        tmp
+    }
+    fn get_street(&self) -> &str {
+        &self.street
+    }
+    fn set_city(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.city = value.to_string();
+        tmp
+    }
+    fn get_city(&self) -> &str {
+        &self.city
+    }
+    fn set_state(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.state = value.to_string();
+        tmp
+    }
+    fn get_state(&self) -> &str {
+        &self.state
+    }
+    fn set_zip_code(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.zip_code = value.to_string();
+        tmp
+    }
+    fn get_zip_code(&self) -> &str {
+        &self.zip_code
+    }
+    fn set_country(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.country = value.to_string();
+        tmp
+    }
+    fn get_country(&self) -> &str {
+        &self.country
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl AddressFormData {
+    fn from_raw(input: &mut AddressFormData) -> Result<Self, Validator<Message>> {
+        let valid = Validator::new()
+            .insert("street", &mut input.street, Trim.and(Required))
+            .insert("city", &mut input.city, Trim.and(Required))
+            .insert("state", &mut input.state, Trim.and(Required))
+            .insert("zip_code", &mut input.zip_code, Trim.and(Required))
+            .insert("country", &mut input.country, Trim.and(Required));
+
+        valid.validate(input.clone())
+    }
+
+    fn to_address(&self) -> ShippingAddress {
+        ShippingAddress {
+            street: self.street.clone(),
+            city: self.city.clone(),
+            state: self.state.clone(),
+            zip_code: self.zip_code.clone(),
+            country: self.country.clone(),
+        }
+    }
+}
+
+#[server]
+#[middleware(auth::RequireAuth)]
+async fn get_address_form_data(address_id: Option<i64>) -> Result<AddressFormData, crate::AppError> {
+    let Some(address_id) = address_id else {
+        return Ok(AddressFormData::default());
+    };
+
+    let address_service = expect_context::<AddressServiceContext>().service;
+    let saved = address_service.get_address(address_id).await?.ok_or(crate::AppError::Other(format!("Address {address_id} not found")))?;
+
+    Ok(AddressFormData {
+        address_id: Some(saved.id),
+        street: saved.address.street,
+        city: saved.address.city,
+        state: saved.address.state,
+        zip_code: saved.address.zip_code,
+        country: saved.address.country,
+    })
+}
+
+#[server]
+#[middleware(auth::RequireAuth)]
+async fn submit_address_form_data(data: AddressFormData) -> Result<(AddressFormData, Option<FormErrors>), crate::AppError> {
+    let mut data = data.clone();
+    if let Err(e) = AddressFormData::from_raw(&mut data) {
+        return Ok((data, Some(to_form_errors(e))));
+    }
+
+    let address_service = expect_context::<AddressServiceContext>().service;
+    match data.address_id {
+        Some(id) => {
+            if !address_service.update_address(SavedAddress { id, address: data.to_address() }).await? {
+                return Err(crate::AppError::Other(format!("Address {id} not found")));
+            }
+        }
+        None => {
+            address_service.add_address(data.to_address()).await?;
+        }
+    }
+
+    Ok((data, None))
+}
+
+#[component]
+fn AddressFields(class_name: &'static str, is_new: bool) -> impl IntoView {
+    // type erased, the whole form doesn't fit the type depth limit otherwise
+    view! { class=class_name,
+        <div class="form-section">
+            <h2>{if is_new { "New address" } else { "Address" }}</h2>
+            <label>
+                Address
+                <InPutText name=field!(street @ AddressFormData) setter=AddressFormData::set_street getter=AddressFormData::get_street />
+                <ValidationMessage<AddressFormData> _phantom=std::marker::PhantomData name=field!(street @ AddressFormData) />
+            </label>
+            <div class="form-group">
+                <div class="form-group-item">
+                    <label>
+                        City
+                        <InPutText name=field!(city @ AddressFormData) setter=AddressFormData::set_city getter=AddressFormData::get_city />
+                        <ValidationMessage<AddressFormData> _phantom=std::marker::PhantomData name=field!(city @ AddressFormData) />
+                    </label>
+                </div>
+                <div class="form-group-item">
+                    <label>
+                        State
+                        <InPutText name=field!(state @ AddressFormData) setter=AddressFormData::set_state getter=AddressFormData::get_state />
+                        <ValidationMessage<AddressFormData> _phantom=std::marker::PhantomData name=field!(state @ AddressFormData) />
+                    </label>
+                </div>
+                <div class="form-group-item">
+                    <label>
+                        Zip code
+                        <InPutText name=field!(zip_code @ AddressFormData) setter=AddressFormData::set_zip_code getter=AddressFormData::get_zip_code />
+                        <ValidationMessage<AddressFormData> _phantom=std::marker::PhantomData name=field!(zip_code @ AddressFormData) />
+                    </label>
+                </div>
+            </div>
+            <label>
+                Country
+                <InPutText name=field!(country @ AddressFormData) setter=AddressFormData::set_country getter=AddressFormData::get_country />
+                <ValidationMessage<AddressFormData> _phantom=std::marker::PhantomData name=field!(country @ AddressFormData) />
+            </label>
+        </div>
+    }
+    .into_any()
+}
+
+#[component]
+pub fn AddressPage() -> impl IntoView {
+    let params = use_params::<AddressIdParams>();
+    let sig_address_id = Signal::derive(move || params.with(|p| p.as_ref().map(|d| d.address_id).map_err(|_| crate::AppError::Other("Invalid Address Id".to_string()))));
+
+    let class_name = style_sheet!("./app/src/pages/addresses/address.css");
+
+    let init_data_res = Resource::new(move || sig_address_id.get(), |address_id| async move { get_address_form_data(address_id?).await });
+
+    let form_action = Action::new(move |data: &AddressFormData| {
+        let data = data.clone();
+        async move { submit_address_form_data(data.clone()).await }
+    });
+
+    let address_view = move || {
+        let is_new = matches!(sig_address_id.get_untracked(), Ok(None));
+        view! { class=class_name,
+            <div class="address-form">
+                <EditForm
+                    init_data_resource=init_data_res
+                    form_action=form_action
+                    on_ok=|_data| {
+                        let navigate = use_navigate();
+                        navigate("/user/addresses", Default::default());
+                    }
+                >
+                    <div class="form">
+                        <AddressFields class_name is_new />
+                        <div class="form-section">
+                            <div class="form-buttons">
+                                <a href="user/addresses" class="button button-secondary">
+                                    <img role="presentation" src="icons/arrow-left.svg" />
+                                    Back to your addresses
+                                </a>
+                                <button class="button button-primary" type="submit">
+                                    Save
+                                </button>
+                            </div>
+                        </div>
+                    </div>
+                    <ValidationSummary<AddressFormData> _phantom=std::marker::PhantomData />
+                </EditForm>
+            </div>
+        }
+    };
+
+    crate::app::page_header::set_title("Addresses");
+    view! { class=class_name,
+        <Title text="Addresses | AdventureWorks" />
+        <Transition fallback=move || view! { <p>"Loading data..."</p> }>
+            <ErrorBoundary fallback=|errors| {
+                view! { <ErrorTemplate errors /> }
+            }>
+
+                {address_view}
+
+            </ErrorBoundary>
+        </Transition>
+    }
+}
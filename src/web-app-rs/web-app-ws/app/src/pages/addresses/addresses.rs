@@ -0,0 +1,89 @@
+use leptos_meta::Title;
+use stylers::style_sheet;
+
+use basket_ordering::addresses::server_api::{delete_address, get_addresses};
+use error_template::ErrorTemplate;
+
+use super::*;
+
+#[component]
+pub fn AddressesPage() -> impl IntoView {
+    let delete_action = Action::new(move |address_id: &i64| {
+        let address_id = *address_id;
+        async move { delete_address(address_id).await }
+    });
+
+    let addresses = Resource::new(move || delete_action.version().get(), |_| async move { get_addresses().await });
+
+    let class_name = style_sheet!("./app/src/pages/addresses/addresses.css");
+
+    let addresses_view = move || {
+        Suspend::new(async move {
+            addresses.await.map(|addresses| {
+                if addresses.is_empty() {
+                    return view! { class=class_name, <p>"You have no saved addresses yet."</p> }.into_any();
+                }
+                view! { class=class_name,
+                    <ul class="addresses-list">
+                        {addresses
+                            .into_iter()
+                            .map(|saved| {
+                                let address_id = saved.id;
+                                view! { class=class_name,
+                                    <li class="address-item">
+                                        <address>
+                                            <div>{saved.address.street.clone()}</div>
+                                            <div>{format!("{} {}", saved.address.zip_code, saved.address.city)}</div>
+                                            <div>{saved.address.state.clone()}</div>
+                                            <div>{saved.address.country.clone()}</div>
+                                        </address>
+                                        <div class="address-actions">
+                                            <a class="button button-secondary" href=format!("user/addresses/{address_id}")>
+                                                "Edit"
+                                            </a>
+                                            <button
+                                                class="button button-secondary"
+                                                disabled=move || delete_action.pending().get()
+                                                on:click=move |_| {
+                                                    delete_action.dispatch(address_id);
+                                                }
+                                            >
+                                                "Delete"
+                                            </button>
+                                        </div>
+                                    </li>
+                                }
+                            })
+                            .collect::<Vec<_>>()}
+                    </ul>
+                }
+                .into_any()
+            })
+        })
+    };
+
+    // a failed delete is shown above the list, the addresses stay
+    let delete_error_view = move || match delete_action.value().get() {
+        Some(Err(e)) => Some(view! { <p class="validation-message">{format!("The address could not be deleted: {e}")}</p> }),
+        _ => None,
+    };
+
+    crate::app::page_header::set_title("Addresses");
+
+    view! { class=class_name,
+        <Title text="Addresses | AdventureWorks" />
+        <div class="addresses">
+            <div class="addresses-toolbar">
+                <a class="button button-primary" href="user/addresses/new">
+                    "Add address"
+                </a>
+            </div>
+            {delete_error_view}
+            <Suspense fallback=move || view! { <p>"Loading data..."</p> }>
+                <ErrorBoundary fallback=|errors| view! { <ErrorTemplate errors /> }>
+                    {addresses_view}
+                </ErrorBoundary>
+            </Suspense>
+        </div>
+    }
+}
@@ -0,0 +1,9 @@
+use leptos::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+mod addresses;
+pub(crate) use addresses::AddressesPage;
+
+mod address;
+pub(crate) use address::AddressPage;
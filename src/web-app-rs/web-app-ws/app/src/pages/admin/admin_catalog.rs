@@ -0,0 +1,121 @@
+use leptos_meta::Title;
+use leptos_router::hooks::{self, use_query};
+use stylers::style_sheet;
+
+use catalog::server_api::{delete_catalog_item, get_catalog_items};
+use error_template::ErrorTemplate;
+
+use crate::{
+    components::{NavLinkCb, NavLinkGr},
+    pages::parameter_from_query,
+};
+
+use super::*;
+
+const PAGE_SIZE: usize = 20;
+
+#[component]
+pub fn AdminCatalogPage() -> impl IntoView {
+    let sig_url: ReadSignal<leptos_router::location::Url> = hooks::use_url();
+
+    let page = use_query::<parameter_from_query::Page>();
+    let sig_page_index = Signal::derive(move || page.with(|page| page.as_ref().ok().and_then(|d| d.page).map(|p| if p == 0 { p } else { p - 1 }).unwrap_or(0)));
+
+    let delete_action = Action::new(move |item_id: &usize| {
+        let item_id = *item_id;
+        async move { delete_catalog_item(item_id).await }
+    });
+
+    let catalog = Resource::new(move || (sig_page_index(), delete_action.version().get()), |(page, _)| async move { get_catalog_items(page, PAGE_SIZE, None, None, None).await });
+
+    let sig_cb: RwSignal<Option<NavLinkCb>> = RwSignal::new(None);
+
+    let class_name = style_sheet!("./app/src/pages/admin/admin_catalog.css");
+
+    let items_view = move || {
+        Suspend::new(async move {
+            catalog.await.map(|catalog| {
+                sig_cb.set(Some(NavLinkCb {
+                    url: sig_url.get_untracked(),
+                    page_index: catalog.page_index,
+                    page_size: catalog.page_size,
+                    count: catalog.count,
+                }));
+                view! { class=class_name,
+                    <ul class="admin-catalog-list">
+                        <li class="admin-catalog-header admin-catalog-item">
+                            <div>{"Name"}</div>
+                            <div>{"Brand"}</div>
+                            <div>{"Type"}</div>
+                            <div class="price-header">{"Price"}</div>
+                            <div></div>
+                        </li>
+                        {catalog
+                            .data
+                            .into_iter()
+                            .map(|item| {
+                                let item_id = item.id as usize;
+                                view! { class=class_name,
+                                    <li class="admin-catalog-item">
+                                        <div>
+                                            <a href=format!("admin/catalog/{item_id}")>{item.name.clone()}</a>
+                                        </div>
+                                        <div>{item.catalog_brand.map(|b| b.brand).unwrap_or_default()}</div>
+                                        <div>{item.catalog_type.map(|t| t.type_name).unwrap_or_default()}</div>
+                                        <div class="item-price">{format!("${:.2}", item.price)}</div>
+                                        <div class="item-actions">
+                                            <a class="button button-secondary" href=format!("admin/catalog/{item_id}")>
+                                                "Edit"
+                                            </a>
+                                            <button
+                                                class="button button-secondary"
+                                                disabled=move || delete_action.pending().get()
+                                                on:click=move |_| {
+                                                    delete_action.dispatch(item_id);
+                                                }
+                                            >
+                                                "Delete"
+                                            </button>
+                                        </div>
+                                    </li>
+                                }
+                            })
+                            .collect::<Vec<_>>()}
+                    </ul>
+                }
+            })
+        })
+    };
+
+    let delete_error_view = move || match delete_action.value().get() {
+        Some(Err(e)) => Err(e),
+        _ => Ok(()),
+    };
+
+    crate::app::page_header::set_title("Catalog administration");
+
+    view! { class=class_name,
+        <Title text="Catalog administration | AdventureWorks" />
+        <div class="admin-catalog">
+            <div class="admin-catalog-toolbar">
+                <a class="button button-primary" href="admin/catalog/new">
+                    "New item"
+                </a>
+            </div>
+            <Suspense fallback=move || view! { <p>"Loading data..."</p> }>
+                <ErrorBoundary fallback=|errors| view! { <ErrorTemplate errors /> }>
+                    {delete_error_view}
+                    {items_view}
+                    <div class="page-links">
+                        <NavLinkGr
+                            css_active_class="active-page".to_string()
+                            sig_cb=sig_cb.read_only()
+                            q_param_name="page"
+                            fn_index_to_string=|i| if i == 1 { None } else { Some(i.to_string()) }
+                        />
+                    </div>
+                </ErrorBoundary>
+            </Suspense>
+        </div>
+    }
+}
@@ -0,0 +1,382 @@
+use leptos_meta::Title;
+use leptos_router::{hooks::use_navigate, hooks::use_params, params::Params};
+use stylers::style_sheet;
+
+use catalog::server_api::{get_brands, get_types};
+#[cfg(feature = "ssr")]
+use catalog::{service::CatalogServiceContext, types::CatalogItem};
+use error_template::ErrorTemplate;
+
+#[cfg(feature = "ssr")]
+use valitron::{
+    available::{Message, Required, Trim},
+    register::string::Validator,
+    rule::string::StringRuleExt,
+};
+
+use crate::edit_form::*;
+use crate::services::product_image_url_provider::ProductImageUrlContext;
+use field::*;
+
+use super::*;
+
+#[derive(Params, PartialEq, Clone, Debug)]
+pub struct AdminItemIdParams {
+    item_id: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CatalogItemFormData {
+    item_id: Option<i32>,
+    name: String,
+    description: String,
+    price: String,
+    brand_id: String,
+    type_id: String,
+    picture_file_name: String,
+}
+
+impl CatalogItemFormData {
+    fn set_name(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.name = value.to_string();
+        tmp
+    }
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+    fn set_description(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.description = value.to_string();
+        tmp
+    }
+    fn get_description(&self) -> &str {
+        &self.description
+    }
+    fn set_price(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.price = value.to_string();
+        tmp
+    }
+    fn get_price(&self) -> &str {
+        &self.price
+    }
+    fn set_brand_id(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.brand_id = value.to_string();
+        tmp
+    }
+    fn get_brand_id(&self) -> &str {
+        &self.brand_id
+    }
+    fn set_type_id(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.type_id = value.to_string();
+        tmp
+    }
+    fn get_type_id(&self) -> &str {
+        &self.type_id
+    }
+    fn set_picture_file_name(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.picture_file_name = value.to_string();
+        tmp
+    }
+    fn get_picture_file_name(&self) -> &str {
+        &self.picture_file_name
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl CatalogItemFormData {
+    fn from_raw(input: &mut CatalogItemFormData) -> Result<Self, Validator<Message>> {
+        let valid = Validator::new()
+            .insert("name", &mut input.name, Trim.and(Required))
+            .insert("description", &mut input.description, Trim)
+            .insert("price", &mut input.price, Trim.and(Required))
+            .insert("brand_id", &mut input.brand_id, Trim.and(Required))
+            .insert("type_id", &mut input.type_id, Trim.and(Required))
+            .insert("picture_file_name", &mut input.picture_file_name, Trim);
+
+        valid.validate(input.clone())
+    }
+
+    /// Checks the fields that valitron can't parse; `errors` gets a message for every invalid field.
+    fn parse_values(&self, errors: &mut FormErrors) -> Option<(rust_decimal::Decimal, usize, usize)> {
+        let mut add_error = |name: &str, msg: &str| {
+            let field_errors = errors.entry(name.to_string()).or_default();
+            if field_errors.is_empty() {
+                field_errors.push(msg.to_string());
+            }
+        };
+
+        let price = self.price.parse::<rust_decimal::Decimal>().ok().filter(|p| !p.is_sign_negative());
+        if price.is_none() {
+            add_error("price", "must be a positive number");
+        }
+        let brand_id = self.brand_id.parse::<usize>().ok();
+        if brand_id.is_none() {
+            add_error("brand_id", "must be selected");
+        }
+        let type_id = self.type_id.parse::<usize>().ok();
+        if type_id.is_none() {
+            add_error("type_id", "must be selected");
+        }
+
+        Some((price?, brand_id?, type_id?))
+    }
+}
+
+#[server]
+#[middleware(auth::RequireRole(auth::ADMIN_ROLE))]
+async fn get_catalog_item_form_data(item_id: Option<usize>) -> Result<CatalogItemFormData, crate::AppError> {
+    let Some(item_id) = item_id else {
+        return Ok(CatalogItemFormData::default());
+    };
+
+    let catalog_service = expect_context::<CatalogServiceContext>().service;
+    let item = catalog_service.get_catalog_item(item_id).await.map_err(catalog::server::to_app_error)?.ok_or(crate::AppError::Other(format!("Catalog item {item_id} not found")))?;
+
+    Ok(CatalogItemFormData {
+        item_id: Some(item.id),
+        name: item.name,
+        description: item.description,
+        price: item.price.to_string(),
+        brand_id: item.catalog_brand_id.to_string(),
+        type_id: item.catalog_type_id.to_string(),
+        picture_file_name: item.picture_file_name.unwrap_or_default(),
+    })
+}
+
+#[server]
+#[middleware(auth::RequireRole(auth::ADMIN_ROLE))]
+async fn submit_catalog_item_form_data(data: CatalogItemFormData) -> Result<(CatalogItemFormData, Option<FormErrors>), crate::AppError> {
+    let mut data = data.clone();
+    let mut errors = match CatalogItemFormData::from_raw(&mut data) {
+        Ok(_) => FormErrors::default(),
+        Err(e) => to_form_errors(e),
+    };
+
+    let values = data.parse_values(&mut errors);
+    let (price, brand_id, type_id) = match values {
+        Some(values) if errors.is_empty() => values,
+        _ => return Ok((data, Some(errors))),
+    };
+
+    let catalog_service = expect_context::<CatalogServiceContext>().service;
+
+    // keep the fields the form doesn't edit (stock, thresholds, ...) on update
+    let mut item = match data.item_id {
+        Some(item_id) => catalog_service.get_catalog_item(item_id as usize).await.map_err(catalog::server::to_app_error)?.ok_or(crate::AppError::Other(format!("Catalog item {item_id} not found")))?,
+        None => CatalogItem::new(),
+    };
+
+    item.name = data.name.clone();
+    item.description = data.description.clone();
+    item.price = price;
+    item.catalog_brand_id = brand_id;
+    item.catalog_type_id = type_id;
+    item.picture_file_name = Some(data.picture_file_name.clone()).filter(|f| !f.is_empty());
+    // the related objects would override the ids on the backend
+    item.catalog_brand = None;
+    item.catalog_type = None;
+
+    if data.item_id.is_some() {
+        catalog_service.update_catalog_item(item).await.map_err(catalog::server::to_app_error)?;
+    } else {
+        catalog_service.create_catalog_item(item).await.map_err(catalog::server::to_app_error)?;
+    }
+
+    Ok((data, None))
+}
+
+#[component]
+fn CatalogItemFields(class_name: &'static str, is_new: bool, brand_options: Vec<(String, String)>, type_options: Vec<(String, String)>, picture_view: AnyView) -> impl IntoView {
+    // type erased, the whole form doesn't fit the type depth limit otherwise
+    view! { class=class_name,
+        <div class="form-section">
+            <h2>{if is_new { "New catalog item" } else { "Catalog item" }}</h2>
+            <label>
+                Name
+                <InPutText
+                    name=field!(name @ CatalogItemFormData)
+                    setter=CatalogItemFormData::set_name
+                    getter=CatalogItemFormData::get_name
+                />
+                <ValidationMessage<
+                CatalogItemFormData,
+            >
+                    _phantom=std::marker::PhantomData
+                    name=field!(name @ CatalogItemFormData)
+                />
+            </label>
+            <label>
+                Description
+                <InPutText
+                    name=field!(description @ CatalogItemFormData)
+                    setter=CatalogItemFormData::set_description
+                    getter=CatalogItemFormData::get_description
+                />
+                <ValidationMessage<
+                CatalogItemFormData,
+            >
+                    _phantom=std::marker::PhantomData
+                    name=field!(description @ CatalogItemFormData)
+                />
+            </label>
+            <div class="form-group">
+                <div class="form-group-item">
+                    <label>
+                        Price
+                        <InPutText
+                            name=field!(price @ CatalogItemFormData)
+                            setter=CatalogItemFormData::set_price
+                            getter=CatalogItemFormData::get_price
+                        />
+                        <ValidationMessage<
+                        CatalogItemFormData,
+                    >
+                            _phantom=std::marker::PhantomData
+                            name=field!(price @ CatalogItemFormData)
+                        />
+                    </label>
+                </div>
+                <div class="form-group-item">
+                    <label>
+                        Brand
+                        <InPutSelect
+                            name=field!(brand_id @ CatalogItemFormData)
+                            setter=CatalogItemFormData::set_brand_id
+                            getter=CatalogItemFormData::get_brand_id
+                            options=brand_options
+                        />
+                        <ValidationMessage<
+                        CatalogItemFormData,
+                    >
+                            _phantom=std::marker::PhantomData
+                            name=field!(brand_id @ CatalogItemFormData)
+                        />
+                    </label>
+                </div>
+                <div class="form-group-item">
+                    <label>
+                        Type
+                        <InPutSelect
+                            name=field!(type_id @ CatalogItemFormData)
+                            setter=CatalogItemFormData::set_type_id
+                            getter=CatalogItemFormData::get_type_id
+                            options=type_options
+                        />
+                        <ValidationMessage<
+                        CatalogItemFormData,
+                    >
+                            _phantom=std::marker::PhantomData
+                            name=field!(type_id @ CatalogItemFormData)
+                        />
+                    </label>
+                </div>
+            </div>
+            <label>
+                Picture file name
+                <InPutText
+                    name=field!(picture_file_name @ CatalogItemFormData)
+                    setter=CatalogItemFormData::set_picture_file_name
+                    getter=CatalogItemFormData::get_picture_file_name
+                />
+                <ValidationMessage<
+                CatalogItemFormData,
+            >
+                    _phantom=std::marker::PhantomData
+                    name=field!(picture_file_name @ CatalogItemFormData)
+                />
+            </label>
+            {picture_view}
+        </div>
+    }
+    .into_any()
+}
+
+#[component]
+pub fn AdminCatalogItemPage() -> impl IntoView {
+    let params = use_params::<AdminItemIdParams>();
+    let sig_item_id = Signal::derive(move || params.with(|p| p.as_ref().map(|d| d.item_id).map_err(|_| crate::AppError::Other("Invalid Item Index".to_string()))));
+
+    let class_name = style_sheet!("./app/src/pages/admin/admin_catalog_item.css");
+
+    let init_data_res = Resource::new(move || sig_item_id.get(), |item_id| async move { get_catalog_item_form_data(item_id?).await });
+
+    let brands_and_types = Resource::new(|| (), |_| async move { Ok::<_, crate::AppError>((get_brands().await?, get_types().await?)) });
+
+    let form_action = Action::new(move |data: &CatalogItemFormData| {
+        let data = data.clone();
+        async move { submit_catalog_item_form_data(data.clone()).await }
+    });
+
+    let item_view = move || {
+        Suspend::new(async move {
+            let product_image_url_context = expect_context::<ProductImageUrlContext>();
+            let is_new = matches!(sig_item_id.get_untracked(), Ok(None));
+
+            brands_and_types.await.map(|(brands, types)| {
+                let brand_options = brands.into_iter().map(|b| (b.id.to_string(), b.brand)).collect::<Vec<_>>();
+                let type_options = types.into_iter().map(|t| (t.id.to_string(), t.type_name)).collect::<Vec<_>>();
+                let picture_view = match sig_item_id.get_untracked() {
+                    Ok(Some(item_id)) => {
+                        let url = product_image_url_context.service.get_product_image_url_by_id(item_id as i32);
+                        view! { class=class_name, <img class="item-picture" alt="Current picture" src=url /> }.into_any()
+                    }
+                    _ => {
+                        let _: () = view! {};
+                        ().into_any()
+                    }
+                };
+
+                view! { class=class_name,
+                    <div class="admin-catalog-item">
+                        <EditForm
+                            init_data_resource=init_data_res
+                            form_action=form_action
+                            on_ok=|_data| {
+                                let navigate = use_navigate();
+                                navigate("/admin/catalog", Default::default());
+                            }
+                        >
+                            <div class="form">
+                                <CatalogItemFields class_name is_new brand_options type_options picture_view />
+                                <div class="form-section">
+                                    <div class="form-buttons">
+                                        <a href="admin/catalog" class="button button-secondary">
+                                            <img role="presentation" src="icons/arrow-left.svg" />
+                                            Back to the catalog
+                                        </a>
+                                        <button class="button button-primary" type="submit">
+                                            Save
+                                        </button>
+                                    </div>
+                                </div>
+                            </div>
+                            <ValidationSummary<
+                            CatalogItemFormData,
+                        > _phantom=std::marker::PhantomData />
+                        </EditForm>
+                    </div>
+                }
+            })
+        })
+    };
+
+    crate::app::page_header::set_title("Catalog administration");
+    view! { class=class_name,
+        <Title text="Catalog administration | AdventureWorks" />
+        <Transition fallback=move || view! { <p>"Loading data..."</p> }>
+            <ErrorBoundary fallback=|errors| {
+                view! { <ErrorTemplate errors /> }
+            }>
+
+                {item_view}
+
+            </ErrorBoundary>
+        </Transition>
+    }
+}
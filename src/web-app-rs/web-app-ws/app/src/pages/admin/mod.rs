@@ -0,0 +1,29 @@
+use leptos::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+mod admin_catalog;
+pub(crate) use admin_catalog::AdminCatalogPage;
+
+mod admin_catalog_item;
+pub(crate) use admin_catalog_item::AdminCatalogItemPage;
+
+/// Shows the children to the admins only, a logged-in user without the role gets a Forbidden page instead of another trip to the login.
+#[component]
+pub(crate) fn AdminOnly(children: ChildrenFn) -> impl IntoView {
+    view! {
+        <Show when=|| auth::client::has_role(auth::ADMIN_ROLE) fallback=|| view! { <ForbiddenPage /> }>
+            {children()}
+        </Show>
+    }
+}
+
+#[component]
+fn ForbiddenPage() -> impl IntoView {
+    crate::app::page_header::set_title("Forbidden");
+
+    view! {
+        <leptos_meta::Title text="Forbidden | AdventureWorks" />
+        <p>"Sorry, this page is for the catalog administrators only." <a href="/">"Back to the catalog."</a></p>
+    }
+}
@@ -3,7 +3,9 @@ use crate::services::product_image_url_provider::ProductImageUrlContext;
 use super::*;
 use rust_decimal::prelude::*;
 
+use auth::client::UserInfoCntxt;
 use basket_ordering::basket_state::{self, client::refresh_basket_state_info_action};
+use basket_ordering::wishlist;
 use leptos::ev::SubmitEvent;
 
 use leptos_meta::Title;
@@ -13,6 +15,8 @@ use error_template::ErrorTemplate;
 
 use std::ops::Mul;
 
+use super::saved_for_later::SavedForLater;
+
 #[component]
 pub fn CartPage() -> impl IntoView {
     let basket_state_changed_signal = RwSignal::new(Ok(true) as Result<bool, crate::AppError>);
@@ -54,6 +58,21 @@ pub fn CartPage() -> impl IntoView {
         _ => (),
     });
 
+    let wishlist_changed_signal = RwSignal::new(0usize);
+    let user_info = expect_context::<UserInfoCntxt>().0;
+
+    let move_to_wishlist_action = Action::new(move |&product_id: &i32| async move { wishlist::server_api::move_to_wishlist(product_id).await });
+
+    Effect::new(move || match move_to_wishlist_action.value().get() {
+        Some(Ok(())) => {
+            basket_state_changed_signal.set(Ok(true));
+            refresh_basket_state_info_action().dispatch(());
+            wishlist_changed_signal.update(|v| *v += 1);
+        }
+        Some(Err(e)) => basket_state_changed_signal.set(Err(e)),
+        None => (),
+    });
+
     let class_name = style_sheet!("./app/src/pages/cart/cart.css");
 
     let cart_view = move || {
@@ -96,6 +115,18 @@ pub fn CartPage() -> impl IntoView {
                                                         <p class="price">
                                                             {format!("${:.2}", item.unit_price.clone())}
                                                         </p>
+                                                        // the wishlist is kept per user, guests only have the bag
+                                                        <Show when=move || user_info.with(|user_info| user_info.is_some())>
+                                                            <button
+                                                                type="button"
+                                                                class="button button-secondary"
+                                                                on:click=move |_| {
+                                                                    move_to_wishlist_action.dispatch(update_quantity_id);
+                                                                }
+                                                            >
+                                                                "Save for later"
+                                                            </button>
+                                                        </Show>
                                                     </div>
                                                 </div>
                                                 <div class="catalog-item-quantity">
@@ -184,5 +215,6 @@ pub fn CartPage() -> impl IntoView {
                 <div class="cart">{cart_view}</div>
             </ErrorBoundary>
         </Transition>
+        <SavedForLater basket_changed=basket_state_changed_signal wishlist_changed=wishlist_changed_signal />
     }
 }
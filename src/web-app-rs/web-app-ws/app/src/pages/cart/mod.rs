@@ -3,3 +3,5 @@ use leptos::*;
 
 mod cart;
 pub(crate) use cart::CartPage;
+
+mod saved_for_later;
@@ -0,0 +1,115 @@
+use super::*;
+
+use auth::client::UserInfoCntxt;
+use basket_ordering::basket_state::client::refresh_basket_state_info_action;
+use basket_ordering::wishlist::{self, types::WishlistItem};
+use stylers::style_sheet;
+
+use crate::services::product_image_url_provider::ProductImageUrlContext;
+use error_template::ErrorTemplate;
+
+/// The wishlist of the signed in user below the shopping bag, guests have none.
+///
+/// `basket_changed` is set when a product moves into the bag, `wishlist_changed` is bumped by whoever
+/// changes the wishlist and reloads it.
+#[component]
+pub fn SavedForLater(basket_changed: RwSignal<Result<bool, crate::AppError>>, wishlist_changed: RwSignal<usize>) -> impl IntoView {
+    let user_info = expect_context::<UserInfoCntxt>().0;
+
+    let wishlist_res = Resource::new(
+        move || (wishlist_changed.get(), user_info.with(|user_info| user_info.is_some())),
+        |(_, logged_in)| async move {
+            if !logged_in {
+                return Ok(vec![]) as Result<Vec<WishlistItem>, crate::AppError>;
+            }
+            wishlist::server_api::get_wishlist_items().await
+        },
+    );
+
+    let move_to_basket_action = Action::new(move |&product_id: &i32| async move { wishlist::server_api::move_to_basket(product_id).await });
+    let remove_action = Action::new(move |&product_id: &i32| async move { wishlist::server_api::remove_wishlist_item(product_id).await.map(|_| ()) });
+
+    Effect::new(move || match move_to_basket_action.value().get() {
+        Some(Ok(())) => {
+            basket_changed.set(Ok(true));
+            refresh_basket_state_info_action().dispatch(());
+            wishlist_changed.update(|v| *v += 1);
+        }
+        Some(Err(e)) => basket_changed.set(Err(e)),
+        None => (),
+    });
+
+    Effect::new(move || match remove_action.value().get() {
+        Some(Ok(())) => wishlist_changed.update(|v| *v += 1),
+        Some(Err(e)) => basket_changed.set(Err(e)),
+        None => (),
+    });
+
+    let class_name = style_sheet!("./app/src/pages/cart/saved_for_later.css");
+
+    let wishlist_view = move || {
+        Suspend::new(async move {
+            wishlist_res.await.map(|wishlist_items| {
+                let product_image_url_context = expect_context::<ProductImageUrlContext>();
+
+                if wishlist_items.is_empty() {
+                    return ().into_any();
+                }
+
+                view! { class=class_name,
+                    <div class="wishlist">
+                        <h2>"Saved for later"</h2>
+                        <For
+                            each=move || wishlist_items.clone()
+                            key=|item| item.product_id
+                            children=move |item| {
+                                let product_id = item.product_id;
+                                view! { class=class_name,
+                                    <div class="wishlist-item">
+                                        <img
+                                            alt=item.product_name.clone()
+                                            src=product_image_url_context.service.get_product_image_url_by_id(product_id)
+                                        />
+                                        <div class="wishlist-item-content">
+                                            <a class="name" href=format!("/item/{product_id}")>
+                                                {item.product_name.clone()}
+                                            </a>
+                                            <p class="price">{format!("${:.2}", item.unit_price)}</p>
+                                        </div>
+                                        <div class="wishlist-item-actions">
+                                            <button
+                                                type="button"
+                                                class="button button-primary"
+                                                on:click=move |_| {
+                                                    move_to_basket_action.dispatch(product_id);
+                                                }
+                                            >
+                                                "Move to bag"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class="button button-secondary"
+                                                on:click=move |_| {
+                                                    remove_action.dispatch(product_id);
+                                                }
+                                            >
+                                                "Remove"
+                                            </button>
+                                        </div>
+                                    </div>
+                                }
+                            }
+                        />
+                    </div>
+                }
+                .into_any()
+            })
+        })
+    };
+
+    view! { class=class_name,
+        <Transition fallback=move || ()>
+            <ErrorBoundary fallback=|errors| view! { <ErrorTemplate errors /> }>{wishlist_view}</ErrorBoundary>
+        </Transition>
+    }
+}
@@ -1,11 +1,20 @@
 mod catalog;
 pub(crate) use catalog::CatalogPage;
 
+/// Default items per catalog page, `pageSize` in the query selects one of `PAGE_SIZES`.
+pub(crate) const PAGE_SIZE: usize = 9;
+pub(crate) const PAGE_SIZES: [usize; 3] = [PAGE_SIZE, 18, 36];
+
 mod catalog_list_item;
 
 mod catalog_search;
-pub(crate) use catalog_search::CatalogSearch;
+pub(crate) use catalog_search::{CatalogSearch, path_from_url};
+
+mod catalog_search_box;
+use ::catalog::service::CatalogSort;
+pub(crate) use catalog_search_box::CatalogSearchBox;
 use leptos::prelude::ServerFnError;
+use leptos_router::params::ParamsMap;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -17,12 +26,43 @@ pub enum CatalogPageError {
     InvalidBrandId,
     #[error("Invalid Item Type Id")]
     InvalidItemTypeId,
+    #[error("Invalid Search Query")]
+    InvalidSearchQuery,
+    #[error("Invalid Sort Order")]
+    InvalidSort,
+    #[error("Invalid Page Size")]
+    InvalidPageSize,
     #[error("ServerFnError {0}")]
     ServerFnError(ServerFnError),
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct CatalogSearchQuery {
+    pub text: String,
+    pub semantic: bool,
+}
+
 impl From<CatalogPageError> for crate::AppError {
     fn from(value: CatalogPageError) -> Self {
         crate::AppError::Other(value.to_string())
     }
 }
+
+/// `sort` and `pageSize` of the catalog query, shared by the catalog and item pages.
+pub(crate) fn sort_from_query(params: &ParamsMap) -> Result<Option<CatalogSort>, CatalogPageError> {
+    params.get_str("sort").map(|d| d.parse()).transpose().map_err(|_| CatalogPageError::InvalidSort)
+}
+
+/// `scroll=infinite` replaces the page links by loading the following pages into the listing.
+pub(crate) fn infinite_scroll_from_query(params: &ParamsMap) -> bool {
+    params.get_str("scroll") == Some(SCROLL_INFINITE)
+}
+
+pub(crate) const SCROLL_INFINITE: &str = "infinite";
+
+pub(crate) fn page_size_from_query(params: &ParamsMap) -> Result<usize, CatalogPageError> {
+    match params.get_str("pageSize") {
+        None => Ok(PAGE_SIZE),
+        Some(d) => d.parse::<usize>().ok().filter(|d| PAGE_SIZES.contains(d)).ok_or(CatalogPageError::InvalidPageSize),
+    }
+}
@@ -7,18 +7,38 @@ use leptos_router::hooks::{self, use_query, use_query_map};
 use stylers::style_sheet;
 
 use crate::{
-    components::{NavLinkCb, NavLinkGr},
+    components::{NavLinkCb, NavLinkGr, NavLinkScroll},
     pages::{
-        catalog::{CatalogPageError, CatalogSearch},
+        catalog::{CatalogPageError, CatalogSearch, CatalogSearchBox, CatalogSearchQuery, infinite_scroll_from_query, page_size_from_query, sort_from_query},
         parameter_from_query,
     },
 };
 
 use error_template::ErrorTemplate;
 
-use catalog::server_api::get_catalog_items;
+use catalog::{
+    server_api::{get_catalog_items, search_by_name, search_semantic},
+    service::{CatalogResult, CatalogSort},
+    types::CatalogItem,
+};
 
-const PAGE_SIZE: usize = 9;
+type CatalogQuery = (
+    Result<Option<usize>, CatalogPageError>,
+    Result<Option<usize>, CatalogPageError>,
+    Result<Option<CatalogSearchQuery>, CatalogPageError>,
+    Result<Option<CatalogSort>, CatalogPageError>,
+    Result<usize, CatalogPageError>,
+);
+
+/// One page of the listing selected by brand, type, search, sort and page size.
+async fn fetch_catalog_page(page: usize, (brand, typeid, search, sort, page_size): CatalogQuery) -> Result<CatalogResult, crate::AppError> {
+    let (sort, page_size) = (sort?, page_size?);
+    match search? {
+        Some(CatalogSearchQuery { text, semantic: true }) => search_semantic(text, page, page_size, sort).await,
+        Some(CatalogSearchQuery { text, semantic: false }) => search_by_name(text, page, page_size, sort).await,
+        None => get_catalog_items(page, page_size, brand?, typeid?, sort).await,
+    }
+}
 
 #[component]
 pub fn CatalogPage() -> impl IntoView {
@@ -41,17 +61,46 @@ pub fn CatalogPage() -> impl IntoView {
             .map_err(|_| CatalogPageError::InvalidItemTypeId)
     });
 
+    let sig_sort = Signal::derive(move || params.with(sort_from_query));
+    let sig_page_size = Signal::derive(move || params.with(page_size_from_query));
+    let sig_infinite = Signal::derive(move || params.with(infinite_scroll_from_query));
+
+    let search = use_query::<parameter_from_query::Search>();
+    let sig_search = Signal::derive(move || {
+        search.with(|d| {
+            d.as_ref()
+                .map(|d| d.q.as_ref().map(|q| q.trim()).filter(|q| !q.is_empty()).map(|q| CatalogSearchQuery { text: q.to_string(), semantic: d.semantic.unwrap_or_default() }))
+                .map_err(|_| CatalogPageError::InvalidSearchQuery)
+        })
+    });
+
+    let sig_query = move || (sig_brand_id(), sig_type_id(), sig_search(), sig_sort(), sig_page_size());
+
     let catalog = Resource::new(
-        move || (sig_page_index(), sig_brand_id(), sig_type_id()),
-        |(page, brand, typeid)| async move {
-            //log!("Resource Catalog page: {:?} brand: {:?} typeid: {:?}", page, brand, typeid);
+        move || (sig_page_index(), sig_query()),
+        |(page, query)| async move {
+            //log!("Resource Catalog page: {:?} query: {:?}", page, query);
             let page = page?.map(|p| if p == 0 { p } else { p - 1 }).unwrap_or(0);
-            get_catalog_items(page, PAGE_SIZE, brand?, typeid?).await
+            fetch_catalog_page(page, query).await
         },
     );
 
     let sig_cb: RwSignal<Option<NavLinkCb>> = RwSignal::new(None);
 
+    // infinite scrolling: the pages following the one of `catalog`, reset when `catalog` reloads
+    let extra_items: RwSignal<Vec<CatalogItem>> = RwSignal::new(Vec::new());
+    let load_next = Action::new(move |page: &usize| fetch_catalog_page(*page, untrack(sig_query)));
+    Effect::new(move |_| {
+        if let Some(Ok(catalog)) = load_next.value().get() {
+            extra_items.update(|items| items.extend(catalog.data));
+            sig_cb.update(|cb| {
+                if let Some(cb) = cb {
+                    cb.page_index = catalog.page_index;
+                }
+            });
+        }
+    });
+
     let class_name_item = style_sheet!("./app/src/pages/catalog/catalog_list_item.css");
 
     let items_view = move || {
@@ -62,6 +111,7 @@ pub fn CatalogPage() -> impl IntoView {
                 {catalog
                     .await
                     .map(|catalog| {
+                        extra_items.set(Vec::new());
                         sig_cb
                             .set(
                                 Some(NavLinkCb {
@@ -71,6 +121,7 @@ pub fn CatalogPage() -> impl IntoView {
                                     count: catalog.count,
                                 }),
                             );
+                        let query = sig_url.get_untracked().search().to_string();
                         catalog
                             .data
                             .into_iter()
@@ -80,6 +131,7 @@ pub fn CatalogPage() -> impl IntoView {
                                     <super::catalog_list_item::CatalogListItem
                                         class_name=class_name_item
                                         item=item
+                                        query=query.clone()
                                     />
                                 }
                                     .into_view()
@@ -90,22 +142,57 @@ pub fn CatalogPage() -> impl IntoView {
         })
     };
 
+    let extra_items_view = move || {
+        let query = sig_url.with(|url| url.search().to_string());
+        extra_items
+            .get()
+            .into_iter()
+            .map(|item| {
+                view! {
+                    <super::catalog_list_item::CatalogListItem
+                        class_name=class_name_item
+                        item=item
+                        query=query.clone()
+                    />
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
     let class_name = style_sheet!("./app/src/pages/catalog/catalog.css");
 
     let nav_link_view = move || {
         Suspend::new(async move {
-            view! { class=class_name,
-                <div class="page-links">
-
-                    <NavLinkGr
-                        css_active_class="active-page".to_string()
-                        sig_cb=sig_cb.read_only()
-                        q_param_name="page"
-                        fn_index_to_string=|i| if i == 1 { None } else { Some(i.to_string()) }
-                    />
-                </div>
+            let fn_index_to_string: fn(usize) -> Option<String> = |i| if i == 1 { None } else { Some(i.to_string()) };
+            if sig_infinite() {
+                view! { class=class_name,
+                    <div class="page-links">
+                        <NavLinkScroll
+                            sig_cb=sig_cb.read_only()
+                            q_param_name="page"
+                            fn_index_to_string
+                            on_next=Callback::new(move |page_index| {
+                                load_next.dispatch(page_index);
+                            })
+                            sig_loading=load_next.pending()
+                        />
+                    </div>
+                }
+                .into_any()
+            } else {
+                view! { class=class_name,
+                    <div class="page-links">
+
+                        <NavLinkGr
+                            css_active_class="active-page".to_string()
+                            sig_cb=sig_cb.read_only()
+                            q_param_name="page"
+                            fn_index_to_string
+                        />
+                    </div>
+                }
+                .into_any()
             }
-            .into_any()
         })
     };
 
@@ -119,10 +206,11 @@ pub fn CatalogPage() -> impl IntoView {
 
                 <div class="catalog">
 
-                    <CatalogSearch sig_brand_id sig_type_id />
+                    <CatalogSearch sig_brand_id sig_type_id sig_sort sig_page_size />
                     <div>
+                        <CatalogSearchBox sig_search />
 
-                        <div class="catalog-items">{items_view}</div>
+                        <div class="catalog-items">{items_view} {extra_items_view}</div>
                         {nav_link_view}
 
                     </div>
@@ -4,22 +4,26 @@ use crate::services::product_image_url_provider::ProductImageUrlContext;
 use catalog::types::CatalogItem;
 
 #[component]
-pub(super) fn CatalogListItem(class_name: &'static str, item: CatalogItem) -> impl IntoView {
+pub(super) fn CatalogListItem(class_name: &'static str, item: CatalogItem, query: String) -> impl IntoView {
     let product_image_url_context = expect_context::<ProductImageUrlContext>();
-    fn item_url(item: &CatalogItem) -> String {
-        format!("item/{}", item.id)
+    // the query of the listing goes along, so the item page can navigate within it
+    fn item_url(item: &CatalogItem, query: &str) -> String {
+        if query.is_empty() { format!("item/{}", item.id) } else { format!("item/{}?{query}", item.id) }
     }
 
     let price = format!("{:.2}", (item.price));
 
     view! { class=class_name,
         <div class="catalog-item">
-            <a class="catalog-product" href=item_url(&item)>
+            <a class="catalog-product" href=item_url(&item, &query)>
 
                 <span class="catalog-product-image">
                     <img
                         alt=item.name.clone()
                         src=product_image_url_context.service.get_product_image_url(&item)
+                        srcset=product_image_url_context.service.get_product_image_srcset(item.id)
+                        sizes="(max-width: 480px) 100vw, 33vw"
+                        loading="lazy"
                     />
                 </span>
                 <span class="catalog-product-content">
@@ -8,11 +8,13 @@ use leptos_router::{
 use logging::log;
 use stylers::style_sheet;
 
-use crate::pages::catalog::CatalogPageError;
+use crate::pages::catalog::{CatalogPageError, PAGE_SIZE, PAGE_SIZES, SCROLL_INFINITE, infinite_scroll_from_query};
 
 use catalog::server_api::{get_brands, get_types};
+use catalog::service::CatalogSort;
 
-fn path_from_url(url: &mut location::Url, params: &[(&'static str, Option<String>)]) -> String {
+/// The path of `url` with the query parameters of `params` replaced, `None` removes one.
+pub(crate) fn path_from_url(url: &mut location::Url, params: &[(&'static str, Option<String>)]) -> String {
     let mut rslt = url.path().to_string();
     let search_params = url.search_params_mut();
 
@@ -28,7 +30,7 @@ fn path_from_url(url: &mut location::Url, params: &[(&'static str, Option<String
 }
 
 #[component]
-pub fn CatalogSearch(sig_brand_id: Signal<Result<Option<usize>, CatalogPageError>>, sig_type_id: Signal<Result<Option<usize>, CatalogPageError>>) -> impl IntoView {
+pub fn CatalogSearch(sig_brand_id: Signal<Result<Option<usize>, CatalogPageError>>, sig_type_id: Signal<Result<Option<usize>, CatalogPageError>>, sig_sort: Signal<Result<Option<CatalogSort>, CatalogPageError>>, sig_page_size: Signal<Result<usize, CatalogPageError>>) -> impl IntoView {
     use futures::join;
 
     let sig_url: ReadSignal<leptos_router::location::Url> = hooks::use_url();
@@ -75,7 +77,7 @@ pub fn CatalogSearch(sig_brand_id: Signal<Result<Option<usize>, CatalogPageError
 
     fn brand_uri(sig_url: ReadSignal<leptos_router::location::Url>, brand_id: Option<usize>) -> String {
         let mut url = sig_url.get();
-        let params = [("page", None), ("brand", brand_id.map(|x| x.to_string()))];
+        let params = [("page", None), ("q", None), ("semantic", None), ("brand", brand_id.map(|x| x.to_string()))];
 
         path_from_url(&mut url, &params[..])
     }
@@ -83,7 +85,7 @@ pub fn CatalogSearch(sig_brand_id: Signal<Result<Option<usize>, CatalogPageError
     fn type_uri(sig_url: ReadSignal<leptos_router::location::Url>, type_id: Option<usize>) -> String {
         let mut url = sig_url.get();
 
-        let params = [("page", None), ("type", type_id.map(|x| x.to_string()))];
+        let params = [("page", None), ("q", None), ("semantic", None), ("type", type_id.map(|x| x.to_string()))];
 
         path_from_url(&mut url, &params[..])
     }
@@ -163,6 +165,7 @@ pub fn CatalogSearch(sig_brand_id: Signal<Result<Option<usize>, CatalogPageError
                                                             .into_any()}
                                                     </div>
                                                 </div>
+                                                <CatalogListingOptions class_name sig_url sig_sort sig_page_size />
                                             </div>
                                         </div>
                                     }
@@ -184,3 +187,97 @@ pub fn CatalogSearch(sig_brand_id: Signal<Result<Option<usize>, CatalogPageError
         }}
     }
 }
+
+fn sort_label(sort: CatalogSort) -> &'static str {
+    match sort {
+        CatalogSort::Name => "Name",
+        CatalogSort::PriceAsc => "Price ↑",
+        CatalogSort::PriceDesc => "Price ↓",
+        CatalogSort::Newest => "Newest",
+    }
+}
+
+/// Sort order, page size and paging tags, all go back to the first page and keep the rest of the query.
+#[component]
+fn CatalogListingOptions(class_name: &'static str, sig_url: ReadSignal<leptos_router::location::Url>, sig_sort: Signal<Result<Option<CatalogSort>, CatalogPageError>>, sig_page_size: Signal<Result<usize, CatalogPageError>>) -> impl IntoView {
+    fn sort_uri(sig_url: ReadSignal<leptos_router::location::Url>, sort: CatalogSort) -> String {
+        let mut url = sig_url.get();
+        // name is the order of the catalog-api and needs no parameter
+        let sort = if sort == CatalogSort::Name { None } else { Some(sort.as_str().to_string()) };
+        path_from_url(&mut url, &[("page", None), ("sort", sort)])
+    }
+
+    fn page_size_uri(sig_url: ReadSignal<leptos_router::location::Url>, page_size: usize) -> String {
+        let mut url = sig_url.get();
+        let page_size = if page_size == PAGE_SIZE { None } else { Some(page_size.to_string()) };
+        path_from_url(&mut url, &[("page", None), ("pageSize", page_size)])
+    }
+
+    fn scroll_uri(sig_url: ReadSignal<leptos_router::location::Url>, infinite: bool) -> String {
+        let mut url = sig_url.get();
+        path_from_url(&mut url, &[("page", None), ("scroll", infinite.then(|| SCROLL_INFINITE.to_string()))])
+    }
+
+    let active_sort = move || sig_sort.get().ok().flatten().unwrap_or(CatalogSort::Name);
+    let active_page_size = move || sig_page_size.get().unwrap_or(PAGE_SIZE);
+
+    view! { class=class_name,
+        <div class="catalog-search-group">
+            <h3>Sort by</h3>
+            <div class="catalog-search-group-tags">
+                {CatalogSort::ALL
+                    .into_iter()
+                    .map(|sort| {
+                        view! { class=class_name,
+                            <a
+                                href=move || sort_uri(sig_url, sort)
+                                class="catalog-search-tag"
+                                class:active=move || active_sort() == sort
+                            >
+                                {sort_label(sort)}
+                            </a>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </div>
+        </div>
+        <div class="catalog-search-group">
+            <h3>Per page</h3>
+            <div class="catalog-search-group-tags">
+                {PAGE_SIZES
+                    .into_iter()
+                    .map(|page_size| {
+                        view! { class=class_name,
+                            <a
+                                href=move || page_size_uri(sig_url, page_size)
+                                class="catalog-search-tag"
+                                class:active=move || active_page_size() == page_size
+                            >
+                                {page_size}
+                            </a>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </div>
+        </div>
+        <div class="catalog-search-group">
+            <h3>Paging</h3>
+            <div class="catalog-search-group-tags">
+                {[(false, "Pages"), (true, "Infinite scroll")]
+                    .into_iter()
+                    .map(|(infinite, label)| {
+                        view! { class=class_name,
+                            <a
+                                href=move || scroll_uri(sig_url, infinite)
+                                class="catalog-search-tag"
+                                class:active=move || sig_url.with(|url| infinite_scroll_from_query(url.search_params())) == infinite
+                            >
+                                {label}
+                            </a>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
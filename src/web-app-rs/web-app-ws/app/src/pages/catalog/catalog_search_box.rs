@@ -0,0 +1,46 @@
+use leptos::prelude::*;
+use leptos_router::{components::Form, hooks::use_query_map};
+
+use stylers::style_sheet;
+
+use crate::pages::catalog::{CatalogPageError, CatalogSearchQuery};
+
+#[component]
+pub fn CatalogSearchBox(sig_search: Signal<Result<Option<CatalogSearchQuery>, CatalogPageError>>) -> impl IntoView {
+    let sig_text = Signal::derive(move || sig_search.get().ok().flatten().map(|s| s.text).unwrap_or_default());
+    let sig_semantic = Signal::derive(move || sig_search.get().ok().flatten().map(|s| s.semantic).unwrap_or_default());
+    let sig_is_active = Signal::derive(move || matches!(sig_search.get(), Ok(Some(_))));
+
+    // the form replaces the query, sort order and page size are carried along
+    let query = use_query_map();
+    let hidden_inputs = move || ["sort", "pageSize"].into_iter().filter_map(|name| query.with(|q| q.get(name)).map(|value| view! { <input type="hidden" name=name value=value /> })).collect::<Vec<_>>();
+
+    let class_name = style_sheet!("./app/src/pages/catalog/catalog_search_box.css");
+
+    view! { class=class_name,
+        <div class="catalog-search-box">
+            <Form method="GET" action="">
+                {hidden_inputs}
+                <input
+                    type="search"
+                    name="q"
+                    placeholder="Search products"
+                    aria-label="Search products"
+                    prop:value=sig_text
+                />
+                <label class="catalog-search-box-semantic">
+                    <input type="checkbox" name="semantic" value="true" prop:checked=sig_semantic />
+                    "Semantic"
+                </label>
+                <button type="submit" class="button button-primary">
+                    Search
+                </button>
+                <Show when=sig_is_active>
+                    <a href="" class="catalog-search-box-clear">
+                        Clear
+                    </a>
+                </Show>
+            </Form>
+        </div>
+    }
+}
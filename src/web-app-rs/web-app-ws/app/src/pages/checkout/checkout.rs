@@ -1,7 +1,14 @@
 use super::*;
 
+use basket_ordering::addresses::server_api::get_addresses;
+use basket_ordering::addresses::types::SavedAddress;
+#[cfg(feature = "ssr")]
+use basket_ordering::addresses::{service::AddressServiceContext, types::ShippingAddress};
+use basket_ordering::basket_state::server_api::get_order_draft;
 #[cfg(feature = "ssr")]
 use basket_ordering::basket_state::service::{BasketCheckoutInfo, BasketStateServiceContext};
+use basket_ordering::basket_state::types::{MASKED, mask_card_number};
+use basket_ordering::ordering::server_api::get_card_types;
 
 use error_template::ErrorTemplate;
 
@@ -20,7 +27,10 @@ use valitron::{
 use crate::edit_form::*;
 use field::*;
 
-#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[cfg(feature = "ssr")]
+use super::payment;
+
+#[derive(PartialEq, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct BasketCheckoutInfoFormData {
     street: String,
@@ -28,9 +38,36 @@ struct BasketCheckoutInfoFormData {
     state: String,
     country: String,
     zip_code: String,
+    /// Adds the address to the address book once the order is placed.
+    save_address: bool,
+    card_type_id: String,
+    card_number: String,
+    card_holder_name: String,
+    /// `MM/YY`
+    card_expiration: String,
+    card_security_number: String,
     request_id: Uuid,
 }
 
+impl std::fmt::Debug for BasketCheckoutInfoFormData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BasketCheckoutInfoFormData")
+            .field("street", &self.street)
+            .field("city", &self.city)
+            .field("state", &self.state)
+            .field("country", &self.country)
+            .field("zip_code", &self.zip_code)
+            .field("save_address", &self.save_address)
+            .field("card_type_id", &self.card_type_id)
+            .field("card_number", &mask_card_number(&self.card_number))
+            .field("card_holder_name", &MASKED)
+            .field("card_expiration", &self.card_expiration)
+            .field("card_security_number", &MASKED)
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
+
 impl BasketCheckoutInfoFormData {
     fn set_street(&self, value: &str) -> Self {
         let mut tmp = self.clone();
@@ -72,6 +109,65 @@ impl BasketCheckoutInfoFormData {
     fn get_zip_code(&self) -> &str {
         &self.zip_code
     }
+    fn set_card_type_id(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.card_type_id = value.to_string();
+        tmp
+    }
+    fn get_card_type_id(&self) -> &str {
+        &self.card_type_id
+    }
+    fn set_card_number(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.card_number = value.to_string();
+        tmp
+    }
+    fn get_card_number(&self) -> &str {
+        &self.card_number
+    }
+    fn set_card_holder_name(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.card_holder_name = value.to_string();
+        tmp
+    }
+    fn get_card_holder_name(&self) -> &str {
+        &self.card_holder_name
+    }
+    fn set_card_expiration(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.card_expiration = value.to_string();
+        tmp
+    }
+    fn get_card_expiration(&self) -> &str {
+        &self.card_expiration
+    }
+    fn set_card_security_number(&self, value: &str) -> Self {
+        let mut tmp = self.clone();
+        tmp.card_security_number = value.to_string();
+        tmp
+    }
+    fn get_card_security_number(&self) -> &str {
+        &self.card_security_number
+    }
+
+    fn set_address(&self, address: &SavedAddress) -> Self {
+        let mut tmp = self.clone();
+        tmp.street = address.address.street.clone();
+        tmp.city = address.address.city.clone();
+        tmp.state = address.address.state.clone();
+        tmp.country = address.address.country.clone();
+        tmp.zip_code = address.address.zip_code.clone();
+        tmp
+    }
+
+    fn is_address(&self, address: &SavedAddress) -> bool {
+        let a = &address.address;
+        (a.street.as_str(), a.city.as_str(), a.state.as_str(), a.country.as_str(), a.zip_code.as_str()) == (self.street.trim(), self.city.trim(), self.state.trim(), self.country.trim(), self.zip_code.trim())
+    }
+
+    fn address_lines(&self) -> Vec<String> {
+        [self.street.clone(), format!("{} {}", self.zip_code, self.city), self.state.clone(), self.country.clone()].into_iter().map(|x| x.trim().to_string()).filter(|x| !x.is_empty()).collect()
+    }
 }
 
 #[cfg(feature = "ssr")]
@@ -82,41 +178,78 @@ impl BasketCheckoutInfoFormData {
             .insert("city", &mut input.city, Trim.and(Required))
             .insert("state", &mut input.state, Trim.and(Required))
             .insert("country", &mut input.country, Trim.and(Required))
-            .insert("zip_code", &mut input.zip_code, Trim.and(Required));
+            .insert("zip_code", &mut input.zip_code, Trim.and(Required))
+            .insert("card_type_id", &mut input.card_type_id, Trim.and(Required))
+            .insert("card_number", &mut input.card_number, Trim.and(Required).and(payment::CardNumber))
+            .insert("card_holder_name", &mut input.card_holder_name, Trim.and(Required))
+            .insert("card_expiration", &mut input.card_expiration, Trim.and(Required).and(payment::CardExpiration))
+            .insert("card_security_number", &mut input.card_security_number, Trim.and(Required).and(payment::CardSecurityNumber));
 
         valid.validate(input.clone())
     }
+
+    /// The rules of `from_raw` and the card type and expiration as the ordering-api takes them.
+    fn validate(&mut self) -> Result<(i32, chrono::DateTime<chrono::Utc>), FormErrors> {
+        let mut errors = match Self::from_raw(self) {
+            Ok(_) => FormErrors::default(),
+            Err(e) => to_form_errors(e),
+        };
+
+        let card_type_id = self.card_type_id.parse::<i32>().ok();
+        if card_type_id.is_none() {
+            errors.entry("card_type_id".to_string()).or_insert_with(|| vec!["must be selected".to_string()]);
+        }
+        match (card_type_id, payment::parse_card_expiration(&self.card_expiration)) {
+            (Some(card_type_id), Some(card_expiration)) if errors.is_empty() => Ok((card_type_id, card_expiration)),
+            _ => Err(errors),
+        }
+    }
 }
 
 #[server]
 #[middleware(auth::RequireAuth)]
 async fn get_basket_checkout_info_form_data() -> Result<BasketCheckoutInfoFormData, crate::AppError> {
-    use auth::service::AuthServiceContext;
-    let user_address_info = expect_context::<AuthServiceContext>().service.get_user_address_info().await?;
-    //log!("user_address_info: {:#?}", user_address_info);
-
-    Ok(BasketCheckoutInfoFormData {
-        street: user_address_info.street.unwrap_or_default(),
-        city: user_address_info.city.unwrap_or_default(),
-        state: user_address_info.state.unwrap_or_default(),
-        country: user_address_info.country.unwrap_or_default(),
-        zip_code: user_address_info.zip.unwrap_or_default(),
+    // the first address of the book, the address claims of the sign-in only seed the book
+    let addresses = expect_context::<AddressServiceContext>().service.get_addresses().await?;
+
+    let data = BasketCheckoutInfoFormData {
+        save_address: addresses.is_empty(),
         request_id: Uuid::new_v4(),
+        ..Default::default()
+    };
+    Ok(match addresses.first() {
+        Some(address) => data.set_address(address),
+        None => data,
     })
 }
 
+/// Only validates the form, the order is placed after the review.
 #[server]
 #[middleware(auth::RequireAuth)]
-async fn submit_basket_checkout_info_form_data(data: BasketCheckoutInfoFormData) -> Result<(BasketCheckoutInfoFormData, Option<FormErrors>), crate::AppError> {
+async fn validate_basket_checkout_info_form_data(data: BasketCheckoutInfoFormData) -> Result<(BasketCheckoutInfoFormData, Option<FormErrors>), crate::AppError> {
     let mut data = data.clone();
-    let validation_result = BasketCheckoutInfoFormData::from_raw(&mut data);
+    match data.validate() {
+        Ok(_) => Ok((data, None)),
+        Err(errors) => Ok((data, Some(errors))),
+    }
+}
 
-    match validation_result {
-        Ok(data) => {
+#[server]
+#[middleware(auth::RequireAuth)]
+async fn submit_basket_checkout_info_form_data(data: BasketCheckoutInfoFormData) -> Result<(BasketCheckoutInfoFormData, Option<FormErrors>), crate::AppError> {
+    let mut data = data.clone();
+    match data.validate() {
+        Ok((card_type_id, card_expiration)) => {
             let basket_service = expect_context::<BasketStateServiceContext>().service;
 
             let data_ = data.clone();
-            let card_expiration = chrono::Utc::now().checked_add_months(chrono::Months::new(12)).ok_or(crate::AppError::internal_server_error())?;
+            let address = ShippingAddress {
+                street: data.street.clone(),
+                city: data.city.clone(),
+                state: data.state.clone(),
+                zip_code: data.zip_code.clone(),
+                country: data.country.clone(),
+            };
 
             let checkout_info = BasketCheckoutInfo {
                 street: data.street,
@@ -124,48 +257,321 @@ async fn submit_basket_checkout_info_form_data(data: BasketCheckoutInfoFormData)
                 state: data.state,
                 country: data.country,
                 zip_code: data.zip_code,
-                card_number: None,
-                card_holder_name: None,
-                card_security_number: None,
+                card_number: data.card_number,
+                card_holder_name: data.card_holder_name,
+                card_security_number: data.card_security_number,
                 card_expiration,
-                card_type_id: 1,
+                card_type_id,
                 buyer: None,
                 request_id: data.request_id,
             };
             basket_service.checkout(checkout_info).await?;
 
+            // the order is placed, a failed save only costs retyping the address next time
+            if data_.save_address
+                && let Err(e) = expect_context::<AddressServiceContext>().service.add_address(address).await
+            {
+                leptos::logging::warn!("saving the shipping address failed {e:?}");
+            }
+
             Ok((data_, None))
         }
-        Err(e) => Ok((data, Some(to_form_errors(e)))),
+        Err(errors) => Ok((data, Some(errors))),
     }
 }
 
+/// Shows the errors of the validation of the server when the order is placed in the fields of the form.
+#[component]
+fn PlaceOrderErrors(place_order_action: Action<BasketCheckoutInfoFormData, Result<(BasketCheckoutInfoFormData, Option<FormErrors>), crate::AppError>>) -> impl IntoView {
+    let form_state = expect_context::<FormState<BasketCheckoutInfoFormData, Field<BasketCheckoutInfoFormData>>>();
+
+    Effect::new(move || {
+        if let Some(Ok((data, Some(errors)))) = place_order_action.value().get() {
+            form_state.form_data_validated.set((data.clone(), errors));
+            form_state.form_data_current.set(data);
+        }
+    });
+}
+
+/// Copies a saved address into the address fields, any change of the fields may be saved as a new address.
+#[component]
+fn AddressPicker(class_name: &'static str, addresses: Vec<SavedAddress>) -> impl IntoView {
+    let form_state = expect_context::<FormState<BasketCheckoutInfoFormData, Field<BasketCheckoutInfoFormData>>>();
+    let addresses = StoredValue::new(addresses);
+
+    let selected = move || {
+        let current = form_state.form_data_current.get();
+        addresses.with_value(|addresses| addresses.iter().find(|a| current.is_address(a)).map(|a| a.id.to_string()).unwrap_or_default())
+    };
+
+    let on_change = move |ev: leptos::ev::Event| {
+        let Ok(address_id) = event_target_value(&ev).parse::<i64>() else {
+            return;
+        };
+        let Some(address) = addresses.with_value(|addresses| addresses.iter().find(|a| a.id == address_id).cloned()) else {
+            return;
+        };
+        let data = form_state.form_data_current.get_untracked().set_address(&address);
+        // the inputs show the validated data, the other fields keep what was typed
+        form_state.form_data_validated.update(|(v, _)| *v = data.clone());
+        form_state.form_data_current.set(data);
+    };
+
+    let save_address = move || form_state.form_data_current.with(|d| d.save_address);
+    let on_save_address = move |ev: leptos::ev::Event| {
+        let checked = event_target_checked(&ev);
+        form_state.form_data_current.update(|d| d.save_address = checked);
+    };
+
+    view! { class=class_name,
+        <Show when=move || addresses.with_value(|a| !a.is_empty())>
+            <label>
+                Saved addresses
+                <select name="saved_address" prop:value=selected on:change=on_change>
+                    <option value="">"New address"</option>
+                    {addresses
+                        .get_value()
+                        .into_iter()
+                        .map(|a| view! { <option value=a.id.to_string()>{a.address.summary()}</option> })
+                        .collect_view()}
+                </select>
+            </label>
+        </Show>
+        <label class="save-address">
+            <input type="checkbox" name="save_address" prop:checked=save_address on:change=on_save_address />
+            Save this address to my addresses
+        </label>
+    }
+    .into_any()
+}
+
+#[component]
+fn PaymentFields(class_name: &'static str, card_type_options: Vec<(String, String)>) -> impl IntoView {
+    // type erased, the whole form doesn't fit the type depth limit otherwise
+    view! { class=class_name,
+        <div class="form-section">
+            <h2>Payment</h2>
+            <label>
+                Card type
+                <InPutSelect
+                    name=field!(card_type_id @ BasketCheckoutInfoFormData)
+                    setter=BasketCheckoutInfoFormData::set_card_type_id
+                    getter=BasketCheckoutInfoFormData::get_card_type_id
+                    options=card_type_options
+                />
+                <ValidationMessage<
+                BasketCheckoutInfoFormData,
+            >
+                    _phantom=std::marker::PhantomData
+                    name=field!(card_type_id @ BasketCheckoutInfoFormData)
+                />
+            </label>
+            <label>
+                Card number
+                <InPutText
+                    name=field!(card_number @ BasketCheckoutInfoFormData)
+                    setter=BasketCheckoutInfoFormData::set_card_number
+                    getter=BasketCheckoutInfoFormData::get_card_number
+                />
+                <ValidationMessage<
+                BasketCheckoutInfoFormData,
+            >
+                    _phantom=std::marker::PhantomData
+                    name=field!(card_number @ BasketCheckoutInfoFormData)
+                />
+            </label>
+            <label>
+                Name on the card
+                <InPutText
+                    name=field!(card_holder_name @ BasketCheckoutInfoFormData)
+                    setter=BasketCheckoutInfoFormData::set_card_holder_name
+                    getter=BasketCheckoutInfoFormData::get_card_holder_name
+                />
+                <ValidationMessage<
+                BasketCheckoutInfoFormData,
+            >
+                    _phantom=std::marker::PhantomData
+                    name=field!(card_holder_name @ BasketCheckoutInfoFormData)
+                />
+            </label>
+            <div class="form-group">
+                <div class="form-group-item">
+                    <label>
+                        Expiration (MM/YY)
+                        <InPutText
+                            name=field!(card_expiration @ BasketCheckoutInfoFormData)
+                            setter=BasketCheckoutInfoFormData::set_card_expiration
+                            getter=BasketCheckoutInfoFormData::get_card_expiration
+                        />
+                        <ValidationMessage<
+                        BasketCheckoutInfoFormData,
+                    >
+                            _phantom=std::marker::PhantomData
+                            name=field!(card_expiration @ BasketCheckoutInfoFormData)
+                        />
+                    </label>
+                </div>
+                <div class="form-group-item">
+                    <label>
+                        Security code
+                        <InPutText
+                            name=field!(card_security_number @ BasketCheckoutInfoFormData)
+                            setter=BasketCheckoutInfoFormData::set_card_security_number
+                            getter=BasketCheckoutInfoFormData::get_card_security_number
+                        />
+                        <ValidationMessage<
+                        BasketCheckoutInfoFormData,
+                    >
+                            _phantom=std::marker::PhantomData
+                            name=field!(card_security_number @ BasketCheckoutInfoFormData)
+                        />
+                    </label>
+                </div>
+            </div>
+        </div>
+    }
+    .into_any()
+}
+
 #[component]
 pub fn CheckoutPage() -> impl IntoView {
     let class_name = style_sheet!("./app/src/pages/checkout/checkout.css");
 
     let init_data_res = Resource::new(|| (), |_| async move { get_basket_checkout_info_form_data().await });
+    let card_types = Resource::new(|| (), |_| async move { get_card_types().await });
+    let addresses = Resource::new(|| (), |_| async move { get_addresses().await });
+
+    // the validated address while the order is reviewed, `None` while the address is edited
+    let sig_review = RwSignal::new(None::<BasketCheckoutInfoFormData>);
 
     let form_action = Action::new(move |data: &BasketCheckoutInfoFormData| {
+        let data = data.clone();
+        async move { validate_basket_checkout_info_form_data(data.clone()).await }
+    });
+
+    let place_order_action = Action::new(move |data: &BasketCheckoutInfoFormData| {
         let data = data.clone();
         async move { submit_basket_checkout_info_form_data(data.clone()).await }
     });
 
+    let navigate = use_navigate();
+    Effect::new(move || match place_order_action.value().get() {
+        Some(Ok((_, None))) => navigate("/user/orders", Default::default()),
+        // the data did not pass the validation of the server, back to the form which shows the errors
+        Some(Ok((_, Some(_)))) => sig_review.set(None),
+        _ => (),
+    });
+
+    // priced again on every review, the basket may have changed meanwhile
+    let order_draft = Resource::new(
+        move || sig_review.with(Option::is_some),
+        |reviewing| async move {
+            match reviewing {
+                true => get_order_draft().await.map(Some),
+                false => Ok(None),
+            }
+        },
+    );
+
+    let place_order_error_view = move || match place_order_action.value().get() {
+        Some(Err(e)) => Some(view! { <p class="validation-message">{format!("The order could not be placed: {e}")}</p> }),
+        _ => None,
+    };
+
+    let review_view = move || {
+        Suspend::new(async move {
+            order_draft.await.map(|draft| {
+                draft.map(|draft| {
+                    let is_empty = draft.order_items.is_empty();
+                    let data = sig_review.get_untracked().unwrap_or_default();
+                    let address = data.address_lines();
+                    let card_type = card_types.get_untracked().and_then(Result::ok).and_then(|x| x.into_iter().find(|t| t.id.to_string() == data.card_type_id)).map(|t| t.name).unwrap_or_default();
+                    view! { class=class_name,
+                        <div class="form">
+                            <div class="form-section">
+                                <h2>Review your order</h2>
+                                <ul class="review-items">
+                                    <li class="review-item review-items-header">
+                                        <div class="item-name">"Product"</div>
+                                        <div>"Units"</div>
+                                        <div class="item-price">"Unit price"</div>
+                                        <div class="item-price">"Total"</div>
+                                    </li>
+                                    {draft
+                                        .order_items
+                                        .iter()
+                                        .map(|item| {
+                                            view! { class=class_name,
+                                                <li class="review-item">
+                                                    <div class="item-name">{item.product_name.clone()}</div>
+                                                    <div>{item.units}</div>
+                                                    <div class="item-price">{format!("${:.2}", item.unit_price)}</div>
+                                                    <div class="item-price">{format!("${:.2}", item.unit_price * rust_decimal::Decimal::from(item.units))}</div>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view()}
+                                </ul>
+                                {is_empty.then(|| view! { class=class_name, <p>"Your shopping bag is empty."</p> })}
+                                <div class="review-total">
+                                    <span>"Total"</span>
+                                    <span>{format!("${:.2}", draft.total)}</span>
+                                </div>
+                            </div>
+                            <div class="form-section">
+                                <h2>Shipping address</h2>
+                                <address>{address.into_iter().map(|line| view! { class=class_name, <div>{line}</div> }).collect_view()}</address>
+                            </div>
+                            <div class="form-section">
+                                <h2>Payment</h2>
+                                <div>{format!("{card_type} {}, expires {}", mask_card_number(&data.card_number), data.card_expiration)}</div>
+                            </div>
+                            <div class="form-section">
+                                <div class="form-buttons">
+                                    <button class="button button-secondary" on:click=move |_| sig_review.set(None)>
+                                        <img role="presentation" src="icons/arrow-left.svg" />
+                                        Back to the details
+                                    </button>
+                                    <button
+                                        class="button button-primary"
+                                        disabled=move || is_empty || place_order_action.pending().get()
+                                        on:click=move |_| {
+                                            // a second click before the button is disabled is the same order again
+                                            if place_order_action.pending().get_untracked() {
+                                                return;
+                                            }
+                                            if let Some(data) = sig_review.get_untracked() {
+                                                place_order_action.dispatch(data);
+                                            }
+                                        }
+                                    >
+                                        Place order
+                                    </button>
+                                </div>
+                            </div>
+                        </div>
+                    }
+                })
+            })
+        })
+    };
+
     let checkout_view = move || {
         Suspend::new(async move {
-            view! { class=class_name,
-                <div class="checkout">
+            let card_type_options = card_types.await?.into_iter().map(|t| (t.id.to_string(), t.name)).collect::<Vec<_>>();
+            let addresses = addresses.await?;
+            Ok::<_, crate::AppError>(view! { class=class_name,
+                <div class="checkout" style:display=move || if sig_review.with(Option::is_some) { "none" } else { "" }>
                     <EditForm
                         init_data_resource=init_data_res
                         form_action=form_action
-                        on_ok=|_data| {
-                            let navigate = use_navigate();
-                            navigate("/user/orders", Default::default());
-                        }
+                        on_ok=move |data| sig_review.set(Some(data))
                     >
+                        <PlaceOrderErrors place_order_action=place_order_action />
                         <div class="form">
                             <div class="form-section">
                                 <h2>Shipping address</h2>
+                                <AddressPicker class_name=class_name addresses=addresses />
                                 <label>
                                     Address
                                     <InPutText
@@ -247,6 +653,7 @@ pub fn CheckoutPage() -> impl IntoView {
                                     </label>
                                 </div>
                             </div>
+                            <PaymentFields class_name=class_name card_type_options=card_type_options />
                             <div class="form-section">
                                 <div class="form-buttons">
                                     <a href="cart" class="button button-secondary">
@@ -254,7 +661,7 @@ pub fn CheckoutPage() -> impl IntoView {
                                         Back to the shopping bag
                                     </a>
                                     <button class="button button-primary" type="submit">
-                                        Place order
+                                        Review order
                                     </button>
                                 </div>
                             </div>
@@ -264,13 +671,17 @@ pub fn CheckoutPage() -> impl IntoView {
                     > _phantom=std::marker::PhantomData />
                     </EditForm>
                 </div>
-            }
+                <Show when=move || sig_review.with(Option::is_some)>
+                    <div class="checkout">{review_view}</div>
+                </Show>
+            })
         })
     };
 
     crate::app::page_header::set_title("Checkout");
     view! { class=class_name,
         <Title text=format!("Checkout | AdventureWorks") />
+        {place_order_error_view}
         <Transition fallback=move || view! { <p>"Loading data..."</p> }>
             <ErrorBoundary fallback=|errors| {
                 view! { <ErrorTemplate errors /> }
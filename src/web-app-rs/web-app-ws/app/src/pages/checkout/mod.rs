@@ -4,4 +4,6 @@ use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
 
 mod checkout;
+#[cfg(feature = "ssr")]
+mod payment;
 pub(crate) use checkout::CheckoutPage;
@@ -0,0 +1,111 @@
+//! Checks of the payment fields of the checkout form on the server, the card data itself only goes on to the ordering-api.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use valitron::{available::Message, rule::string::StringRule};
+
+/// The Luhn checksum of the card number, `digits` holds nothing but ascii digits.
+pub(super) fn luhn_valid(digits: &str) -> bool {
+    let sum = digits
+        .bytes()
+        .rev()
+        .map(|b| u32::from(b - b'0'))
+        .enumerate()
+        .map(|(i, d)| match i % 2 {
+            0 => d,
+            _ if d > 4 => d * 2 - 9,
+            _ => d * 2,
+        })
+        .sum::<u32>();
+    sum % 10 == 0
+}
+
+/// `MM/YY` as the first instant of the following month, a card is valid through its month of expiry.
+pub(super) fn parse_card_expiration(value: &str) -> Option<DateTime<Utc>> {
+    let (month, year) = value.split_once('/')?;
+    let (month, year) = (month.trim().parse::<u32>().ok()?, year.trim().parse::<i32>().ok()?);
+    if !(1..=12).contains(&month) || !(0..100).contains(&year) {
+        return None;
+    }
+    let (year, month) = if month == 12 { (2001 + year, 1) } else { (2000 + year, month + 1) };
+    Some(NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+fn card_not_expired(value: &str, now: DateTime<Utc>) -> bool {
+    parse_card_expiration(value).is_some_and(|expiration| expiration > now)
+}
+
+// empty values are left to `Required`
+
+/// 12 to 19 digits with a valid Luhn checksum, spaces and dashes are removed.
+#[derive(Clone)]
+pub(super) struct CardNumber;
+
+impl StringRule for CardNumber {
+    type Message = Message;
+    const NAME: &'static str = "card_number";
+
+    fn message(&self) -> Self::Message {
+        Message::fallback("is not a valid card number")
+    }
+
+    fn call(&mut self, data: &mut String) -> bool {
+        data.retain(|c| !c.is_whitespace() && c != '-');
+        data.is_empty() || (data.chars().all(|c| c.is_ascii_digit()) && (12..=19).contains(&data.len()) && luhn_valid(data))
+    }
+}
+
+/// `MM/YY` of this month or later.
+#[derive(Clone)]
+pub(super) struct CardExpiration;
+
+impl StringRule for CardExpiration {
+    type Message = Message;
+    const NAME: &'static str = "card_expiration";
+
+    fn message(&self) -> Self::Message {
+        Message::fallback("must be MM/YY and not expired")
+    }
+
+    fn call(&mut self, data: &mut String) -> bool {
+        data.is_empty() || card_not_expired(data, Utc::now())
+    }
+}
+
+/// The 3 or 4 digits on the card.
+#[derive(Clone)]
+pub(super) struct CardSecurityNumber;
+
+impl StringRule for CardSecurityNumber {
+    type Message = Message;
+    const NAME: &'static str = "card_security_number";
+
+    fn message(&self) -> Self::Message {
+        Message::fallback("must be 3 or 4 digits")
+    }
+
+    fn call(&mut self, data: &mut String) -> bool {
+        data.is_empty() || (data.chars().all(|c| c.is_ascii_digit()) && (3..=4).contains(&data.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_luhn_valid() {
+        assert!(luhn_valid("4111111111111111"));
+        assert!(luhn_valid("378282246310005"));
+        assert!(!luhn_valid("4111111111111112"));
+    }
+
+    #[test]
+    fn test_card_expiration() {
+        let now = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        assert_eq!(parse_card_expiration("12/26"), NaiveDate::from_ymd_opt(2027, 1, 1).unwrap().and_hms_opt(0, 0, 0).map(|x| x.and_utc()));
+        assert!(card_not_expired("10/26", now));
+        assert!(!card_not_expired("09/26", now));
+        assert!(!card_not_expired("13/27", now));
+        assert!(!card_not_expired("1027", now));
+    }
+}
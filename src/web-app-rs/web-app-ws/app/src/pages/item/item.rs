@@ -1,15 +1,15 @@
+use auth::client::UserInfoCntxt;
 use basket_ordering::basket_state::client::refresh_basket_state_info_action;
 use leptos::ev::SubmitEvent;
-use leptos_router::{NavigateOptions, hooks::use_navigate, location};
 use stylers::style_sheet;
 
 use crate::services::product_image_url_provider::ProductImageUrlContext;
 
-use auth::client::UserInfoCntxt;
 use catalog::{server_api::get_catalog_item, types::CatalogItem};
 use error_template::ErrorTemplate;
 
 use super::*;
+use super::item_nav::{ItemBadges, ItemNeighbours, ItemRelated};
 
 use rust_decimal::prelude::ToPrimitive;
 
@@ -43,6 +43,23 @@ pub fn ItemPage() -> impl IntoView {
         None => (),
     });
 
+    let user_info = expect_context::<UserInfoCntxt>().0;
+    let sig_saved = RwSignal::new(false);
+    // the neighbour links reuse the page for another item
+    Effect::new(move || {
+        sig_item_id.track();
+        sig_saved.set(false);
+    });
+
+    let save_for_later_action = Action::new(move |&product_id: &i32| async move { basket_ordering::wishlist::server_api::add_wishlist_item(product_id).await });
+
+    Effect::new(move || match save_for_later_action.value().get() {
+        // already saved before counts as saved too
+        Some(Ok(_)) => sig_saved.set(true),
+        Some(Err(e)) => sig_num_in_cart.set(Err(e)),
+        None => (),
+    });
+
     let class_name = style_sheet!("./app/src/pages/item/item.css");
 
     let num_in_cart_view = move || match sig_num_in_cart.get() {
@@ -67,9 +84,6 @@ pub fn ItemPage() -> impl IntoView {
             let mut price_fmt = numfmt::Formatter::new() // start with blank representation
                 .precision(numfmt::Precision::Decimals(2));
 
-            let user_info_context = expect_context::<UserInfoCntxt>().0;
-            let is_logged_in = user_info_context.get().is_some();
-
             item.await.map(|item: Option<catalog::types::CatalogItem>| {
                 item.map_or_else(
                     || {
@@ -98,31 +112,26 @@ pub fn ItemPage() -> impl IntoView {
                         crate::app::page_header::set_subtitle(barand_name.as_str());
 
                         let product_image_url = product_image_url_context.service.get_product_image_url(&item);
+                        let product_image_srcset = product_image_url_context.service.get_product_image_srcset(item.id);
                         let item_ = item.clone();
+                        let item_badges = item.clone();
+                        let item_id = item.id;
+                        // anonymous users add to the guest basket of their session
                         let on_submit = move |e: SubmitEvent| {
                             e.prevent_default();
-                            match is_logged_in {
-                                true => {
-                                    add_to_basket_action.dispatch(item_.clone());
-                                }
-                                false => {
-                                    let url: ReadSignal<location::Url> = hooks::use_url();
-                                    let url = url.get_untracked();
-                                    let navigate = use_navigate();
-                                    let login_url = auth::login_url(&url);
-                                    navigate(&login_url, NavigateOptions::default());
-                                }
-                            }
+                            add_to_basket_action.dispatch(item_.clone());
                         };
 
                         view! { class=class_name,
                             <Title text=format!("{} | AdventureWorks", item.name) />
 
+                            <ItemNeighbours item_id />
+
                             <div class="item-details">
-                                <img alt=item.name src=product_image_url />
+                                <img alt=item.name src=product_image_url srcset=product_image_srcset sizes="(max-width: 480px) 50vw, 25rem" />
                                 <div class="description">
                                     <p>{item.description}</p>
-                                    <p>Brand: <strong>{barand_name}</strong></p>
+                                    <ItemBadges item=item_badges />
                                     <form
                                         class="add-to-cart"
                                         // method="post"
@@ -131,82 +140,68 @@ pub fn ItemPage() -> impl IntoView {
 
                                         <span class="price">{price.to_string()}</span>
 
-                                        {move || {
-                                            if is_logged_in {
-                                                view! { class=class_name,
-                                                    <button type="submit" title="Add to basket">
-                                                        <svg
-                                                            width="24"
-                                                            height="24"
-                                                            viewBox="0 0 24 24"
-                                                            fill="none"
-                                                            stroke="currentColor"
-                                                            xmlns="http://www.w3.org/2000/svg"
-                                                        >
-                                                            <path
-                                                                id="Vector"
-                                                                d="M6 2L3 6V20C3 20.5304 3.21071 21.0391 3.58579 21.4142C3.96086 21.7893 4.46957 22 5 22H19C19.5304 22 20.0391 21.7893 20.4142 21.4142C20.7893 21.0391 21 20.5304 21 20V6L18 2H6Z"
-                                                                stroke-width="1.5"
-                                                                stroke-linecap="round"
-                                                                stroke-linejoin="round"
-                                                            />
-                                                            <path
-                                                                id="Vector_2"
-                                                                d="M3 6H21"
-                                                                stroke-width="1.5"
-                                                                stroke-linecap="round"
-                                                                stroke-linejoin="round"
-                                                            />
-                                                            <path
-                                                                id="Vector_3"
-                                                                d="M16 10C16 11.0609 15.5786 12.0783 14.8284 12.8284C14.0783 13.5786 13.0609 14 12 14C10.9391 14 9.92172 13.5786 9.17157 12.8284C8.42143 12.0783 8 11.0609 8 10"
-                                                                stroke-width="1.5"
-                                                                stroke-linecap="round"
-                                                                stroke-linejoin="round"
-                                                            />
-                                                        </svg>
-                                                        Add to shopping bag
-                                                    </button>
-                                                }
-                                                    .into_any()
-                                            } else {
-
-                                                view! { class=class_name,
-                                                    <button type="submit" title="Log in to purchase">
-                                                        <svg
-                                                            width="24"
-                                                            height="24"
-                                                            viewBox="0 0 24 24"
-                                                            fill="none"
-                                                            stroke="currentColor"
-                                                            xmlns="http://www.w3.org/2000/svg"
-                                                        >
-                                                            <path
-                                                                d="M20 21V19C20 17.9391 19.5786 16.9217 18.8284 16.1716C18.0783 15.4214 17.0609 15 16 15H8C6.93913 15 5.92172 15.4214 5.17157 16.1716C4.42143 16.9217 4 17.9391 4 19V21"
-                                                                stroke-width="1.5"
-                                                                stroke-linecap="round"
-                                                                stroke-linejoin="round"
-                                                            />
-                                                            <path
-                                                                d="M12 11C14.2091 11 16 9.20914 16 7C16 4.79086 14.2091 3 12 3C9.79086 3 8 4.79086 8 7C8 9.20914 9.79086 11 12 11Z"
-                                                                stroke-width="1.5"
-                                                                stroke-linecap="round"
-                                                                stroke-linejoin="round"
-                                                            />
-                                                        </svg>
-                                                        Log in to purchase
-                                                    </button>
-                                                }
-                                                    .into_any()
-                                            }
-                                        }}
+                                        <button type="submit" title="Add to basket">
+                                            <svg
+                                                width="24"
+                                                height="24"
+                                                viewBox="0 0 24 24"
+                                                fill="none"
+                                                stroke="currentColor"
+                                                xmlns="http://www.w3.org/2000/svg"
+                                            >
+                                                <path
+                                                    id="Vector"
+                                                    d="M6 2L3 6V20C3 20.5304 3.21071 21.0391 3.58579 21.4142C3.96086 21.7893 4.46957 22 5 22H19C19.5304 22 20.0391 21.7893 20.4142 21.4142C20.7893 21.0391 21 20.5304 21 20V6L18 2H6Z"
+                                                    stroke-width="1.5"
+                                                    stroke-linecap="round"
+                                                    stroke-linejoin="round"
+                                                />
+                                                <path
+                                                    id="Vector_2"
+                                                    d="M3 6H21"
+                                                    stroke-width="1.5"
+                                                    stroke-linecap="round"
+                                                    stroke-linejoin="round"
+                                                />
+                                                <path
+                                                    id="Vector_3"
+                                                    d="M16 10C16 11.0609 15.5786 12.0783 14.8284 12.8284C14.0783 13.5786 13.0609 14 12 14C10.9391 14 9.92172 13.5786 9.17157 12.8284C8.42143 12.0783 8 11.0609 8 10"
+                                                    stroke-width="1.5"
+                                                    stroke-linecap="round"
+                                                    stroke-linejoin="round"
+                                                />
+                                            </svg>
+                                            Add to shopping bag
+                                        </button>
 
                                     </form>
 
                                     {num_in_cart_view}
 
+                                    // the wishlist is kept per user, guests only have the bag
+                                    <Show when=move || user_info.with(|user_info| user_info.is_some())>
+                                        <Show
+                                            when=move || sig_saved.get()
+                                            fallback=move || view! { class=class_name,
+                                                <button
+                                                    type="button"
+                                                    class="button button-secondary save-for-later"
+                                                    on:click=move |_| {
+                                                        save_for_later_action.dispatch(item_id);
+                                                    }
+                                                >
+                                                    "Save for later"
+                                                </button>
+                                            }
+                                        >
+                                            <p>"Saved for later, see the " <a href="cart">"shopping bag"</a></p>
+                                        </Show>
+                                    </Show>
+
                                 </div>
                             </div>
+
+                            <ItemRelated item_id=item_id as usize />
                         }
                         .into_any()
                     },
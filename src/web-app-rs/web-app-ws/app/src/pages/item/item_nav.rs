@@ -0,0 +1,181 @@
+use leptos::prelude::*;
+use leptos_router::{hooks, location, params::ParamsMap};
+use stylers::style_sheet;
+
+use catalog::{
+    server_api::{get_item_neighbours, get_related_items, get_types},
+    service::{CatalogFilter, CatalogItemNeighbour},
+    types::CatalogItem,
+};
+
+use crate::pages::catalog::{PAGE_SIZE, page_size_from_query, sort_from_query};
+use crate::services::product_image_url_provider::ProductImageUrlContext;
+
+const RELATED_COUNT: usize = 4;
+
+// one style_sheet! for the components of this file, the stylers build adds the css for each `let .. = style_sheet!`
+fn class_name() -> &'static str {
+    let class_name = style_sheet!("./app/src/pages/item/item_nav.css");
+    class_name
+}
+
+/// The catalog listing the item page was opened from, the catalog page passes its query along with the item links.
+fn filter_from_query(params: &ParamsMap) -> (CatalogFilter, usize, usize) {
+    let parse = |name: &str| params.get_str(name).and_then(|v| v.parse::<usize>().ok());
+
+    let filter = CatalogFilter {
+        brand: parse("brand"),
+        type_id: parse("type"),
+        search: params.get_str("q").map(str::trim).filter(|q| !q.is_empty()).map(str::to_string),
+        semantic: params.get_str("semantic") == Some("true"),
+        sort: sort_from_query(params).ok().flatten(),
+    };
+    // `page` is 1-based and omitted for the first page
+    let page_index = parse("page").map(|p| p.saturating_sub(1)).unwrap_or(0);
+    (filter, page_index, page_size_from_query(params).unwrap_or(PAGE_SIZE))
+}
+
+fn neighbour_path(url: &location::Url, neighbour: &CatalogItemNeighbour) -> String {
+    let mut search_params = url.search_params().clone();
+    match neighbour.page_index {
+        0 => {
+            search_params.remove("page");
+        }
+        i => search_params.replace("page", (i + 1).to_string()),
+    }
+    format!("/item/{}{}", neighbour.id, search_params.to_query_string())
+}
+
+/// Brand and type of the item, linking to the catalog filtered by them.
+#[component]
+pub(super) fn ItemBadges(item: CatalogItem) -> impl IntoView {
+    let class_name = class_name();
+
+    let brand_href = format!("/?brand={}", item.catalog_brand_id);
+    let brand_name = item.catalog_brand.map(|x| x.brand).unwrap_or_default();
+
+    // the catalog-api does not include the type with single items
+    let type_id = item.catalog_type_id;
+    let type_name = item.catalog_type.map(|x| x.type_name);
+    let type_name = Resource::new(
+        move || type_name.clone(),
+        move |type_name| async move {
+            match type_name {
+                Some(type_name) => Ok(Some(type_name)),
+                None => get_types().await.map(|types| types.into_iter().find(|x| x.id == type_id).map(|x| x.type_name)),
+            }
+        },
+    );
+
+    let type_view = move || {
+        Suspend::new(async move {
+            type_name.await.ok().flatten().map(|type_name| {
+                view! { class=class_name,
+                    <a class="badge" href=format!("/?type={type_id}")>
+                        {type_name}
+                    </a>
+                }
+            })
+        })
+    };
+
+    view! { class=class_name,
+        <p class="badges">
+            <a class="badge" href=brand_href>
+                {brand_name}
+            </a>
+            <Suspense fallback=|| ()>{type_view}</Suspense>
+        </p>
+    }
+}
+
+/// Back, previous and next links within the listing the item page was opened from.
+#[component]
+pub(super) fn ItemNeighbours(item_id: i32) -> impl IntoView {
+    let class_name = class_name();
+
+    let sig_url: ReadSignal<location::Url> = hooks::use_url();
+
+    let neighbours = Resource::new(move || sig_url.with(|url| filter_from_query(url.search_params())), move |(filter, page_index, page_size)| async move { get_item_neighbours(item_id, filter, page_index, page_size).await });
+
+    let neighbours_view = move || {
+        Suspend::new(async move {
+            let url = sig_url.get_untracked();
+            let back_href = format!("/{}", url.search_params().to_query_string());
+            let neighbours = neighbours.await.unwrap_or_default();
+
+            view! { class=class_name,
+                <nav class="item-nav">
+                    <a href=back_href>"Back to catalog"</a>
+                    <span class="prev-next">
+                        {neighbours
+                            .prev
+                            .map(|prev| {
+                                view! { class=class_name,
+                                    <a rel="prev" href=neighbour_path(&url, &prev) title=prev.name.clone()>
+                                        "‹ Previous"
+                                    </a>
+                                }
+                            })}
+                        {neighbours
+                            .next
+                            .map(|next| {
+                                view! { class=class_name,
+                                    <a rel="next" href=neighbour_path(&url, &next) title=next.name.clone()>
+                                        "Next ›"
+                                    </a>
+                                }
+                            })}
+                    </span>
+                </nav>
+            }
+        })
+    };
+
+    view! { <Suspense fallback=|| ()>{neighbours_view}</Suspense> }
+}
+
+#[component]
+pub(super) fn ItemRelated(item_id: usize) -> impl IntoView {
+    let class_name = class_name();
+
+    let related = Resource::new(move || item_id, |item_id| async move { get_related_items(item_id, RELATED_COUNT).await });
+
+    let related_view = move || {
+        Suspend::new(async move {
+            let product_image_url_context = expect_context::<ProductImageUrlContext>();
+
+            related.await.ok().filter(|items| !items.is_empty()).map(|items| {
+                view! { class=class_name,
+                    <section class="related">
+                        <h2>"Related products"</h2>
+                        <div class="related-items">
+                            {items
+                                .into_iter()
+                                .map(|item| {
+                                    let src = product_image_url_context.service.get_product_image_url(&item);
+                                    let srcset = product_image_url_context.service.get_product_image_srcset(item.id);
+                                    view! { class=class_name,
+                                        <a class="related-item" href=format!("/item/{}", item.id)>
+                                            <img
+                                                alt=item.name.clone()
+                                                src=src
+                                                srcset=srcset
+                                                sizes="(max-width: 480px) 50vw, 20vw"
+                                                loading="lazy"
+                                            />
+                                            <span class="name">{item.name}</span>
+                                            <span class="price">{format!("{:.2}", item.price)}</span>
+                                        </a>
+                                    }
+                                })
+                                .collect::<Vec<_>>()}
+                        </div>
+                    </section>
+                }
+            })
+        })
+    };
+
+    view! { <Suspense fallback=|| ()>{related_view}</Suspense> }
+}
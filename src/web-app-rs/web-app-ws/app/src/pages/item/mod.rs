@@ -1,10 +1,7 @@
 use leptos::prelude::*;
 
 use leptos_meta::Title;
-use leptos_router::{
-    hooks::{self, use_params},
-    params::Params,
-};
+use leptos_router::{hooks::use_params, params::Params};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -12,6 +9,8 @@ use thiserror::Error;
 mod item;
 pub(crate) use item::ItemPage;
 
+mod item_nav;
+
 #[derive(Error, Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum ItemPageError {
     #[error("Invalid Item Index")]
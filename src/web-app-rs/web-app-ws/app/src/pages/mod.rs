@@ -1,13 +1,17 @@
+mod addresses;
+mod admin;
 mod cart;
 mod catalog;
 mod checkout;
 mod item;
 mod orders;
+pub(crate) use addresses::{AddressPage, AddressesPage};
+pub(crate) use admin::{AdminCatalogItemPage, AdminCatalogPage, AdminOnly};
 pub(crate) use cart::CartPage;
 pub(crate) use catalog::CatalogPage;
 pub(crate) use checkout::CheckoutPage;
 pub(crate) use item::ItemPage;
-pub(crate) use orders::OrdersPage;
+pub(crate) use orders::{OrderDetailsPage, OrdersPage};
 
 mod parameter_from_query {
 
@@ -23,4 +27,10 @@ mod parameter_from_query {
     pub struct BrandId {
         pub brand: Option<usize>,
     }
+
+    #[derive(Params, PartialEq)]
+    pub struct Search {
+        pub q: Option<String>,
+        pub semantic: Option<bool>,
+    }
 }
@@ -1,5 +1,7 @@
 use leptos::prelude::*;
 use leptos::*;
 
+mod order_details;
 mod orders;
+pub(crate) use order_details::OrderDetailsPage;
 pub(crate) use orders::OrdersPage;
@@ -0,0 +1,146 @@
+use super::*;
+
+use basket_ordering::ordering::{
+    server_api::get_order,
+    types::{OrderDetails, OrderStatusChange, order_timeline},
+};
+use leptos_router::{hooks::use_params, params::Params};
+
+use error_template::ErrorTemplate;
+
+use leptos_meta::Title;
+use stylers::style_sheet;
+
+#[derive(Params, PartialEq, Clone, Debug)]
+pub struct OrderIdParams {
+    id: Option<i32>,
+}
+
+fn address_lines(order: &OrderDetails) -> Vec<String> {
+    let city_line = [order.zipcode.as_deref(), order.city.as_deref()].into_iter().flatten().filter(|x| !x.is_empty()).collect::<Vec<_>>().join(" ");
+    [order.street.clone(), Some(city_line), order.state.clone(), order.country.clone()].into_iter().flatten().filter(|x| !x.is_empty()).collect()
+}
+
+#[component]
+pub fn OrderDetailsPage() -> impl IntoView {
+    let params = use_params::<OrderIdParams>();
+    let sig_order_id = Signal::derive(move || params.with(|p| p.as_ref().ok().and_then(|p| p.id)));
+
+    // the live events of this order, the timeline gets their times
+    let status_changes = RwSignal::new(Vec::<OrderStatusChange>::new());
+
+    if cfg!(feature = "hydrate") {
+        use futures::{StreamExt, channel::mpsc};
+        let (_tx, rx) = mpsc::channel(1);
+        task::spawn_local(async move {
+            match basket_ordering::ordering::server_api::order_status_notify(rx.into()).await {
+                Ok(mut messages) => {
+                    while let Some(change) = messages.next().await {
+                        match change {
+                            Ok(change) if Some(change.order_id) == sig_order_id.get_untracked() => status_changes.update(|changes| changes.push(change)),
+                            Ok(_) => (),
+                            Err(e) => leptos::logging::error!("{e}"),
+                        }
+                    }
+                }
+                Err(e) => leptos::logging::error!("{e}"),
+            }
+        });
+    }
+
+    let order = Resource::new(
+        move || sig_order_id.get(),
+        |order_id| async move {
+            match order_id {
+                Some(order_id) => get_order(order_id).await,
+                None => Ok(None),
+            }
+        },
+    );
+
+    let class_name = style_sheet!("./app/src/pages/orders/order_details.css");
+
+    let timeline_view = move |status: String| {
+        move || {
+            order_timeline(&status, &status_changes.get())
+                .into_iter()
+                .map(|step| {
+                    view! { class=class_name,
+                        <li class=format!("timeline-step {class_name} {} {}", step.status.to_lowercase(), if step.reached { "reached" } else { "" })>
+                            <span class="timeline-status">{step.status.clone()}</span>
+                            <span class="timeline-at">{step.at.map(|at| at.format("%d-%b-%y %H:%M:%S").to_string()).unwrap_or_default()}</span>
+                        </li>
+                    }
+                })
+                .collect_view()
+        }
+    };
+
+    let order_view = move || {
+        Suspend::new(async move {
+            order.await.map(|order| match order {
+                None => {
+                    crate::app::page_header::set_title("Order not found");
+                    view! { class=class_name, <p>"Sorry, we couldn't find this order." <a href="/user/orders">"Back to your orders."</a></p> }.into_any()
+                }
+                Some(order) => {
+                    crate::app::page_header::set_title(&format!("Order #{}", order.order_number));
+                    let address = address_lines(&order);
+
+                    view! { class=class_name,
+                        <Title text=format!("Order #{} | AdventureWorks", order.order_number) />
+                        <div class="order-summary">
+                            <div>{order.date.format("%d-%b-%y %H:%M:%S").to_string()}</div>
+                            <div class="order-total">{format!("${:.2}", order.total)}</div>
+                        </div>
+                        {order.description.clone().filter(|d| !d.is_empty()).map(|d| view! { class=class_name, <p class="order-description">{d}</p> })}
+
+                        <div class="order-details">
+                            <ul class="order-items">
+                                <li class="order-item order-items-header">
+                                    <div class="item-name">"Product"</div>
+                                    <div>"Units"</div>
+                                    <div class="item-price">"Unit price"</div>
+                                    <div class="item-price">"Total"</div>
+                                </li>
+                                {order
+                                    .order_items
+                                    .iter()
+                                    .map(|item| {
+                                        view! { class=class_name,
+                                            <li class="order-item">
+                                                <div class="item-name">{item.product_name.clone()}</div>
+                                                <div>{item.units}</div>
+                                                <div class="item-price">{format!("${:.2}", item.unit_price)}</div>
+                                                <div class="item-price">{format!("${:.2}", item.unit_price * rust_decimal::Decimal::from(item.units))}</div>
+                                            </li>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </ul>
+
+                            <div class="order-side">
+                                <h3>"Shipping address"</h3>
+                                <address>{address.into_iter().map(|line| view! { class=class_name, <div>{line}</div> }).collect_view()}</address>
+
+                                <h3>"Status"</h3>
+                                <ol class="timeline">{timeline_view(order.status.clone())}</ol>
+                            </div>
+                        </div>
+                        <a href="/user/orders" class="order-back">"Back to your orders"</a>
+                    }
+                    .into_any()
+                }
+            })
+        })
+    };
+
+    view! { class=class_name,
+        <Title text="Order | AdventureWorks" />
+        <Suspense fallback=move || view! { <p>"Loading data..."</p> }>
+            <ErrorBoundary fallback=|errors| view! { <ErrorTemplate errors /> }>
+                <div class="order">{order_view}</div>
+            </ErrorBoundary>
+        </Suspense>
+    }
+}
@@ -1,17 +1,170 @@
 use super::*;
 
+use std::collections::HashSet;
+
 use basket_ordering::basket_state::client::refresh_basket_state_info_action;
+use basket_ordering::ordering::{
+    server_api::{cancel_order, export_orders_csv, get_orders, order_status_notify},
+    types::{ORDER_STATUS_CANCELLED, ORDER_STATUS_STEPS, OrderFilter, OrderSort, is_cancellable},
+};
+use uuid::Uuid;
 
 use error_template::ErrorTemplate;
 
 use leptos_meta::Title;
+use leptos_router::{
+    components::Form,
+    hooks::{self, use_query_map},
+    params::ParamsMap,
+};
 use stylers::style_sheet;
 
+use crate::{
+    components::{NavLinkCb, NavLinkGr},
+    pages::catalog::path_from_url,
+};
+
+const PAGE_SIZE: usize = 10;
+
+/// `status`, `from`, `to` and `sort` of the orders query, an empty value is no filter.
+fn filter_from_query(params: &ParamsMap) -> Result<OrderFilter, crate::AppError> {
+    let invalid = |name: &str| crate::AppError::Other(format!("Invalid {name} of the orders query"));
+    let get = |name: &str| params.get_str(name).filter(|x| !x.is_empty());
+
+    let status = get("status").map(|x| x.to_string());
+    if status.as_deref().is_some_and(|x| x != ORDER_STATUS_CANCELLED && !ORDER_STATUS_STEPS.contains(&x)) {
+        return Err(invalid("status"));
+    }
+    Ok(OrderFilter {
+        status,
+        from: get("from").map(|x| x.parse()).transpose().map_err(|_| invalid("from date"))?,
+        to: get("to").map(|x| x.parse()).transpose().map_err(|_| invalid("to date"))?,
+        sort: get("sort").map(|x| x.parse()).transpose().map_err(|_| invalid("sort order"))?,
+    })
+}
+
+/// `page` of the query is 1-based.
+fn page_index_from_query(params: &ParamsMap) -> usize {
+    params.get_str("page").and_then(|x| x.parse::<usize>().ok()).map(|x| x.saturating_sub(1)).unwrap_or(0)
+}
+
+fn sort_label(sort: OrderSort) -> &'static str {
+    match sort {
+        OrderSort::Newest => "Newest",
+        OrderSort::Oldest => "Oldest",
+        OrderSort::TotalDesc => "Total: high to low",
+        OrderSort::TotalAsc => "Total: low to high",
+    }
+}
+
+/// The CSV as a `data:` URL of the download link, all but the unreserved characters percent encoded.
+fn csv_data_url(csv: &str) -> String {
+    let mut url = String::from("data:text/csv;charset=utf-8,");
+    for b in csv.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => url.push(b as char),
+            _ => url.push_str(&format!("%{b:02X}")),
+        }
+    }
+    url
+}
+
+#[component]
+fn OrderFilters(class_name: &'static str, sig_filter: Signal<Result<OrderFilter, crate::AppError>>, export_action: Action<OrderFilter, Result<String, crate::AppError>>) -> impl IntoView {
+    let sig_url: ReadSignal<leptos_router::location::Url> = hooks::use_url();
+    let filter = move || sig_filter.get().unwrap_or_default();
+
+    // a new selection starts at the first page
+    let uri = move |name: &'static str, value: Option<&'static str>| {
+        let mut url = sig_url.get();
+        path_from_url(&mut url, &[("page", None), (name, value.map(|x| x.to_string()))])
+    };
+
+    let statuses = ORDER_STATUS_STEPS.into_iter().chain([ORDER_STATUS_CANCELLED]).collect::<Vec<_>>();
+
+    view! { class=class_name,
+        <div class="orders-filters">
+            <div class="orders-filter-group">
+                <h3>Status</h3>
+                <div class="orders-filter-tags">
+                    <a href=move || uri("status", None) class="orders-filter-tag" class:active=move || filter().status.is_none()>
+                        All
+                    </a>
+                    {statuses
+                        .into_iter()
+                        .map(|status| {
+                            view! { class=class_name,
+                                <a
+                                    href=move || uri("status", Some(status))
+                                    class="orders-filter-tag"
+                                    class:active=move || filter().status.as_deref() == Some(status)
+                                >
+                                    {status}
+                                </a>
+                            }
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+            <div class="orders-filter-group">
+                <h3>Sort</h3>
+                <div class="orders-filter-tags">
+                    {OrderSort::ALL
+                        .into_iter()
+                        .map(|sort| {
+                            let value = if sort == OrderSort::Newest { None } else { Some(sort.as_str()) };
+                            view! { class=class_name,
+                                <a
+                                    href=move || uri("sort", value)
+                                    class="orders-filter-tag"
+                                    class:active=move || filter().sort.unwrap_or(OrderSort::Newest) == sort
+                                >
+                                    {sort_label(sort)}
+                                </a>
+                            }
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+            <Form method="get" action="/user/orders">
+                <div class="orders-filter-dates">
+                    <input type="hidden" name="status" prop:value=move || filter().status.unwrap_or_default() />
+                    <input type="hidden" name="sort" prop:value=move || filter().sort.map(|x| x.as_str()).unwrap_or_default() />
+                    <label>
+                        From
+                        <input type="date" name="from" prop:value=move || filter().from.map(|x| x.to_string()).unwrap_or_default() />
+                    </label>
+                    <label>
+                        To
+                        <input type="date" name="to" prop:value=move || filter().to.map(|x| x.to_string()).unwrap_or_default() />
+                    </label>
+                    <button class="button button-secondary" type="submit">
+                        Apply
+                    </button>
+                    <button
+                        class="button button-secondary"
+                        type="button"
+                        disabled=move || export_action.pending().get() || sig_filter.with(Result::is_err)
+                        on:click=move |_| {
+                            export_action.dispatch(filter());
+                        }
+                    >
+                        Export CSV
+                    </button>
+                </div>
+            </Form>
+        </div>
+    }
+    .into_any()
+}
+
 #[component]
 pub fn OrdersPage() -> impl IntoView {
     use futures::{StreamExt, channel::mpsc};
     let (_tx, rx) = mpsc::channel(1);
     let refresh_orders = RwSignal::new(true);
+    // cancelled orders that the ordering-api did not confirm yet
+    let cancelling = RwSignal::new(HashSet::<i32>::new());
 
     if cfg!(feature = "hydrate") {
         task::spawn_local(async move {
@@ -31,23 +184,103 @@ pub fn OrdersPage() -> impl IntoView {
         });
     }
 
+    if cfg!(feature = "hydrate") {
+        let (_tx, rx) = mpsc::channel(1);
+        task::spawn_local(async move {
+            match order_status_notify(rx.into()).await {
+                Ok(mut messages) => {
+                    while let Some(change) = messages.next().await {
+                        match change {
+                            Ok(change) => {
+                                if change.status == ORDER_STATUS_CANCELLED {
+                                    cancelling.update(|x| {
+                                        x.remove(&change.order_id);
+                                    });
+                                }
+                                refresh_orders.update(|x| *x = !*x);
+                            }
+                            Err(e) => leptos::logging::error!("{e}"),
+                        }
+                    }
+                }
+                Err(e) => leptos::logging::error!("{e}"),
+            }
+        });
+    }
+
     Effect::new(move || {
         refresh_basket_state_info_action().dispatch(());
     });
 
-    let orders = Resource::new(move || refresh_orders.get(), |_| async move { basket_ordering::ordering::server_api::get_orders().await });
+    // a new request id per click, retries of the same click are deduplicated by the ordering-api
+    let cancel_action = Action::new(move |&order_number: &i32| async move { (order_number, cancel_order(order_number, Uuid::new_v4()).await) });
+
+    Effect::new(move || match cancel_action.value().get() {
+        Some((_, Ok(()))) => refresh_orders.update(|x| *x = !*x),
+        Some((order_number, Err(e))) => {
+            leptos::logging::error!("{e}");
+            cancelling.update(|x| {
+                x.remove(&order_number);
+            });
+        }
+        None => (),
+    });
+
+    let sig_url: ReadSignal<leptos_router::location::Url> = hooks::use_url();
+    let params = use_query_map();
+    let sig_filter = Signal::derive(move || params.with(filter_from_query));
+    let sig_page_index = Signal::derive(move || params.with(page_index_from_query));
+
+    // the live status changes refetch the page shown with its filter
+    let orders = Resource::new(move || (refresh_orders.get(), sig_page_index.get(), sig_filter.get()), |(_, page_index, filter)| async move { get_orders(page_index, PAGE_SIZE, filter?).await });
+
+    let sig_cb: RwSignal<Option<NavLinkCb>> = RwSignal::new(None);
+
+    let export_action = Action::new(move |filter: &OrderFilter| {
+        let filter = filter.clone();
+        async move { export_orders_csv(filter).await }
+    });
+    let export_link = NodeRef::<leptos::html::A>::new();
+    Effect::new(move || {
+        if let Some(Ok(csv)) = export_action.value().get()
+            && let Some(link) = export_link.get_untracked()
+        {
+            link.set_href(&csv_data_url(&csv));
+            link.click();
+        }
+    });
 
     let class_name = style_sheet!("./app/src/pages/orders/orders.css");
 
+    // a failed cancel or export is shown above the list, the orders stay
+    let cancel_error_view = move || match cancel_action.value().get() {
+        Some((order_number, Err(e))) => Some(view! { <p class="validation-message">{format!("Order {order_number} could not be cancelled: {e}")}</p> }),
+        _ => None,
+    };
+
+    let export_error_view = move || match export_action.value().get() {
+        Some(Err(e)) => Some(view! { <p class="validation-message">{format!("The orders could not be exported: {e}")}</p> }),
+        _ => None,
+    };
+
     let orders_view = move || {
         Suspend::new(async move {
             view! { class=class_name,
                 {orders
                     .await
                     .map(|d| {
-                        if d.is_empty() {
+                        sig_cb.set(Some(NavLinkCb {
+                            url: sig_url.get_untracked(),
+                            page_index: d.page_index,
+                            page_size: d.page_size,
+                            count: d.count,
+                        }));
+                        if d.data.is_empty() && sig_filter.get_untracked().is_ok_and(|x| x == OrderFilter::default()) {
                             view! { class=class_name, <p>"You haven't yet placed any orders."</p> }
                                 .into_any()
+                        } else if d.data.is_empty() {
+                            view! { class=class_name, <p>"No orders match the filter."</p> }
+                                .into_any()
                         } else {
                             view! { class=class_name,
                                 <ul class="orders-list">
@@ -56,13 +289,36 @@ pub fn OrdersPage() -> impl IntoView {
                                         <div>{"Date"}</div>
                                         <div class="total-header">{"Total"}</div>
                                         <div>{"Status"}</div>
+                                        <div></div>
                                     </li>
                                     {d
+                                        .data
                                         .into_iter()
                                         .map(|item| {
+                                            let order_number = item.order_number as i32;
+                                            let cancel_view = is_cancellable(&item.status)
+                                                .then(|| {
+                                                    let is_cancelling = move || cancelling.with(|x| x.contains(&order_number));
+                                                    view! { class=class_name,
+                                                        <button
+                                                            class="button button-secondary"
+                                                            disabled=is_cancelling
+                                                            on:click=move |_| {
+                                                                cancelling.update(|x| {
+                                                                    x.insert(order_number);
+                                                                });
+                                                                cancel_action.dispatch(order_number);
+                                                            }
+                                                        >
+                                                            {move || if is_cancelling() { "Cancelling..." } else { "Cancel" }}
+                                                        </button>
+                                                    }
+                                                });
                                             view! { class=class_name,
                                                 <li class="orders-item">
-                                                    <div class="order-number">{item.order_number}</div>
+                                                    <div class="order-number">
+                                                        <a href=format!("/user/orders/{}", item.order_number)>{item.order_number}</a>
+                                                    </div>
                                                     <div class="order-date">
                                                         {item.date.format("%d-%b-%y %H:%M:%S").to_string()}
                                                     </div>
@@ -75,6 +331,7 @@ pub fn OrdersPage() -> impl IntoView {
                                                             item.status.clone().to_lowercase(),
                                                         )>{item.status.clone()}</span>
                                                     </div>
+                                                    <div class="order-actions">{cancel_view}</div>
                                                 </li>
                                             }
                                                 .into_any()
@@ -96,9 +353,23 @@ pub fn OrdersPage() -> impl IntoView {
 
     view! { class=class_name,
         <Title text=format!("Orders | AdventureWorks") />
+        {cancel_error_view}
+        {export_error_view}
         <Suspense fallback=move || view! { <p>"Loading data..."</p> }>
             <ErrorBoundary fallback=|errors| view! { <ErrorTemplate errors /> }>
-                <div class="orders">{orders_view}</div>
+                <div class="orders">
+                    <OrderFilters class_name sig_filter export_action />
+                    {orders_view}
+                    <div class="page-links">
+                        <NavLinkGr
+                            css_active_class="active-page".to_string()
+                            sig_cb=sig_cb.read_only()
+                            q_param_name="page"
+                            fn_index_to_string=|i| if i == 1 { None } else { Some(i.to_string()) }
+                        />
+                    </div>
+                    <a node_ref=export_link class="orders-export-link" download="orders.csv"></a>
+                </div>
             </ErrorBoundary>
         </Suspense>
     }
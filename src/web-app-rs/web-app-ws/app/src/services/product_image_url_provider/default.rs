@@ -11,6 +11,9 @@ impl ProductImageUrlProvider for ProductImageUrlProviderDefault {
     fn get_product_image_url_by_id(&self, product_id: i32) -> String {
         format!("product-images/{product_id}?api-version=1.0")
     }
+    fn get_product_image_url_sized(&self, product_id: i32, width: u32) -> String {
+        format!("product-images/{product_id}?api-version=1.0&w={width}&format=webp")
+    }
 }
 
 pub fn make_service() -> ProductImageUrlContext {
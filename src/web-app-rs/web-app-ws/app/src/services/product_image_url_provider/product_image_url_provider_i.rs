@@ -2,10 +2,21 @@ use std::sync::Arc;
 
 pub use catalog::types::CatalogItem;
 
+/// Widths of the resized variants offered in `srcset`.
+pub const PRODUCT_IMAGE_WIDTHS: [u32; 4] = [160, 320, 640, 960];
+
 pub trait ProductImageUrlProvider: Send + Sync {
     fn get_product_image_url(&self, item: &CatalogItem) -> String;
 
     fn get_product_image_url_by_id(&self, product_id: i32) -> String;
+
+    /// The picture resized to `width`, keeping the aspect ratio.
+    fn get_product_image_url_sized(&self, product_id: i32, width: u32) -> String;
+
+    /// `srcset` attribute value with a variant for each of `PRODUCT_IMAGE_WIDTHS`.
+    fn get_product_image_srcset(&self, product_id: i32) -> String {
+        PRODUCT_IMAGE_WIDTHS.iter().map(|w| format!("{} {w}w", self.get_product_image_url_sized(product_id, *w))).collect::<Vec<_>>().join(", ")
+    }
 }
 
 #[derive(Clone)]
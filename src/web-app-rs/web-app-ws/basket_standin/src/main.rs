@@ -0,0 +1,85 @@
+//! Local stand-in of the .NET basket-api for development and e2e runs, the `BasketApi.Basket` gRPC service
+//! of basket.proto. The web app finds it like the real one through `services__basket_api__http__0`,
+//! the default address matches the one in webapp.env.
+
+mod store;
+mod token;
+
+use std::net::SocketAddr;
+
+use anyhow::Result;
+use basket_ordering::basket::server::basket_grpc::{
+    BasketItem, CustomerBasketResponse, DeleteBasketRequest, DeleteBasketResponse, GetBasketRequest, UpdateBasketRequest,
+    basket_server::{Basket, BasketServer},
+};
+use log::{error, info};
+use tonic::{Request, Response, Status};
+
+use store::BasketStore;
+use token::TokenValidator;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:5221";
+
+struct BasketStandIn {
+    store: BasketStore,
+    tokens: TokenValidator,
+}
+
+fn store_err(e: anyhow::Error) -> Status {
+    error!("basket store: {e:?}");
+    Status::internal("basket store error")
+}
+
+fn response(items: store::Quantities) -> Response<CustomerBasketResponse> {
+    Response::new(CustomerBasketResponse {
+        items: items.into_iter().map(|(product_id, quantity)| BasketItem { product_id, quantity }).collect(),
+    })
+}
+
+#[tonic::async_trait]
+impl Basket for BasketStandIn {
+    async fn get_basket(&self, request: Request<GetBasketRequest>) -> Result<Response<CustomerBasketResponse>, Status> {
+        let sub = self.tokens.sub_from_metadata(request.metadata()).await?;
+        Ok(response(self.store.get(&sub).await.map_err(store_err)?))
+    }
+
+    async fn update_basket(&self, request: Request<UpdateBasketRequest>) -> Result<Response<CustomerBasketResponse>, Status> {
+        let sub = self.tokens.sub_from_metadata(request.metadata()).await?;
+        let items = request.into_inner().items.into_iter().map(|item| (item.product_id, item.quantity)).collect();
+        self.store.update(&sub, items).await.map_err(store_err)?;
+        Ok(response(self.store.get(&sub).await.map_err(store_err)?))
+    }
+
+    async fn delete_basket(&self, request: Request<DeleteBasketRequest>) -> Result<Response<DeleteBasketResponse>, Status> {
+        let sub = self.tokens.sub_from_metadata(request.metadata()).await?;
+        self.store.delete(&sub).await.map_err(store_err)?;
+        Ok(Response::new(DeleteBasketResponse {}))
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = log4rs::init_file("log4rs.yaml", Default::default()) {
+        eprintln!("log4rs.yaml not loaded: {e}");
+    }
+
+    if let Err(e) = main_().await {
+        error!("{:?}", e);
+    }
+}
+
+/// `BASKET_STANDIN_ADDR` is the listen address, see [`BasketStore::from_env`] and [`TokenValidator::from_env`]
+/// for the storage and the token settings.
+async fn main_() -> Result<()> {
+    let addr: SocketAddr = std::env::var("BASKET_STANDIN_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string()).parse()?;
+
+    let store = BasketStore::from_env().await?;
+    let tokens = TokenValidator::from_env().await?;
+    if tokens.is_dev() {
+        info!("BASKET_STANDIN_ISSUER not set, accepting dev tokens without any check");
+    }
+
+    info!("basket stand-in listening on {addr}");
+    tonic::transport::Server::builder().add_service(BasketServer::new(BasketStandIn { store, tokens })).serve(addr).await?;
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use anyhow::Result;
+use sqlx::SqlitePool;
+
+/// Product id and quantity, the only fields of a basket item in basket.proto.
+pub type Quantities = Vec<(i32, i32)>;
+
+/// Baskets by user `sub`, like the basket-api an update replaces the whole basket.
+pub enum BasketStore {
+    Memory(Mutex<HashMap<String, Quantities>>),
+    Sqlite(SqlitePool),
+}
+
+impl BasketStore {
+    /// SQLite when `BASKET_STANDIN_DB` holds a database url (e.g. `sqlite://baskets.db?mode=rwc`), memory otherwise.
+    pub async fn from_env() -> Result<Self> {
+        match std::env::var("BASKET_STANDIN_DB").ok().filter(|v| !v.trim().is_empty()) {
+            Some(url) => Self::sqlite(url.trim()).await,
+            None => Ok(Self::memory()),
+        }
+    }
+
+    pub fn memory() -> Self {
+        BasketStore::Memory(Mutex::new(HashMap::new()))
+    }
+
+    pub async fn sqlite(url: &str) -> Result<Self> {
+        let db = SqlitePool::connect(url).await?;
+        sqlx::query("create table if not exists basket_items (user_sub text not null, product_id integer not null, quantity integer not null, position integer not null, primary key (user_sub, product_id))")
+            .execute(&db)
+            .await?;
+        Ok(BasketStore::Sqlite(db))
+    }
+
+    pub async fn get(&self, sub: &str) -> Result<Quantities> {
+        match self {
+            BasketStore::Memory(baskets) => Ok(baskets.lock().unwrap_or_else(|e| e.into_inner()).get(sub).cloned().unwrap_or_default()),
+            BasketStore::Sqlite(db) => Ok(sqlx::query_as("select product_id, quantity from basket_items where user_sub = ? order by position").bind(sub).fetch_all(db).await?),
+        }
+    }
+
+    /// Items without a positive quantity are dropped, the same product twice keeps the last quantity.
+    pub async fn update(&self, sub: &str, items: Quantities) -> Result<()> {
+        let mut basket = Quantities::new();
+        for (product_id, quantity) in items.into_iter().filter(|(_, quantity)| *quantity > 0) {
+            match basket.iter_mut().find(|(id, _)| *id == product_id) {
+                Some(item) => item.1 = quantity,
+                None => basket.push((product_id, quantity)),
+            }
+        }
+
+        match self {
+            BasketStore::Memory(baskets) => {
+                let mut baskets = baskets.lock().unwrap_or_else(|e| e.into_inner());
+                if basket.is_empty() {
+                    baskets.remove(sub);
+                } else {
+                    baskets.insert(sub.to_string(), basket);
+                }
+            }
+            BasketStore::Sqlite(db) => {
+                let mut tx = db.begin().await?;
+                sqlx::query("delete from basket_items where user_sub = ?").bind(sub).execute(&mut *tx).await?;
+                for (position, (product_id, quantity)) in basket.into_iter().enumerate() {
+                    sqlx::query("insert into basket_items (user_sub, product_id, quantity, position) values (?, ?, ?, ?)").bind(sub).bind(product_id).bind(quantity).bind(position as i64).execute(&mut *tx).await?;
+                }
+                tx.commit().await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn delete(&self, sub: &str) -> Result<()> {
+        self.update(sub, vec![]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stores_replace_whole_baskets() {
+        for store in [BasketStore::memory(), BasketStore::sqlite("sqlite::memory:").await.unwrap()] {
+            store.update("alice", vec![(1, 2), (2, 0), (3, 1), (1, 4)]).await.unwrap();
+            store.update("bob", vec![(5, 1)]).await.unwrap();
+            assert_eq!(store.get("alice").await.unwrap(), vec![(1, 4), (3, 1)]);
+
+            store.update("alice", vec![(3, 2)]).await.unwrap();
+            assert_eq!(store.get("alice").await.unwrap(), vec![(3, 2)]);
+
+            store.delete("alice").await.unwrap();
+            assert!(store.get("alice").await.unwrap().is_empty());
+            assert_eq!(store.get("bob").await.unwrap(), vec![(5, 1)]);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, Validation, decode, decode_header,
+    jwk::{Jwk, JwkSet},
+};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tonic::Status;
+
+/// Tokens with an unknown `kid` fetch the keys of the issuer again at most this often.
+const JWKS_REFETCH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+}
+
+#[derive(Deserialize)]
+struct Discovery {
+    jwks_uri: String,
+}
+
+/// The keys of the issuer and when they were fetched.
+pub struct IssuerKeys {
+    set: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Turns the bearer token of a call into the `sub` whose basket it gets.
+pub enum TokenValidator {
+    /// Signature, issuer, expiry and audience checked against the keys of an OpenID Connect issuer.
+    Issuer { issuer: String, audience: Option<String>, jwks_uri: String, http_client: reqwest::Client, keys: RwLock<IssuerKeys> },
+    /// Nothing is checked: the `sub` of a JWT is taken as is and any other token is the `sub` itself,
+    /// e.g. `Bearer alice`. Only for local runs.
+    Dev,
+}
+
+impl TokenValidator {
+    /// `BASKET_STANDIN_ISSUER` is the identity server, e.g. `https://localhost:5243`, and `BASKET_STANDIN_AUDIENCE`
+    /// the audience the tokens must have (default `basket`, empty disables the check). Without an issuer dev
+    /// tokens are accepted. `BASKET_STANDIN_ACCEPT_INVALID_CERTS=true` trusts the development certificate of the issuer.
+    pub async fn from_env() -> Result<Self> {
+        let Some(issuer) = std::env::var("BASKET_STANDIN_ISSUER").ok().filter(|v| !v.trim().is_empty()) else {
+            return Ok(TokenValidator::Dev);
+        };
+        let issuer = issuer.trim().trim_end_matches('/').to_string();
+        let audience = match std::env::var("BASKET_STANDIN_AUDIENCE") {
+            Ok(audience) if audience.trim().is_empty() => None,
+            Ok(audience) => Some(audience.trim().to_string()),
+            Err(_) => Some("basket".to_string()),
+        };
+        let accept_invalid_certs = std::env::var("BASKET_STANDIN_ACCEPT_INVALID_CERTS").is_ok_and(|v| v.trim() == "true");
+
+        let http_client = reqwest::Client::builder().danger_accept_invalid_certs(accept_invalid_certs).build()?;
+        let discovery = http_client.get(format!("{issuer}/.well-known/openid-configuration")).send().await?.error_for_status()?.json::<Discovery>().await?;
+        let keys = http_client.get(&discovery.jwks_uri).send().await?.error_for_status()?.json::<JwkSet>().await?;
+
+        Ok(TokenValidator::Issuer {
+            issuer,
+            audience,
+            jwks_uri: discovery.jwks_uri,
+            http_client,
+            keys: RwLock::new(IssuerKeys { set: keys, fetched_at: Instant::now() }),
+        })
+    }
+
+    pub fn is_dev(&self) -> bool {
+        matches!(self, TokenValidator::Dev)
+    }
+
+    /// The `sub` of the `authorization` metadata, `Unauthenticated` for a missing or invalid token.
+    pub async fn sub_from_metadata(&self, metadata: &tonic::metadata::MetadataMap) -> Result<String, Status> {
+        let token = metadata.get("authorization").and_then(|v| v.to_str().ok()).and_then(|v| v.strip_prefix("Bearer ")).map(str::trim).filter(|t| !t.is_empty()).ok_or_else(|| Status::unauthenticated("bearer token required"))?;
+
+        self.sub(token).await.map_err(|e| {
+            log::info!("rejected token: {e}");
+            Status::unauthenticated("invalid token")
+        })
+    }
+
+    async fn sub(&self, token: &str) -> Result<String> {
+        match self {
+            TokenValidator::Dev => Ok(dev_sub(token)),
+            TokenValidator::Issuer { issuer, audience, jwks_uri, http_client, keys } => {
+                let header = decode_header(token)?;
+                let kid = header.kid.ok_or_else(|| anyhow!("token without kid"))?;
+
+                // an unknown kid means the issuer rotated its keys, or a made up kid that must not make every call
+                // fetch the keys again
+                if keys.read().await.set.find(&kid).is_none() {
+                    let mut keys = keys.write().await;
+                    if keys.set.find(&kid).is_none() && keys.fetched_at.elapsed() >= JWKS_REFETCH_INTERVAL {
+                        keys.fetched_at = Instant::now();
+                        keys.set = http_client.get(jwks_uri).send().await?.error_for_status()?.json::<JwkSet>().await?;
+                    }
+                }
+                let keys = keys.read().await;
+                let jwk = keys.set.find(&kid).ok_or_else(|| anyhow!("unknown kid {kid}"))?;
+                let key = DecodingKey::from_jwk(jwk)?;
+
+                // the algorithm of the key, never the one the token claims
+                let mut validation = Validation::new(key_algorithm(jwk)?);
+                validation.set_issuer(&[issuer]);
+                match audience {
+                    Some(audience) => validation.set_audience(&[audience]),
+                    None => validation.validate_aud = false,
+                }
+                Ok(decode::<Claims>(token, &key, &validation)?.claims.sub)
+            }
+        }
+    }
+}
+
+/// The `alg` of the key, RS256 of the identity server for a key without one.
+fn key_algorithm(jwk: &Jwk) -> Result<Algorithm> {
+    match jwk.common.key_algorithm {
+        Some(alg) => alg.to_string().parse::<Algorithm>().map_err(|_| anyhow!("key algorithm {alg} is not for signatures")),
+        None => Ok(Algorithm::RS256),
+    }
+}
+
+fn dev_sub(token: &str) -> String {
+    let mut validation = Validation::default();
+    validation.insecure_disable_signature_validation();
+    validation.validate_aud = false;
+    validation.validate_exp = false;
+    validation.required_spec_claims.clear();
+
+    match decode_header(token).and_then(|header| {
+        validation.algorithms = vec![header.alg];
+        decode::<Claims>(token, &DecodingKey::from_secret(&[]), &validation)
+    }) {
+        Ok(data) => data.claims.sub,
+        Err(_) => token.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+
+    #[test]
+    fn test_dev_sub() {
+        #[derive(serde::Serialize)]
+        struct Claims<'a> {
+            sub: &'a str,
+            exp: u64,
+        }
+        let jwt = encode(&Header::default(), &Claims { sub: "alice-sub", exp: 1 }, &EncodingKey::from_secret(b"any")).unwrap();
+
+        assert_eq!(dev_sub(&jwt), "alice-sub");
+        assert_eq!(dev_sub("bob"), "bob");
+    }
+
+    #[derive(serde::Serialize)]
+    struct IssuedClaims<'a> {
+        sub: &'a str,
+        iss: &'a str,
+        exp: u64,
+    }
+
+    /// An issuer with one HS256 key `k1`, its keys were fetched `fetched_ago`.
+    fn issuer(fetched_ago: Duration) -> TokenValidator {
+        let set: JwkSet = serde_json::from_value(serde_json::json!({
+            "keys": [{ "kty": "oct", "kid": "k1", "alg": "HS256", "k": "c2VjcmV0" }]
+        }))
+        .unwrap();
+        TokenValidator::Issuer {
+            issuer: "https://issuer".to_string(),
+            audience: None,
+            // nothing listens there, a fetch fails
+            jwks_uri: "http://127.0.0.1:9/jwks".to_string(),
+            http_client: reqwest::Client::new(),
+            keys: RwLock::new(IssuerKeys { set, fetched_at: Instant::now() - fetched_ago }),
+        }
+    }
+
+    fn token(alg: Algorithm, kid: &str) -> String {
+        let header = Header { kid: Some(kid.to_string()), ..Header::new(alg) };
+        let claims = IssuedClaims { sub: "alice-sub", iss: "https://issuer", exp: 4_000_000_000 };
+        encode(&header, &claims, &EncodingKey::from_secret(b"secret")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_algorithm_of_the_key() {
+        let validator = issuer(Duration::ZERO);
+
+        assert_eq!(validator.sub(&token(Algorithm::HS256, "k1")).await.unwrap(), "alice-sub");
+        assert!(validator.sub(&token(Algorithm::HS384, "k1")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_refetch_is_limited() {
+        let validator = issuer(JWKS_REFETCH_INTERVAL);
+
+        // the first unknown kid fetches the keys, the next ones wait for the interval
+        let e = validator.sub(&token(Algorithm::HS256, "k2")).await.unwrap_err();
+        assert!(e.downcast_ref::<reqwest::Error>().is_some());
+        let e = validator.sub(&token(Algorithm::HS256, "k2")).await.unwrap_err();
+        assert_eq!(e.to_string(), "unknown kid k2");
+    }
+}
@@ -0,0 +1,2 @@
+-- Comma separated roles of the user.
+alter table users add column roles text;
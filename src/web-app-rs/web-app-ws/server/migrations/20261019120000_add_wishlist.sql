@@ -0,0 +1,9 @@
+-- Products saved for later, one row per user and product.
+create table if not exists wishlist_items
+(
+    user_sub   text    not null,
+    product_id integer not null,
+    -- Unix seconds.
+    added_at   integer not null,
+    primary key (user_sub, product_id)
+);
@@ -0,0 +1,21 @@
+-- Shipping addresses of the address book, the address claims of the identity server only seed the first one.
+create table if not exists addresses
+(
+    id         integer primary key autoincrement,
+    user_sub   text    not null,
+    street     text    not null,
+    city       text    not null,
+    state      text    not null,
+    zip_code   text    not null,
+    country    text    not null,
+    -- Unix seconds.
+    created_at integer not null
+);
+
+create index if not exists addresses_user_sub on addresses (user_sub);
+
+-- Users whose book got the address claims already, deleting that address must not bring it back.
+create table if not exists address_books_seeded
+(
+    user_sub text not null primary key
+);
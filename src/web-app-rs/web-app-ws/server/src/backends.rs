@@ -0,0 +1,66 @@
+use anyhow::Result;
+
+use basket_ordering::{basket::service::BasketServiceContext, ordering::service::OrderingServiceContext};
+use catalog::service::CatalogServiceContext;
+
+use crate::url_mapper::UrlMapService;
+
+pub struct Backends {
+    pub catalog_service_context: CatalogServiceContext,
+    pub basket_service_context: BasketServiceContext,
+    pub ordering_service_context: OrderingServiceContext,
+}
+
+#[cfg(not(feature = "mock-backends"))]
+pub async fn make_backends(url_mapper: UrlMapService) -> Result<Backends> {
+    use api_version::versioning;
+    use catalog::server::HttpClient;
+
+    let catalog_api_version = match std::env::var("CATALOG_API_VERSION") {
+        Ok(v) => v.parse::<versioning::QueryStringApiVersion>()?,
+        Err(_) => versioning::QueryStringApiVersion::from((1, 0)),
+    };
+
+    let catalog_service_context = ::catalog::server::make_service(HttpClient::new(), url_mapper.clone(), catalog_api_version)?;
+    let catalog_service_context = ::catalog::cache::make_cached_service(catalog_service_context, ::catalog::cache::CatalogCacheConfig::from_env()?).await;
+
+    let basket_service_context = basket_ordering::basket::server::make_service(url_mapper.clone()).await.unwrap();
+
+    let ordering_service_context = basket_ordering::ordering::server::make_service(basket_ordering::ordering::server::HttpClient::new(), url_mapper.clone(), versioning::QueryStringApiVersion::from((1, 0))).await.unwrap();
+
+    Ok(Backends {
+        catalog_service_context,
+        basket_service_context,
+        ordering_service_context,
+    })
+}
+
+#[cfg(not(feature = "mock-backends"))]
+pub async fn product_images_router(url_mapper: UrlMapService) -> Result<axum::Router> {
+    use crate::forwarder::{image_cache, product_images};
+
+    let cache = image_cache::ImageCache::open(image_cache::ImageCacheConfig::from_env()?).await?;
+    Ok(product_images::router(product_images::catalog_api_source(url_mapper), cache))
+}
+
+/// In-memory catalog, basket and ordering services, the catalog is seeded from `CATALOG_MOCK_FIXTURE`.
+#[cfg(feature = "mock-backends")]
+pub async fn make_backends(_url_mapper: UrlMapService) -> Result<Backends> {
+    log::info!("using mock backends");
+
+    Ok(Backends {
+        catalog_service_context: ::catalog::mock::make_service_from_env()?,
+        basket_service_context: basket_ordering::basket::mock::make_service(),
+        ordering_service_context: basket_ordering::ordering::mock::make_service(),
+    })
+}
+
+/// Product images are read from `CATALOG_MOCK_PICS`, by default the pictures of the .NET catalog-api.
+#[cfg(feature = "mock-backends")]
+pub async fn product_images_router(_url_mapper: UrlMapService) -> Result<axum::Router> {
+    use crate::forwarder::{image_cache, product_images};
+
+    let pics_dir = std::env::var("CATALOG_MOCK_PICS").unwrap_or_else(|_| "../../Catalog.API/Pics".to_string());
+    let cache = image_cache::ImageCache::open(image_cache::ImageCacheConfig::from_env()?).await?;
+    Ok(product_images::router(product_images::ImageSource::Dir(pics_dir.into()), cache))
+}
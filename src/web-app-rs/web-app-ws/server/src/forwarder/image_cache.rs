@@ -0,0 +1,188 @@
+use std::{
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result, anyhow};
+use log::warn;
+use lru::LruCache;
+use sha2::{Digest, Sha256};
+
+pub struct ImageCacheConfig {
+    pub dir: PathBuf,
+    /// Total size of the cached files, the least recently used ones are removed above it. 0 disables the cache.
+    pub max_bytes: u64,
+    /// Age after which a cached picture is fetched and rendered again.
+    pub ttl: Duration,
+}
+
+impl Default for ImageCacheConfig {
+    fn default() -> Self {
+        Self {
+            dir: std::env::temp_dir().join("web-app-rs-product-images"),
+            max_bytes: 256 * 1024 * 1024,
+            ttl: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+impl ImageCacheConfig {
+    /// Reads `PRODUCT_IMAGE_CACHE_DIR`, `PRODUCT_IMAGE_CACHE_MAX_MB` and `PRODUCT_IMAGE_CACHE_TTL` (seconds);
+    /// unset variables keep the default.
+    pub fn from_env() -> Result<Self> {
+        let u64_from_env = |name: &str| -> Result<Option<u64>> { std::env::var(name).ok().map(|v| v.trim().parse::<u64>().map_err(|e| anyhow!("{name}: {e}"))).transpose() };
+
+        let default = Self::default();
+        Ok(Self {
+            dir: std::env::var("PRODUCT_IMAGE_CACHE_DIR").map(PathBuf::from).unwrap_or(default.dir),
+            max_bytes: u64_from_env("PRODUCT_IMAGE_CACHE_MAX_MB")?.map(|mb| mb * 1024 * 1024).unwrap_or(default.max_bytes),
+            ttl: u64_from_env("PRODUCT_IMAGE_CACHE_TTL")?.map(Duration::from_secs).unwrap_or(default.ttl),
+        })
+    }
+}
+
+struct Index {
+    /// file name -> size
+    files: LruCache<String, u64>,
+    total: u64,
+}
+
+/// Rendered pictures on disk, one file per key, evicted least recently used first.
+pub struct ImageCache {
+    config: ImageCacheConfig,
+    index: Mutex<Index>,
+}
+
+fn file_name(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// Strong ETag of the response body.
+pub fn etag(bytes: &[u8]) -> String {
+    format!("\"{:.32x}\"", Sha256::digest(bytes))
+}
+
+impl ImageCache {
+    /// Creates the directory or picks up the files of a previous run, oldest first.
+    pub async fn open(config: ImageCacheConfig) -> Result<Self> {
+        tokio::fs::create_dir_all(&config.dir).await.with_context(|| format!("cannot create image cache dir {}", config.dir.display()))?;
+
+        let mut existing = Vec::new();
+        let mut dir = tokio::fs::read_dir(&config.dir).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            // a `.tmp` left by an interrupted write is not part of the cache
+            if metadata.is_file() && entry.path().extension().is_none() {
+                existing.push((metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH), entry.file_name().to_string_lossy().to_string(), metadata.len()));
+            }
+        }
+        existing.sort();
+
+        let cache = Self {
+            config,
+            index: Mutex::new(Index { files: LruCache::unbounded(), total: 0 }),
+        };
+        for (_, name, size) in existing {
+            cache.insert(name, size).await;
+        }
+        Ok(cache)
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.config.ttl
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Index> {
+        self.index.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let name = file_name(key);
+        self.lock().files.get(&name)?;
+
+        let path = self.config.dir.join(&name);
+        let age = tokio::fs::metadata(&path).await.ok()?.modified().ok()?.elapsed().unwrap_or_default();
+        if age > self.config.ttl {
+            return None;
+        }
+        tokio::fs::read(&path).await.ok()
+    }
+
+    pub async fn put(&self, key: &str, bytes: &[u8]) {
+        if bytes.len() as u64 > self.config.max_bytes {
+            return;
+        }
+        let name = file_name(key);
+
+        // write and rename, readers never see a partial file
+        let path = self.config.dir.join(&name);
+        let tmp = path.with_extension("tmp");
+        let written = async {
+            tokio::fs::write(&tmp, bytes).await?;
+            tokio::fs::rename(&tmp, &path).await
+        };
+        match written.await {
+            Ok(()) => self.insert(name, bytes.len() as u64).await,
+            Err(e) => warn!("cannot write image cache file {}: {e}", path.display()),
+        }
+    }
+
+    async fn insert(&self, name: String, size: u64) {
+        let evicted = {
+            let mut index = self.lock();
+            if let Some(old) = index.files.put(name, size) {
+                index.total -= old;
+            }
+            index.total += size;
+
+            let mut evicted = Vec::new();
+            while index.total > self.config.max_bytes {
+                let Some((name, size)) = index.files.pop_lru() else { break };
+                index.total -= size;
+                evicted.push(name);
+            }
+            evicted
+        };
+
+        for name in evicted {
+            let _ = tokio::fs::remove_file(self.config.dir.join(name)).await;
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.lock().files.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_least_recently_used_evicted() {
+        let dir = std::env::temp_dir().join(format!("image-cache-test-{}", std::process::id()));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+
+        let config = ImageCacheConfig { dir: dir.clone(), max_bytes: 10, ttl: Duration::from_secs(60) };
+        let cache = ImageCache::open(config).await.unwrap();
+
+        cache.put("a", b"1234").await;
+        cache.put("b", b"5678").await;
+        assert_eq!(cache.get("a").await.as_deref(), Some(&b"1234"[..]));
+
+        // "b" is the least recently used now
+        cache.put("c", b"90ab").await;
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").await.is_none());
+        assert!(cache.get("a").await.is_some());
+        assert!(!dir.join(file_name("b")).exists());
+
+        // a new cache over the same directory finds the files
+        let cache = ImageCache::open(ImageCacheConfig { dir: dir.clone(), max_bytes: 10, ttl: Duration::from_secs(60) }).await.unwrap();
+        assert_eq!(cache.get("c").await.as_deref(), Some(&b"90ab"[..]));
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+}
@@ -0,0 +1,105 @@
+use std::io::Cursor;
+
+use anyhow::{Result, anyhow};
+use app::services::product_image_url_provider::PRODUCT_IMAGE_WIDTHS;
+use image::{DynamicImage, ImageFormat, imageops::FilterType};
+use serde::Deserialize;
+
+/// AVIF encoder speed (1 slow .. 10 fast) and quality (1 .. 100).
+const AVIF_SPEED: u8 = 8;
+const AVIF_QUALITY: u8 = 70;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VariantFormat {
+    #[default]
+    Webp,
+    Avif,
+}
+
+/// `w`, `h` and `format` of the image route; without any of them the original picture is served.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct ImageVariant {
+    #[serde(rename = "w")]
+    pub width: Option<u32>,
+    #[serde(rename = "h")]
+    pub height: Option<u32>,
+    pub format: Option<VariantFormat>,
+}
+
+impl ImageVariant {
+    pub fn is_original(&self) -> bool {
+        self.width.is_none() && self.height.is_none() && self.format.is_none()
+    }
+
+    /// Zero means unset, anything else is snapped up to the next of the `srcset` widths, or down to the largest, so
+    /// there are few variants of a picture to render and cache.
+    pub fn normalized(self) -> Self {
+        let largest = PRODUCT_IMAGE_WIDTHS[PRODUCT_IMAGE_WIDTHS.len() - 1];
+        let snap = |d: Option<u32>| d.filter(|d| *d > 0).map(|d| PRODUCT_IMAGE_WIDTHS.into_iter().find(|w| *w >= d).unwrap_or(largest));
+        ImageVariant {
+            width: snap(self.width),
+            height: snap(self.height),
+            format: self.format,
+        }
+    }
+
+    /// Part of the cache key, the same variant of the same picture always gives the same key.
+    pub fn key(&self) -> String {
+        let dim = |d: Option<u32>| d.map(|d| d.to_string()).unwrap_or_default();
+        match self.format {
+            _ if self.is_original() => "original".to_string(),
+            format => format!("{}x{}.{:?}", dim(self.width), dim(self.height), format.unwrap_or_default()).to_lowercase(),
+        }
+    }
+
+    /// Fits the picture into width x height keeping the aspect ratio, never scales up.
+    pub fn render(&self, source: &[u8]) -> Result<Vec<u8>> {
+        let img = image::load_from_memory(source)?;
+
+        let (width, height) = (self.width.unwrap_or(u32::MAX).min(img.width()), self.height.unwrap_or(u32::MAX).min(img.height()));
+        let img = if (width, height) == (img.width(), img.height()) { img } else { img.resize(width, height, FilterType::Lanczos3) };
+
+        let mut out = Cursor::new(Vec::new());
+        match self.format.unwrap_or_default() {
+            VariantFormat::Webp => DynamicImage::ImageRgba8(img.to_rgba8()).write_to(&mut out, ImageFormat::WebP)?,
+            VariantFormat::Avif => {
+                let encoder = image::codecs::avif::AvifEncoder::new_with_speed_quality(&mut out, AVIF_SPEED, AVIF_QUALITY);
+                img.write_with_encoder(encoder)?
+            }
+        }
+        Ok(out.into_inner())
+    }
+}
+
+/// Content type of an original picture from its first bytes.
+pub fn sniff_content_type(bytes: &[u8]) -> Result<&'static str> {
+    image::guess_format(bytes).map(|f| f.to_mime_type()).map_err(|e| anyhow!("unknown image format: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_fits_without_upscaling() {
+        let mut png = Cursor::new(Vec::new());
+        DynamicImage::new_rgba8(400, 200).write_to(&mut png, ImageFormat::Png).unwrap();
+        let png = png.into_inner();
+
+        let variant = ImageVariant { width: Some(100), ..Default::default() }.normalized();
+        assert_eq!(variant.key(), "160x.webp");
+        let webp = image::load_from_memory(&variant.render(&png).unwrap()).unwrap();
+        assert_eq!((webp.width(), webp.height()), (160, 80));
+
+        let variant = ImageVariant { width: Some(5000), height: Some(0), ..Default::default() }.normalized();
+        assert_eq!(variant.width, Some(960));
+        assert_eq!(variant.height, None);
+        assert_eq!(ImageVariant { width: Some(321), height: Some(640), ..Default::default() }.normalized().key(), "640x640.webp");
+        let webp = image::load_from_memory(&variant.render(&png).unwrap()).unwrap();
+        assert_eq!((webp.width(), webp.height()), (400, 200));
+
+        assert!(ImageVariant::default().is_original());
+        assert_eq!(sniff_content_type(&png).unwrap(), "image/png");
+    }
+}
@@ -1,70 +1,173 @@
+pub mod image_cache;
+mod image_variant;
 pub mod product_images;
+pub mod routes;
 
 type Client = reqwest::Client;
 
-use anyhow::{Context, Result};
-use axum::{body::HttpBody, extract::Request};
+use anyhow::{Context, Result, anyhow};
+use axum::body::HttpBody;
+use axum::extract::Request;
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header};
 use axum::{
-    body::{Body, Bytes},
-    response::Response,
+    body::Body,
+    response::{IntoResponse, Response},
 };
+use futures::{StreamExt, TryStreamExt};
 
-//use log::info;
-use tokio_stream::{StreamExt, wrappers::UnboundedReceiverStream};
+/// Headers that only apply to a single connection and are never forwarded (RFC 9110 7.6.1).
+const HOP_BY_HOP: [HeaderName; 8] = [header::CONNECTION, HeaderName::from_static("keep-alive"), header::PROXY_AUTHENTICATE, header::PROXY_AUTHORIZATION, header::TE, header::TRAILER, header::TRANSFER_ENCODING, header::UPGRADE];
 
-async fn forward(url: &str, req: Request, client: Client) -> Result<Response> {
-    //info!("Sent request to {url} ");
+#[derive(Clone, Debug)]
+pub struct ForwardOptions {
+    /// Replaces the `Authorization` header of the request.
+    pub bearer_token: Option<String>,
+    pub max_request_bytes: u64,
+    pub max_response_bytes: u64,
+}
 
-    let method = req.method().clone();
-
-    let mut body_stream = req.into_body().into_data_stream();
-
-    let resp = if body_stream.is_end_stream() {
-        let reqwest_response = client.request(method, url);
-
-        let resp = reqwest_response.send().await;
-
-        resp.with_context(|| format!("Error sending request to {url}"))?
-    } else {
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        let body_copy_h = tokio::spawn(async move {
-            loop {
-                let r: Result<Option<Bytes>, axum::Error> = body_stream.try_next().await;
-
-                match r {
-                    Ok(Some(b)) => match tx.send(Ok(b)) {
-                        Ok(_) => {}
-                        Err(e) => {
-                            return Err(e);
-                        }
-                    },
-                    Ok(None) => {
-                        return Ok(());
-                    }
-                    Err(e) => match tx.send(Err(e)) {
-                        Ok(_) => {
-                            return Ok(());
-                        }
-                        Err(e) => {
-                            return Err(e);
-                        }
-                    },
-                }
-            }
-        });
+impl Default for ForwardOptions {
+    fn default() -> Self {
+        Self {
+            bearer_token: None,
+            max_request_bytes: 10 * 1024 * 1024,
+            max_response_bytes: 50 * 1024 * 1024,
+        }
+    }
+}
+
+/// Copies `headers` without the hop-by-hop ones, the ones named by `Connection` and `removed`.
+fn end_to_end_headers(headers: &HeaderMap, removed: &[HeaderName]) -> HeaderMap {
+    let named_by_connection = headers.get_all(header::CONNECTION).iter().filter_map(|v| v.to_str().ok()).flat_map(|v| v.split(',')).filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok()).collect::<Vec<_>>();
+
+    let mut rslt = headers.clone();
+    for name in HOP_BY_HOP.iter().chain(named_by_connection.iter()).chain(removed.iter()) {
+        rslt.remove(name);
+    }
+    rslt
+}
+
+/// The error that cuts a body off in [`limited`].
+#[derive(Debug)]
+struct BodyTooLarge(u64);
 
-        let reqwest_response = client.request(method, url).body(reqwest::Body::wrap_stream(UnboundedReceiverStream::new(rx)));
+impl std::fmt::Display for BodyTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "body larger than {} bytes", self.0)
+    }
+}
 
-        let resp = reqwest_response.send().await;
+impl std::error::Error for BodyTooLarge {}
+
+/// Stops a body stream with a [`BodyTooLarge`] error once more than `limit` bytes went through.
+fn limited<S, E>(stream: S, limit: u64) -> impl futures::Stream<Item = Result<axum::body::Bytes, axum::BoxError>>
+where
+    S: futures::Stream<Item = Result<axum::body::Bytes, E>>,
+    E: Into<axum::BoxError>,
+{
+    stream.map_err(Into::into).scan(0u64, move |total, chunk| {
+        let chunk = chunk.and_then(|chunk| {
+            *total += chunk.len() as u64;
+            if *total > limit { Err(BodyTooLarge(limit).into()) } else { Ok(chunk) }
+        });
+        futures::future::ready(Some(chunk))
+    })
+}
 
-        let body_copy_err = body_copy_h.await.unwrap();
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers.get(header::CONTENT_LENGTH)?.to_str().ok()?.parse().ok()
+}
 
-        let _ = body_copy_err.with_context(|| "Error copying body")?;
+/// Sends `req` to `url` with its end-to-end headers and streams the response back, `Range` requests
+/// and partial responses pass through unchanged. The session cookie of the web app stays here.
+async fn forward(url: &str, req: Request, client: Client, options: &ForwardOptions) -> Result<Response> {
+    //info!("Sent request to {url} ");
 
-        resp.with_context(|| "Error sending request")?
+    if content_length(req.headers()).is_some_and(|len| len > options.max_request_bytes) {
+        return Ok(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+    }
+
+    let (parts, body) = req.into_parts();
+
+    let mut headers = end_to_end_headers(&parts.headers, &[header::HOST, header::COOKIE]);
+    if let Some(host) = parts.headers.get(header::HOST) {
+        headers.insert(HeaderName::from_static("x-forwarded-host"), host.clone());
+    }
+    if let Some(token) = &options.bearer_token {
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}"))?);
+    }
+
+    let mut request = client.request(parts.method, url).headers(headers);
+    if !body.is_end_stream() {
+        request = request.body(reqwest::Body::wrap_stream(limited(body.into_data_stream(), options.max_request_bytes)));
+    }
+
+    let resp = match request.send().await {
+        Ok(resp) => resp,
+        // a streamed body without a content length ran over the limit
+        Err(e) if std::iter::successors(Some(&e as &dyn std::error::Error), |e| e.source()).any(|e| e.is::<BodyTooLarge>()) => return Ok(StatusCode::PAYLOAD_TOO_LARGE.into_response()),
+        Err(e) => return Err(e).with_context(|| format!("Error sending request to {url}")),
     };
 
-    let response_builder = Response::builder().status(resp.status().as_u16());
+    if content_length(resp.headers()).is_some_and(|len| len > options.max_response_bytes) {
+        return Err(anyhow!("response of {url} larger than {} bytes", options.max_response_bytes));
+    }
+
+    let mut response_builder = Response::builder().status(resp.status().as_u16());
+    if let Some(h) = response_builder.headers_mut() {
+        *h = end_to_end_headers(resp.headers(), &[header::SET_COOKIE]);
+    }
+
+    response_builder.body(Body::from_stream(limited(resp.bytes_stream(), options.max_response_bytes))).with_context(|| "Error creating response")
+}
 
-    response_builder.body(Body::from_stream(resp.bytes_stream())).with_context(|| "Error creating response")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_end_to_end_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONNECTION, HeaderValue::from_static("keep-alive, x-trace"));
+        headers.insert(HeaderName::from_static("keep-alive"), HeaderValue::from_static("timeout=5"));
+        headers.insert(HeaderName::from_static("x-trace"), HeaderValue::from_static("1"));
+        headers.insert(header::TRANSFER_ENCODING, HeaderValue::from_static("chunked"));
+        headers.insert(header::COOKIE, HeaderValue::from_static("id=1"));
+        headers.insert(header::RANGE, HeaderValue::from_static("bytes=0-99"));
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("image/webp"));
+
+        let rslt = end_to_end_headers(&headers, &[header::COOKIE]);
+        let mut names = rslt.keys().map(|k| k.as_str()).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["content-type", "range"]);
+    }
+
+    #[tokio::test]
+    async fn test_limited() {
+        let chunks = || futures::stream::iter([Ok::<_, std::io::Error>(axum::body::Bytes::from_static(b"1234")), Ok(axum::body::Bytes::from_static(b"5678"))]);
+
+        let all = limited(chunks(), 8).collect::<Vec<_>>().await;
+        assert!(all.iter().all(|c| c.is_ok()));
+
+        let cut = limited(chunks(), 6).collect::<Vec<_>>().await;
+        assert!(cut[0].is_ok());
+        assert!(cut[1].as_ref().is_err_and(|e| e.is::<BodyTooLarge>()));
+    }
+
+    #[tokio::test]
+    async fn test_forward_streamed_body_too_large() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, axum::Router::new().fallback(|body: String| async move { body })).await });
+
+        let options = ForwardOptions { max_request_bytes: 6, ..Default::default() };
+        let chunks = futures::stream::iter([Ok::<_, std::io::Error>(axum::body::Bytes::from_static(b"1234")), Ok(axum::body::Bytes::from_static(b"5678"))]);
+        let req = Request::builder().method("POST").uri("/").body(Body::from_stream(chunks)).unwrap();
+        let resp = forward(&format!("http://{addr}/"), req, Client::new(), &options).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let req = Request::builder().method("POST").uri("/").body(Body::from("1234")).unwrap();
+        let resp = forward(&format!("http://{addr}/"), req, Client::new(), &options).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
 }
@@ -1,5 +1,11 @@
-use axum::extract::{Path, Request};
-use axum::http::StatusCode;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, Weak},
+};
+
+use anyhow::{Result, anyhow};
+use axum::extract::{Path, Query, Request};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
 
 use axum::{
     extract::State,
@@ -7,40 +13,184 @@ use axum::{
     routing::get,
 };
 
-use futures::Future;
-
 use super::Client;
+use super::image_cache::{ImageCache, etag};
+use super::image_variant::{ImageVariant, sniff_content_type};
 use crate::url_mapper::UrlMapService;
 use log::error;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Largest original picture read from the source.
+const MAX_SOURCE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Query parameters passed on to the source, any others are dropped.
+const SOURCE_QUERY_PARAMS: [&str; 1] = ["api-version"];
+
+/// Where the original pictures come from.
+#[derive(Clone)]
+pub enum ImageSource {
+    CatalogApi {
+        client: Client,
+        url_map_service: UrlMapService,
+    },
+    /// `{id}.webp` files in a local directory, used with `mock-backends`.
+    #[cfg(feature = "mock-backends")]
+    Dir(std::path::PathBuf),
+}
+
+impl ImageSource {
+    /// The original picture, `None` if the source has none for `id`.
+    async fn fetch(&self, id: usize, query: &str) -> Result<Option<Vec<u8>>> {
+        match self {
+            ImageSource::CatalogApi { client, url_map_service } => {
+                let base_map_to_path = "http://catalog-api";
+                let base = url_map_service.get_mapped_url(base_map_to_path).unwrap_or(base_map_to_path);
+
+                let uri = format!("{base}/api/catalog/items/{id}/pic{query}");
+
+                // a plain request, the conditional and range headers of the browser are about the variant
+                let resp = client.get(&uri).send().await?;
+                match resp.status() {
+                    StatusCode::NOT_FOUND => Ok(None),
+                    status if status.is_success() => {
+                        if resp.content_length().is_some_and(|len| len > MAX_SOURCE_BYTES) {
+                            return Err(anyhow!("picture {uri} larger than {MAX_SOURCE_BYTES} bytes"));
+                        }
+                        Ok(Some(resp.bytes().await?.to_vec()))
+                    }
+                    status => Err(anyhow!("catalog-api responded {status} for {uri}")),
+                }
+            }
+            #[cfg(feature = "mock-backends")]
+            ImageSource::Dir(pics_dir) => match tokio::fs::read(pics_dir.join(format!("{id}.webp"))).await {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+        }
+    }
+}
+
+/// The `SOURCE_QUERY_PARAMS` of the query, passed on to the source.
+fn source_query(query: Option<&str>) -> String {
+    let rest = query.unwrap_or_default().split('&').filter(|p| SOURCE_QUERY_PARAMS.contains(&p.split('=').next().unwrap_or_default())).collect::<Vec<_>>().join("&");
+    if rest.is_empty() { rest } else { format!("?{rest}") }
+}
+
+/// One render of a variant at a time, concurrent requests for it wait and find it in the cache.
+#[derive(Default)]
+struct RenderLocks {
+    locks: Mutex<HashMap<String, Weak<AsyncMutex<()>>>>,
+}
+
+impl RenderLocks {
+    async fn lock(&self, key: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+            // variants without a pending render have no strong reference left
+            locks.retain(|_, lock| lock.strong_count() > 0);
+            match locks.get(key).and_then(Weak::upgrade) {
+                Some(lock) => lock,
+                None => {
+                    let lock = Arc::new(AsyncMutex::new(()));
+                    locks.insert(key.to_string(), Arc::downgrade(&lock));
+                    lock
+                }
+            }
+        };
+        lock.lock_owned().await
+    }
+}
 
-fn handler_get(Path(id): Path<usize>, State(state): State<LState>, req: Request) -> impl Future<Output = Response> {
-    let q = if let Some(q) = req.uri().query() { format!("?{q}") } else { "".to_string() };
+async fn variant_bytes(id: usize, variant: ImageVariant, state: &LState, query: Option<&str>) -> Result<Option<Vec<u8>>> {
+    // the key has the normalized variant only, other query parameters do not make new files
+    let key = format!("{id}/{}", variant.key());
 
-    let base_map_to_path = "http://catalog-api";
-    let base = state.url_map_service.get_mapped_url(base_map_to_path).unwrap_or(base_map_to_path);
+    if let Some(bytes) = state.cache.get(&key).await {
+        return Ok(Some(bytes));
+    }
+    let _render = state.renders.lock(&key).await;
+    if let Some(bytes) = state.cache.get(&key).await {
+        return Ok(Some(bytes));
+    }
+
+    let query = source_query(query);
+
+    let Some(source) = state.source.fetch(id, &query).await? else {
+        return Ok(None);
+    };
+    let bytes = if variant.is_original() { source } else { tokio::task::spawn_blocking(move || variant.render(&source)).await?? };
+
+    state.cache.put(&key, &bytes).await;
+    Ok(Some(bytes))
+}
 
-    //info!("Forwarding request to {base}");
+fn respond(bytes: Vec<u8>, headers: &HeaderMap, max_age: u64) -> Response {
+    let etag = etag(&bytes);
+    let cache_control = format!("public, max-age={max_age}");
 
-    let ext = format!("/api/catalog/items/{}/pic", id);
-    let uri = format!("{base}{ext}{q}");
+    if headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()).is_some_and(|v| v.split(',').any(|v| v.trim() == etag)) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag), (header::CACHE_CONTROL, cache_control)]).into_response();
+    }
 
-    async move {
-        super::forward(&uri, req, state.client).await.unwrap_or_else(|err| {
-            error!("Error forwarding request: {}", err);
+    let content_type = sniff_content_type(&bytes).unwrap_or("application/octet-stream");
+    ([(header::CONTENT_TYPE, HeaderValue::from_static(content_type)), (header::ETAG, HeaderValue::from_str(&etag).unwrap()), (header::CACHE_CONTROL, HeaderValue::from_str(&cache_control).unwrap())], bytes).into_response()
+}
+
+/// `/product-images/{id}?w=&h=&format=webp|avif`, without parameters the original picture.
+async fn handler_get(Path(id): Path<usize>, Query(variant): Query<ImageVariant>, State(state): State<LState>, req: Request) -> Response {
+    match variant_bytes(id, variant.normalized(), &state, req.uri().query()).await {
+        Ok(Some(bytes)) => respond(bytes, req.headers(), state.cache.ttl().as_secs()),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(err) => {
+            error!("Error serving product image {id}: {}", err);
             (StatusCode::INTERNAL_SERVER_ERROR).into_response()
-        })
+        }
     }
 }
 
 #[derive(Clone)]
 struct LState {
-    client: Client,
-    url_map_service: UrlMapService,
+    source: ImageSource,
+    cache: Arc<ImageCache>,
+    renders: Arc<RenderLocks>,
 }
 
-pub fn router(url_map_service: UrlMapService) -> axum::Router {
-    let state = LState { client: Client::new(), url_map_service };
+/// Responses may be cached by the browser as long as they stay in the disk cache.
+pub fn router(source: ImageSource, cache: ImageCache) -> axum::Router {
+    let state = LState { source, cache: Arc::new(cache), renders: Arc::default() };
 
     let app = axum::Router::new().route("/product-images/{id}", get(handler_get));
     app.with_state(state)
 }
+
+pub fn catalog_api_source(url_map_service: UrlMapService) -> ImageSource {
+    ImageSource::CatalogApi { client: Client::new(), url_map_service }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_query() {
+        assert_eq!(source_query(None), "");
+        assert_eq!(source_query(Some("w=320&format=avif")), "");
+        assert_eq!(source_query(Some("api-version=1.0&w=320&h=200")), "?api-version=1.0");
+        assert_eq!(source_query(Some("w=320&cachebuster=1&api-version=1.0")), "?api-version=1.0");
+    }
+
+    #[tokio::test]
+    async fn test_one_render_per_variant() {
+        use std::time::Duration;
+
+        let renders = RenderLocks::default();
+        let render = renders.lock("1/320x.webp").await;
+
+        assert!(tokio::time::timeout(Duration::from_millis(20), renders.lock("1/320x.webp")).await.is_err());
+        assert!(tokio::time::timeout(Duration::from_millis(20), renders.lock("1/640x.webp")).await.is_ok());
+
+        drop(render);
+        assert!(tokio::time::timeout(Duration::from_millis(20), renders.lock("1/320x.webp")).await.is_ok());
+    }
+}
@@ -0,0 +1,220 @@
+//! Route table of the reverse proxy: path prefixes of the web app forwarded to backend services
+//! resolved through `url_mapper`.
+
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, Method, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::any;
+use log::{error, info};
+
+use super::{Client, ForwardOptions};
+use crate::url_mapper::UrlMapService;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProxyRoute {
+    /// Path prefix of the web app, e.g. `/proxy/catalog`.
+    pub prefix: String,
+    /// `url_mapper` key of the backend, e.g. `http://catalog-api`.
+    pub service_key: String,
+    /// Path on the backend that replaces `prefix`, e.g. `/api/catalog`.
+    pub path: String,
+    /// Sends the access token of the signed in user as `Authorization: Bearer`. Only safe methods are forwarded, a
+    /// cross-site form could otherwise post with the session cookie and get the token added.
+    pub bearer: bool,
+}
+
+impl std::str::FromStr for ProxyRoute {
+    type Err = anyhow::Error;
+
+    /// `<prefix> <service url>[/path] [bearer]`, e.g. `/proxy/orders http://ordering-api/api/orders bearer`.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.split_whitespace();
+        let (Some(prefix), Some(target)) = (parts.next(), parts.next()) else {
+            return Err(anyhow!("proxy route \"{s}\": expected <prefix> <service url> [bearer]"));
+        };
+        let bearer = match parts.next() {
+            None => false,
+            Some("bearer") => true,
+            Some(flag) => return Err(anyhow!("proxy route \"{s}\": unknown flag {flag}")),
+        };
+
+        if !prefix.starts_with('/') {
+            return Err(anyhow!("proxy route \"{s}\": prefix must start with /"));
+        }
+        let url = url::Url::parse(target).map_err(|e| anyhow!("proxy route \"{s}\": {e}"))?;
+        let host = url.host_str().ok_or_else(|| anyhow!("proxy route \"{s}\": no host in {target}"))?;
+        let service_key = match url.port() {
+            Some(port) => format!("{}://{host}:{port}", url.scheme()),
+            None => format!("{}://{host}", url.scheme()),
+        };
+
+        Ok(ProxyRoute {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            service_key,
+            path: url.path().trim_end_matches('/').to_string(),
+            bearer,
+        })
+    }
+}
+
+pub struct ProxyConfig {
+    pub routes: Vec<ProxyRoute>,
+    pub max_request_bytes: u64,
+    pub max_response_bytes: u64,
+}
+
+impl ProxyConfig {
+    /// Reads the routes from `PROXY_ROUTES`, separated by `,`, and the limits from
+    /// `PROXY_MAX_REQUEST_BYTES` and `PROXY_MAX_RESPONSE_BYTES`.
+    pub fn from_env() -> Result<Self> {
+        let routes = std::env::var("PROXY_ROUTES").unwrap_or_default().split(',').map(str::trim).filter(|r| !r.is_empty()).map(str::parse).collect::<Result<Vec<ProxyRoute>>>()?;
+
+        let limit_from_env = |name: &str, default: u64| -> Result<u64> {
+            match std::env::var(name) {
+                Ok(v) => v.trim().parse::<u64>().map_err(|e| anyhow!("{name}: {e}")),
+                Err(_) => Ok(default),
+            }
+        };
+
+        let default = ForwardOptions::default();
+        Ok(Self {
+            routes,
+            max_request_bytes: limit_from_env("PROXY_MAX_REQUEST_BYTES", default.max_request_bytes)?,
+            max_response_bytes: limit_from_env("PROXY_MAX_RESPONSE_BYTES", default.max_response_bytes)?,
+        })
+    }
+}
+
+#[derive(Clone)]
+struct RouteState {
+    route: Arc<ProxyRoute>,
+    client: Client,
+    url_map_service: UrlMapService,
+    options: ForwardOptions,
+}
+
+/// A `.` or `..` segment, also percent-encoded. The URL parser resolves them and the request would leave the path of
+/// the route, with the token of the user on a bearer route.
+fn has_dot_segment(path: &str) -> bool {
+    path.split('/').any(|segment| matches!(segment.to_ascii_lowercase().replace("%2e", ".").as_str(), "." | ".."))
+}
+
+impl RouteState {
+    /// `None` for a path that would leave `route.path` on the backend.
+    fn upstream_url(&self, req: &Request) -> Option<String> {
+        let route = &self.route;
+        let base = self.url_map_service.get_mapped_url(&route.service_key).unwrap_or(&route.service_key);
+        let rest = req.uri().path().strip_prefix(route.prefix.as_str()).unwrap_or_default();
+        if has_dot_segment(rest) {
+            return None;
+        }
+        let query = req.uri().query().map(|q| format!("?{q}")).unwrap_or_default();
+        Some(format!("{}{}{rest}{query}", base.trim_end_matches('/'), route.path))
+    }
+}
+
+async fn handler(State(state): State<RouteState>, req: Request) -> Response {
+    let Some(url) = state.upstream_url(&req) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    let mut options = state.options.clone();
+    if state.route.bearer {
+        if !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+            return (StatusCode::METHOD_NOT_ALLOWED, [(header::ALLOW, HeaderValue::from_static("GET, HEAD, OPTIONS"))]).into_response();
+        }
+        // anonymous requests go without a token, the backend decides
+        options.bearer_token = auth::server::get_user_ref_from_extensions(req.extensions()).ok().map(|user| user.access_token.clone());
+    }
+
+    super::forward(&url, req, state.client, &options).await.unwrap_or_else(|err| {
+        error!("Error forwarding request: {}", err);
+        (StatusCode::BAD_GATEWAY).into_response()
+    })
+}
+
+/// One route for each prefix and everything below it. Routes with `bearer` need the auth layer on top.
+pub fn router(config: ProxyConfig, url_map_service: UrlMapService) -> axum::Router {
+    let client = Client::new();
+    let options = ForwardOptions {
+        bearer_token: None,
+        max_request_bytes: config.max_request_bytes,
+        max_response_bytes: config.max_response_bytes,
+    };
+
+    config.routes.into_iter().fold(axum::Router::new(), |app, route| {
+        info!("proxy {} -> {}{}", route.prefix, route.service_key, route.path);
+
+        let prefix = route.prefix.clone();
+        let state = RouteState {
+            route: Arc::new(route),
+            client: client.clone(),
+            url_map_service: url_map_service.clone(),
+            options: options.clone(),
+        };
+        app.route(&prefix, any(handler).with_state(state.clone())).route(&format!("{prefix}/{{*rest}}"), any(handler).with_state(state))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_map_route() {
+        let route = "/proxy/orders/ http://ordering-api/api/orders bearer".parse::<ProxyRoute>().unwrap();
+        assert_eq!(
+            route,
+            ProxyRoute {
+                prefix: "/proxy/orders".to_string(),
+                service_key: "http://ordering-api".to_string(),
+                path: "/api/orders".to_string(),
+                bearer: true,
+            }
+        );
+
+        assert!("proxy http://catalog-api".parse::<ProxyRoute>().is_err());
+        assert!("/proxy http://catalog-api admin".parse::<ProxyRoute>().is_err());
+
+        let state = RouteState {
+            route: Arc::new("/proxy/catalog http://catalog-api/api/catalog".parse().unwrap()),
+            client: Client::new(),
+            url_map_service: url_mapper::from_vec(&vec![("http://catalog-api", "http://localhost:5222/")]),
+            options: ForwardOptions::default(),
+        };
+        let req = Request::builder().uri("/proxy/catalog/items/1/pic?api-version=1.0").body(axum::body::Body::empty()).unwrap();
+        assert_eq!(state.upstream_url(&req).as_deref(), Some("http://localhost:5222/api/catalog/items/1/pic?api-version=1.0"));
+    }
+
+    #[tokio::test]
+    async fn test_dot_segments_rejected() {
+        let state = RouteState {
+            route: Arc::new("/proxy/orders http://ordering-api/api/orders bearer".parse().unwrap()),
+            client: Client::new(),
+            url_map_service: url_mapper::from_vec(&vec![("http://ordering-api", "http://localhost:5102/")]),
+            options: ForwardOptions::default(),
+        };
+        for path in ["/proxy/orders/../../anything", "/proxy/orders/%2e%2e/%2E%2e/anything", "/proxy/orders/.%2E/x", "/proxy/orders/items/./1"] {
+            let req = Request::builder().uri(path).body(axum::body::Body::empty()).unwrap();
+            assert_eq!(handler(State(state.clone()), req).await.status(), StatusCode::BAD_REQUEST, "{path}");
+        }
+
+        let req = Request::builder().uri("/proxy/orders/items/1..2/v.1").body(axum::body::Body::empty()).unwrap();
+        assert_eq!(state.upstream_url(&req).as_deref(), Some("http://localhost:5102/api/orders/items/1..2/v.1"));
+    }
+
+    #[tokio::test]
+    async fn test_bearer_route_safe_methods_only() {
+        let state = RouteState {
+            route: Arc::new("/proxy/orders http://ordering-api/api/orders bearer".parse().unwrap()),
+            client: Client::new(),
+            url_map_service: url_mapper::from_vec(&vec![("http://ordering-api", "http://localhost:5102/")]),
+            options: ForwardOptions::default(),
+        };
+        let req = Request::builder().method("POST").uri("/proxy/orders/cancel").body(axum::body::Body::empty()).unwrap();
+        assert_eq!(handler(State(state), req).await.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -1,6 +1,8 @@
+mod backends;
+// with mock-backends the product images are served from disk and nothing is forwarded
+#[cfg_attr(feature = "mock-backends", allow(dead_code))]
 mod forwarder;
-
-use api_version::versioning;
+mod sessions;
 
 use anyhow::Result;
 
@@ -8,11 +10,10 @@ use auth::{openid_client, users::Backend};
 use log::{error, info};
 use log4rs;
 
-use time::Duration;
 use url::Url;
 
-pub(crate) use catalog::server::*;
 pub(crate) use url_mapper;
+#[cfg(not(feature = "mock-backends"))]
 mod eventbus;
 
 #[tokio::main]
@@ -56,20 +57,35 @@ async fn main_() -> Result<()> {
            ("http://ordering-api", "http://localhost:5224"),
        ];
     */
+    #[cfg(not(feature = "mock-backends"))]
     let eventbus = eventbus::init_eventbus("web_app_rs").await;
 
     let url_mapper = url_mapper::from_env();
 
-    let catalog_service_context = ::catalog::server::make_service(HttpClient::new(), url_mapper.clone(), versioning::QueryStringApiVersion::from((1, 0)))?;
-
-    let basket_service_context = basket_ordering::basket::server::make_service(url_mapper.clone()).await.unwrap();
+    let backends::Backends {
+        catalog_service_context,
+        basket_service_context,
+        ordering_service_context,
+    } = backends::make_backends(url_mapper.clone()).await?;
 
-    let ordering_service_context = basket_ordering::ordering::server::make_service(basket_ordering::ordering::server::HttpClient::new(), url_mapper.clone(), versioning::QueryStringApiVersion::from((1, 0))).await.unwrap();
+    // anonymous users get a basket in their session, merged into the user's basket on sign-in
+    let login_hooks_context = auth::service::LoginHooksContext {
+        hooks: vec![basket_ordering::basket::guest::make_login_hook(&basket_service_context)],
+    };
+    let basket_service_context = basket_ordering::basket::guest::make_service(basket_service_context);
 
     let basket_state_service_context = basket_ordering::basket_state::server::make_service(basket_service_context.clone(), catalog_service_context.clone(), ordering_service_context.clone()).unwrap();
 
     let auth_service_context = auth::server::make_service().unwrap();
 
+    // `APP_DB_PATH` keeps the users (and the sessions unless they have a database of their own) over restarts
+    let db_path = std::env::var("APP_DB_PATH").unwrap_or_else(|_| "web-app.db".to_string());
+    let db = sessions::open_db(&db_path).await?;
+    sqlx::migrate!().run(&db).await?;
+
+    let wishlist_service_context = basket_ordering::wishlist::server::make_service(db.clone(), catalog_service_context.clone(), basket_state_service_context.clone());
+    let addresses_service_context = basket_ordering::addresses::server::make_service(db.clone(), auth_service_context.clone());
+
     let site_url = Url::parse(format!("http://{}", leptos_options.site_addr).as_str()).unwrap();
 
     let http_client = reqwest::ClientBuilder::new()
@@ -82,21 +98,16 @@ async fn main_() -> Result<()> {
 
     let openid_client = openid_client::create_from_env(http_client.clone(), site_url).await.unwrap();
 
-    let db = sqlx::SqlitePool::connect(":memory:").await?;
-    sqlx::migrate!().run(&db).await?;
-
-    let session_store = axum_login::tower_sessions::MemoryStore::default();
-    let session_layer = axum_login::tower_sessions::SessionManagerLayer::new(session_store)
-        .with_secure(false)
-        .with_same_site(axum_login::tower_sessions::cookie::SameSite::Lax) // Ensure we send the cookie from the OAuth redirect.
-        .with_expiry(axum_login::tower_sessions::Expiry::OnInactivity(Duration::days(1)));
+    let session_layer = sessions::make_session_layer(&sessions::SessionConfig::from_env()?, &db).await?;
 
     // Auth service.
     //
     // This combines the session layer with our backend to establish the auth
     // service which will provide the auth session as a request extension.
 
-    let backend = Backend::new(db, openid_client, http_client);
+    let admin_users = std::env::var("APP_ADMIN_USERS").map(|v| v.split(',').map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect()).unwrap_or_default();
+
+    let backend = Backend::new(db, openid_client, http_client).with_admin_users(admin_users);
     let auth_layer = axum_login::AuthManagerLayerBuilder::new(backend, session_layer).build();
 
     // Generate the list of routes in your Leptos App
@@ -112,7 +123,10 @@ async fn main_() -> Result<()> {
                 provide_context(basket_service_context.clone());
                 provide_context(ordering_service_context.clone());
                 provide_context(basket_state_service_context.clone());
+                provide_context(wishlist_service_context.clone());
+                provide_context(addresses_service_context.clone());
                 provide_context(auth_service_context.clone());
+                provide_context(login_hooks_context.clone());
 
                 //provide_context(product_image_url_context.clone());
             },
@@ -122,10 +136,13 @@ async fn main_() -> Result<()> {
             },
         )
         .fallback(leptos_axum::file_and_error_handler(shell))
-        .with_state(leptos_options)
-        .layer(auth_layer);
+        .with_state(leptos_options);
+
+    // the proxy routes may send the access token of the session, so they go under the auth layer too
+    let proxy_router = forwarder::routes::router(forwarder::routes::ProxyConfig::from_env()?, url_mapper.clone());
+    let app = Router::new().merge(proxy_router).merge(app).layer(auth_layer).layer(basket_ordering::basket_state::GuestBasketLockLayer::new(sessions::SESSION_COOKIE));
 
-    let forwarder_router = forwarder::product_images::router(url_mapper.clone());
+    let forwarder_router = backends::product_images_router(url_mapper.clone()).await?;
     let app: Router = Router::new().merge(app).merge(forwarder_router);
 
     // run our app with hyper
@@ -135,6 +152,7 @@ async fn main_() -> Result<()> {
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     let _ = axum::serve(listener, app.into_make_service()).await?;
 
+    #[cfg(not(feature = "mock-backends"))]
     let _ = eventbus.stop().await?;
     Ok(())
 }
@@ -0,0 +1,268 @@
+//! Sessions and their cookie. The sessions go into a SQLite table by default so that a restart doesn't sign everybody
+//! out, the `MemoryStore` is left for tests and throwaway runs.
+
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use axum_login::tower_sessions::{
+    Expiry, MemoryStore, SessionManagerLayer,
+    cookie::SameSite,
+    session::{Id, Record},
+    session_store::{self, ExpiredDeletion, SessionStore},
+};
+use log::warn;
+use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
+use time::{Duration, OffsetDateTime};
+
+/// Name of the session cookie.
+pub const SESSION_COOKIE: &str = "id";
+
+/// Opens the SQLite database at `path`, `:memory:` for one that goes with the process; a missing file is created.
+pub async fn open_db(path: &str) -> Result<SqlitePool> {
+    let options = SqliteConnectOptions::from_str(path)?.create_if_missing(true);
+    Ok(SqlitePool::connect_with(options).await?)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionStoreKind {
+    Memory,
+    Sqlite,
+}
+
+pub struct SessionConfig {
+    pub store: SessionStoreKind,
+    /// Database of the sessions, `None` keeps them in the web app database.
+    pub db_path: Option<String>,
+    /// Period of the removal of the expired sessions.
+    pub cleanup_interval: std::time::Duration,
+    pub cookie_secure: bool,
+    /// `Lax` lets the cookie come along with the redirect back from the identity server.
+    pub cookie_same_site: SameSite,
+    /// Inactivity after which the session ends, `None` ends it with the browser session.
+    pub inactivity: Option<Duration>,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            store: SessionStoreKind::Sqlite,
+            db_path: None,
+            cleanup_interval: std::time::Duration::from_secs(60 * 60),
+            cookie_secure: true,
+            cookie_same_site: SameSite::Lax,
+            inactivity: Some(Duration::days(1)),
+        }
+    }
+}
+
+impl SessionConfig {
+    /// Reads `APP_SESSION_STORE` (`sqlite` or `memory`), `APP_SESSION_DB_PATH`, `APP_SESSION_CLEANUP_INTERVAL` and
+    /// `APP_SESSION_INACTIVITY` (seconds, 0 for a browser session cookie), `APP_COOKIE_SECURE` and
+    /// `APP_COOKIE_SAME_SITE` (`lax`, `strict` or `none`); unset variables keep the default.
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().map(|v| v.trim().to_lowercase()).filter(|v| !v.is_empty());
+        let u64_from_env = |name: &str| -> Result<Option<u64>> { var(name).map(|v| v.parse::<u64>().map_err(|e| anyhow!("{name}: {e}"))).transpose() };
+
+        let default = Self::default();
+        let config = Self {
+            store: match var("APP_SESSION_STORE").as_deref() {
+                None | Some("sqlite") => SessionStoreKind::Sqlite,
+                Some("memory") => SessionStoreKind::Memory,
+                Some(v) => return Err(anyhow!("APP_SESSION_STORE: unknown store {v}")),
+            },
+            db_path: std::env::var("APP_SESSION_DB_PATH").ok().filter(|v| !v.trim().is_empty()),
+            cleanup_interval: u64_from_env("APP_SESSION_CLEANUP_INTERVAL")?.filter(|secs| *secs > 0).map(std::time::Duration::from_secs).unwrap_or(default.cleanup_interval),
+            cookie_secure: var("APP_COOKIE_SECURE").map(|v| v.parse::<bool>().map_err(|e| anyhow!("APP_COOKIE_SECURE: {e}"))).transpose()?.unwrap_or(default.cookie_secure),
+            cookie_same_site: match var("APP_COOKIE_SAME_SITE").as_deref() {
+                None => default.cookie_same_site,
+                Some("lax") => SameSite::Lax,
+                Some("strict") => SameSite::Strict,
+                Some("none") => SameSite::None,
+                Some(v) => return Err(anyhow!("APP_COOKIE_SAME_SITE: unknown value {v}")),
+            },
+            inactivity: match u64_from_env("APP_SESSION_INACTIVITY")? {
+                None => default.inactivity,
+                Some(0) => None,
+                Some(secs) => Some(Duration::seconds(i64::try_from(secs)?)),
+            },
+        };
+        // browsers drop a `SameSite=None` cookie without `Secure`
+        if config.cookie_same_site == SameSite::None && !config.cookie_secure {
+            return Err(anyhow!("APP_COOKIE_SAME_SITE=none needs APP_COOKIE_SECURE=true"));
+        }
+        Ok(config)
+    }
+}
+
+/// The sessions of `SqliteSessionStore::migrate`, a record is the JSON of the tower-sessions `Record`.
+#[derive(Clone, Debug)]
+pub struct SqliteSessionStore {
+    db: SqlitePool,
+}
+
+fn backend_err(e: impl std::fmt::Display) -> session_store::Error {
+    session_store::Error::Backend(e.to_string())
+}
+
+impl SqliteSessionStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// The table belongs to the store and not to the web app migrations, the sessions may have a database of their own.
+    pub async fn migrate(&self) -> Result<()> {
+        sqlx::query("create table if not exists sessions (id text primary key not null, data text not null, expiry_date integer not null)").execute(&self.db).await?;
+        sqlx::query("create index if not exists sessions_expiry_date on sessions (expiry_date)").execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn insert(&self, record: &Record, replace: bool) -> session_store::Result<bool> {
+        let sql = match replace {
+            true => "insert into sessions (id, data, expiry_date) values (?, ?, ?) on conflict(id) do update set data = excluded.data, expiry_date = excluded.expiry_date",
+            false => "insert into sessions (id, data, expiry_date) values (?, ?, ?) on conflict(id) do nothing",
+        };
+        let data = serde_json::to_string(record).map_err(|e| session_store::Error::Encode(e.to_string()))?;
+        let rslt = sqlx::query(sql).bind(record.id.to_string()).bind(data).bind(record.expiry_date.unix_timestamp()).execute(&self.db).await.map_err(backend_err)?;
+        Ok(rslt.rows_affected() > 0)
+    }
+}
+
+#[async_trait]
+impl SessionStore for SqliteSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        // another session got the id, the record takes a new one
+        while !self.insert(record, false).await? {
+            record.id = Id::default();
+        }
+        Ok(())
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        self.insert(record, true).await.map(|_| ())
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        let data: Option<(String,)> = sqlx::query_as("select data from sessions where id = ? and expiry_date > ?").bind(session_id.to_string()).bind(OffsetDateTime::now_utc().unix_timestamp()).fetch_optional(&self.db).await.map_err(backend_err)?;
+        data.map(|(data,)| serde_json::from_str(&data).map_err(|e| session_store::Error::Decode(e.to_string()))).transpose()
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        sqlx::query("delete from sessions where id = ?").bind(session_id.to_string()).execute(&self.db).await.map_err(backend_err)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExpiredDeletion for SqliteSessionStore {
+    async fn delete_expired(&self) -> session_store::Result<()> {
+        sqlx::query("delete from sessions where expiry_date <= ?").bind(OffsetDateTime::now_utc().unix_timestamp()).execute(&self.db).await.map_err(backend_err)?;
+        Ok(())
+    }
+}
+
+/// The store picked by `SessionConfig::store`, one type for the session layer either way.
+#[derive(Clone, Debug)]
+pub enum AppSessionStore {
+    Memory(MemoryStore),
+    Sqlite(SqliteSessionStore),
+}
+
+#[async_trait]
+impl SessionStore for AppSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        match self {
+            AppSessionStore::Memory(store) => store.create(record).await,
+            AppSessionStore::Sqlite(store) => store.create(record).await,
+        }
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        match self {
+            AppSessionStore::Memory(store) => store.save(record).await,
+            AppSessionStore::Sqlite(store) => store.save(record).await,
+        }
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        match self {
+            AppSessionStore::Memory(store) => store.load(session_id).await,
+            AppSessionStore::Sqlite(store) => store.load(session_id).await,
+        }
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        match self {
+            AppSessionStore::Memory(store) => store.delete(session_id).await,
+            AppSessionStore::Sqlite(store) => store.delete(session_id).await,
+        }
+    }
+}
+
+/// The session layer of `config`; a SQLite store gets its table and a task removing the expired sessions.
+pub async fn make_session_layer(config: &SessionConfig, app_db: &SqlitePool) -> Result<SessionManagerLayer<AppSessionStore>> {
+    let store = match config.store {
+        SessionStoreKind::Memory => AppSessionStore::Memory(MemoryStore::default()),
+        SessionStoreKind::Sqlite => {
+            let db = match &config.db_path {
+                Some(path) => open_db(path).await?,
+                None => app_db.clone(),
+            };
+            let store = SqliteSessionStore::new(db);
+            store.migrate().await?;
+
+            let cleanup = store.clone();
+            let period = config.cleanup_interval;
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(period);
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = cleanup.delete_expired().await {
+                        warn!("removing the expired sessions failed {e}");
+                    }
+                }
+            });
+            AppSessionStore::Sqlite(store)
+        }
+    };
+
+    Ok(SessionManagerLayer::new(store).with_name(SESSION_COOKIE).with_secure(config.cookie_secure).with_same_site(config.cookie_same_site).with_expiry(match config.inactivity {
+        Some(inactivity) => Expiry::OnInactivity(inactivity),
+        None => Expiry::OnSessionEnd,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sqlite_session_store() {
+        let store = SqliteSessionStore::new(open_db(":memory:").await.unwrap());
+        store.migrate().await.unwrap();
+
+        let mut record = Record {
+            id: Id::default(),
+            data: [("user".to_string(), serde_json::json!("alice"))].into_iter().collect(),
+            expiry_date: OffsetDateTime::now_utc() + Duration::hours(1),
+        };
+        store.create(&mut record).await.unwrap();
+        assert_eq!(store.load(&record.id).await.unwrap(), Some(record.clone()));
+
+        // a taken id gets replaced on create
+        let mut duplicate = record.clone();
+        store.create(&mut duplicate).await.unwrap();
+        assert_ne!(duplicate.id, record.id);
+
+        let expired = Record {
+            expiry_date: OffsetDateTime::now_utc() - Duration::seconds(1),
+            ..record.clone()
+        };
+        store.save(&expired).await.unwrap();
+        assert_eq!(store.load(&record.id).await.unwrap(), None);
+
+        store.delete_expired().await.unwrap();
+        let count: (i64,) = sqlx::query_as("select count(*) from sessions").fetch_one(&store.db).await.unwrap();
+        assert_eq!(count.0, 1);
+    }
+}